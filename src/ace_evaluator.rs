@@ -1,4 +1,4 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 use indexmap::IndexMap;
 use pyo3::pyfunction;
@@ -15,7 +15,10 @@ use crate::{
         PossibleAnswerIrrelevantHygienic, PossibleAnswerNormalHygienic,
         PossibleAnswerPointingOutHygienic,
     },
-    parse_ast::{decode_tool_call_format, parse_from_ast_to_structured, parse_from_string_to_ast},
+    parse_ast::{
+        decode_function_list, decode_function_list_with_fc_mode, decode_tool_call_format,
+        extract_outermost_bracket_content, parse_from_ast_to_structured, parse_from_string_to_ast,
+    },
     paths::{BASE_DATASET_PATH, BASE_OUTPUT_PATH, BASE_SCORE_PATH},
     perturbations::PerturbationType,
     utils::{load_json_lines, write_json_lines_to_file},
@@ -51,6 +54,9 @@ pub struct NormalEvaluationResult {
     pub valid: bool,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub error: Option<String>,
+    // short machine-readable failure-mode tag (e.g. "wrong_function_name"), absent when valid
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error_type: Option<String>,
     pub model_raw_output: String,
     pub possible_answer: Vec<FunctionCallHygienic>,
 }
@@ -61,15 +67,34 @@ pub struct SpecialEvaluationResult {
     pub valid: bool,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub error: Option<String>,
+    // short machine-readable failure-mode tag (e.g. "should_have_refused"), absent when valid
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error_type: Option<String>,
     pub model_raw_output: String,
 }
 
+/// Error returned by the special-evaluation helpers: a human-readable message plus a
+/// short machine-readable `error_type` tag so callers can aggregate dominant failure
+/// modes across a dataset (see [`SpecialEvaluationResult::error_type`]).
+pub struct SpecialEvalError {
+    pub message: String,
+    pub error_type: String,
+}
+
 #[derive(Serialize, Deserialize, Clone)]
 pub struct AgentEvaluationResult {
     pub id: String,
     pub valid: bool,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub error: Option<String>,
+    /// Every discrepancy between `final_world_state` and `expected_world_state`
+    /// (see [`WorldState::diff`]), so a reader can see everything the agent got wrong
+    /// without re-running the comparison by hand; empty when the world states match.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub world_state_diff: Vec<String>,
+    // mirrors the `model_raw_output` field on NormalEvaluationResult/SpecialEvaluationResult
+    // so tooling can read the raw model output uniformly across evaluation types
+    pub model_raw_output: String,
     pub conversation: String,
     pub final_world_state: WorldState,
     pub expected_world_state: WorldState,
@@ -83,6 +108,10 @@ pub struct EvaluationSummary {
     pub accuracy: f64,
     pub correct_count: usize,
     pub total_count: usize,
+    // number of failed entries per error_type tag, so a failing run's dominant failure
+    // mode (wrong function name vs. wrong value vs. missing/extra args) is visible without
+    // grepping every per-entry record
+    pub error_type_counts: HashMap<String, usize>,
 }
 
 #[pyfunction]
@@ -92,92 +121,441 @@ pub fn evaluate_all_results(model_name: String, enable_fc: bool) {
     } else {
         model_name.replace("/", "-")
     };
+    let mut dataset_scores: Vec<(&'static str, f64, usize, usize)> = Vec::new();
+    for perturbation_type in PerturbationType::all_perturbations() {
+        for (dataset_name, dataset_trait) in DATASETS.iter() {
+            if let Some((accuracy, correct_count, total_count)) = evaluate_one_dataset(
+                &model_safe_name,
+                dataset_name,
+                dataset_trait,
+                perturbation_type,
+                enable_fc,
+            ) {
+                dataset_scores.push((
+                    dataset_family(&dataset_trait.evaluation_type),
+                    accuracy,
+                    correct_count,
+                    total_count,
+                ));
+            }
+        }
+    }
+    write_overall_summary(&model_safe_name, &dataset_scores);
+}
+
+/// Buckets a dataset's evaluation type into the coarse family used by the overall
+/// report, so a model's score can be compared family-by-family (agent/atom/multi_turn/
+/// special) instead of only dataset-by-dataset.
+fn dataset_family(evaluation_type: &EvaluationType) -> &'static str {
+    match evaluation_type {
+        EvaluationType::Agent => "agent",
+        EvaluationType::NormalMultiTurn => "multi_turn",
+        EvaluationType::SpecialIncomplete
+        | EvaluationType::SpecialErrorParam
+        | EvaluationType::SpecialIrrelevant => "special",
+        EvaluationType::NormalSingleTurn => "atom",
+    }
+}
+
+/// Rolls up every (dataset, perturbation) accuracy scored this run into a single
+/// `overall_summary.json` under `BASE_SCORE_PATH/<model>/`: total correct/total counts,
+/// the macro-average accuracy across datasets, and per-family sub-averages. Lets someone
+/// comparing models look at one number instead of opening every `*_evaluation.json` file.
+fn write_overall_summary(model_safe_name: &str, dataset_scores: &[(&'static str, f64, usize, usize)]) {
+    if dataset_scores.is_empty() {
+        eprintln!("No dataset scores found for model '{}', skipping overall summary.", model_safe_name);
+        return;
+    }
+    let total_correct: usize = dataset_scores.iter().map(|(_, _, correct, _)| *correct).sum();
+    let total_count: usize = dataset_scores.iter().map(|(_, _, _, total)| *total).sum();
+    let macro_average_accuracy =
+        dataset_scores.iter().map(|(_, accuracy, _, _)| *accuracy).sum::<f64>() / dataset_scores.len() as f64;
+
+    let mut family_accuracies: HashMap<&'static str, Vec<f64>> = HashMap::new();
+    for (family, accuracy, _, _) in dataset_scores {
+        family_accuracies.entry(family).or_default().push(*accuracy);
+    }
+    let family_averages: HashMap<String, f64> = family_accuracies
+        .iter()
+        .map(|(family, accuracies)| {
+            (
+                family.to_string(),
+                accuracies.iter().sum::<f64>() / accuracies.len() as f64,
+            )
+        })
+        .collect();
+
+    let overall_summary = json!({
+        "total_correct": total_correct,
+        "total_count": total_count,
+        "macro_average_accuracy": macro_average_accuracy,
+        "family_averages": family_averages,
+    });
+
+    let output_path = BASE_SCORE_PATH.join(model_safe_name).join("overall_summary.json");
+    std::fs::create_dir_all(output_path.parent().unwrap())
+        .expect("Failed to create directories for overall summary output");
+    write_json_lines_to_file(output_path, &vec![overall_summary])
+        .expect("Failed to write overall summary");
+}
+
+/// Scores a single dataset for a single model, reusing the same per-type dispatch
+/// as [`evaluate_all_results`]; lets a caller re-run evaluation on just one dataset
+/// (e.g. while debugging its scoring) instead of the whole suite.
+#[pyfunction]
+pub fn evaluate_dataset(model_name: String, dataset_name: String, enable_fc: bool) {
+    let model_safe_name = if enable_fc {
+        format!("{}-FC", model_name.replace("/", "-"))
+    } else {
+        model_name.replace("/", "-")
+    };
+    let Some(dataset_trait) = DATASETS.get(&dataset_name) else {
+        panic!(
+            "Unknown dataset '{}'. Valid datasets: {:?}",
+            dataset_name,
+            DATASETS.keys().collect::<Vec<_>>()
+        );
+    };
+    for perturbation_type in PerturbationType::all_perturbations() {
+        evaluate_one_dataset(
+            &model_safe_name,
+            &dataset_name,
+            dataset_trait,
+            perturbation_type,
+            enable_fc,
+        );
+    }
+}
+
+/// Scores one dataset under one perturbation type and writes its evaluation file;
+/// shared by [`evaluate_all_results`] and [`evaluate_dataset`].
+fn evaluate_one_dataset(
+    model_safe_name: &str,
+    dataset_name: &str,
+    dataset_trait: &crate::ace_generator::DatasetTrait,
+    perturbation_type: PerturbationType,
+    enable_fc: bool,
+) -> Option<(f64, usize, usize)> {
+    let perturbation_folder_name = perturbation_type.to_folder_name();
+    let problem_folder_path = match perturbation_type {
+        PerturbationType::NoPerturbation | PerturbationType::Transition => {
+            BASE_DATASET_PATH.join("original_modified") // original dataset
+        }
+        _ => BASE_DATASET_PATH.join(perturbation_folder_name.clone()),
+    };
+    let problem_path = problem_folder_path.join(dataset_name.to_string() + ".json");
+    let possible_answer_path = problem_folder_path
+        .join("possible_answer_hygienic")
+        .join(dataset_name.to_string() + ".json");
+
+    let result_path = BASE_OUTPUT_PATH
+        .join(model_safe_name)
+        .join(perturbation_folder_name.clone())
+        .join(dataset_name.to_string() + "_result.json");
+
+    // Skip if result file doesn't exist
+    if !result_path.exists() {
+        eprintln!("Result file not found: {:?}, skipping...", result_path);
+        return None;
+    }
+
+    let problem_entries = load_json_lines(&problem_path).expect("Failed to read problem file");
+    let result_entries = load_json_lines(&result_path).expect("Failed to read result file");
+    let possible_answer_entries =
+        load_json_lines(&possible_answer_path).expect("Failed to read possible answer file");
+
+    let evaluation_results: Vec<serde_json::Value> = match dataset_trait.evaluation_type {
+        EvaluationType::NormalSingleTurn => evaluate_normal_single_turn(
+            &result_entries,
+            &problem_entries,
+            &possible_answer_entries,
+            enable_fc,
+        ),
+        EvaluationType::NormalMultiTurn => evaluate_normal_multi_turn(
+            &result_entries,
+            &problem_entries,
+            &possible_answer_entries,
+            enable_fc,
+        ),
+        EvaluationType::SpecialIncomplete
+        | EvaluationType::SpecialErrorParam
+        | EvaluationType::SpecialIrrelevant => evaluate_special(
+            &result_entries,
+            &problem_entries,
+            &possible_answer_entries,
+            &dataset_trait.evaluation_type,
+        ),
+        EvaluationType::Agent => {
+            evaluate_agent(&result_entries, &problem_entries, &possible_answer_entries)
+        }
+    };
+
+    let output_evaluation_path = BASE_SCORE_PATH
+        .join(model_safe_name)
+        .join(perturbation_folder_name.clone())
+        .join(dataset_name.to_string() + "_evaluation.json");
+
+    // Create directories if not exist
+    std::fs::create_dir_all(output_evaluation_path.parent().unwrap())
+        .expect("Failed to create directories for evaluation output");
+    write_json_lines_to_file(output_evaluation_path, &evaluation_results)
+        .expect("Failed to write evaluation results");
+
+    // Print summary
+    let first = evaluation_results.first()?;
+    let accuracy = first.get("accuracy")?.as_f64()?;
+    println!("Dataset: {} | Accuracy: {}", dataset_name, accuracy);
+    let correct_count = first.get("correct_count").and_then(|v| v.as_u64()).unwrap_or(0) as usize;
+    let total_count = first.get("total_count").and_then(|v| v.as_u64()).unwrap_or(0) as usize;
+    Some((accuracy, correct_count, total_count))
+}
+
+const KNOWN_INVOLVED_CLASSES: &[&str] =
+    &["BaseApi", "MessageApi", "ReminderApi", "FoodPlatform", "Travel"];
+
+/// Preflight check for a benchmark run: walks every dataset under every perturbation
+/// folder and tries to parse each problem entry into its `AgentEntry`/`NormalEntry`
+/// shape, checks that agent entries only reference known `involved_classes` and that
+/// `initial_config` deserializes into `WorldState`, and checks that every ground-truth
+/// function name in the possible-answer file is actually offered in the entry's
+/// `function` list. Returns one human-readable message per problem found; an empty
+/// vector means everything parses cleanly. `model_name` is accepted for parity with
+/// `evaluate_all_results`/`evaluate_dataset`, even though dataset integrity doesn't
+/// depend on which model is about to be benchmarked.
+#[pyfunction]
+pub fn validate_datasets(model_name: String) -> Vec<String> {
+    let _ = model_name;
+    let mut errors = Vec::new();
     for perturbation_type in PerturbationType::all_perturbations() {
         let perturbation_folder_name = perturbation_type.to_folder_name();
         for (dataset_name, dataset_trait) in DATASETS.iter() {
-            
             let problem_folder_path = match perturbation_type {
                 PerturbationType::NoPerturbation | PerturbationType::Transition => {
-                    BASE_DATASET_PATH
-                        // .join(model_safe_name.clone())
-                        .join("original_modified") // original dataset
-                        // .join(dataset_name.to_string() + "_result.json")
+                    BASE_DATASET_PATH.join("original_modified")
                 }
-                _ => BASE_DATASET_PATH
-                    // .join(model_safe_name.clone())
-                    .join(perturbation_folder_name.clone())
-                    // .join(dataset_name.to_string() + "_result.json"),
+                _ => BASE_DATASET_PATH.join(perturbation_folder_name.clone()),
             };
-            let problem_path = problem_folder_path
-                .join(dataset_name.to_string() + ".json");
-            let possible_answer_path = problem_folder_path
-                .join("possible_answer_hygienic")
-                .join(dataset_name.to_string() + ".json");
+            let problem_path = problem_folder_path.join(dataset_name.to_string() + ".json");
+            if !problem_path.exists() {
+                continue;
+            }
+            let Ok(problem_entries) = load_json_lines(&problem_path) else {
+                errors.push(format!("{}: failed to read as JSON lines", problem_path.display()));
+                continue;
+            };
+
+            let is_agent = matches!(
+                dataset_trait.problem_type,
+                crate::ace_generator::ProblemType::AgentMultiStep
+                    | crate::ace_generator::ProblemType::AgentMultiTurn
+            );
+            let mut offered_function_names: HashMap<String, HashSet<String>> = HashMap::new();
+            for entry_value in &problem_entries {
+                if is_agent {
+                    let agent_entry: AgentEntry = match serde_json::from_value(entry_value.clone()) {
+                        Ok(entry) => entry,
+                        Err(e) => {
+                            errors.push(format!(
+                                "{}: entry does not match AgentEntry shape: {}",
+                                problem_path.display(), e
+                            ));
+                            continue;
+                        }
+                    };
+                    for class_name in &agent_entry.involved_classes {
+                        if !KNOWN_INVOLVED_CLASSES.contains(&class_name.as_str()) {
+                            errors.push(format!(
+                                "{} (id {}): involved_classes references unknown API '{}'",
+                                problem_path.display(), agent_entry.id, class_name
+                            ));
+                        }
+                    }
+                    let initial_config = Value::Object(agent_entry.initial_config.into_iter().collect());
+                    if let Err(e) = serde_json::from_value::<WorldState>(initial_config) {
+                        errors.push(format!(
+                            "{} (id {}): initial_config does not deserialize into WorldState: {}",
+                            problem_path.display(), agent_entry.id, e
+                        ));
+                    }
+                } else {
+                    let normal_entry: NormalEntry = match serde_json::from_value(entry_value.clone()) {
+                        Ok(entry) => entry,
+                        Err(e) => {
+                            errors.push(format!(
+                                "{}: entry does not match NormalEntry shape: {}",
+                                problem_path.display(), e
+                            ));
+                            continue;
+                        }
+                    };
+                    let names: HashSet<String> = normal_entry.function.iter()
+                        .filter_map(|f| f.get("name").and_then(|v| v.as_str()).map(|s| s.to_string()))
+                        .collect();
+                    offered_function_names.insert(normal_entry.id, names);
+                }
+            }
+
+            if matches!(
+                dataset_trait.evaluation_type,
+                EvaluationType::NormalSingleTurn | EvaluationType::NormalMultiTurn
+            ) {
+                let possible_answer_path = problem_folder_path
+                    .join("possible_answer_hygienic")
+                    .join(dataset_name.to_string() + ".json");
+                if let Ok(possible_answer_entries) = load_json_lines(&possible_answer_path) {
+                    for entry_value in &possible_answer_entries {
+                        let possible_answer: PossibleAnswerNormalHygienic =
+                            match serde_json::from_value(entry_value.clone()) {
+                                Ok(entry) => entry,
+                                Err(e) => {
+                                    errors.push(format!(
+                                        "{}: entry does not match PossibleAnswerNormalHygienic shape: {}",
+                                        possible_answer_path.display(), e
+                                    ));
+                                    continue;
+                                }
+                            };
+                        let Some(offered) = offered_function_names.get(&possible_answer.id) else {
+                            continue;
+                        };
+                        for call in &possible_answer.ground_truth {
+                            if !offered.contains(&call.name) {
+                                errors.push(format!(
+                                    "{} (id {}): ground truth calls '{}', which is not in the problem's function list",
+                                    possible_answer_path.display(), possible_answer.id, call.name
+                                ));
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+    errors
+}
+
+/// Extracts the bare function name from a raw call string such as `"ApiName(key='value')"`.
+fn function_name_from_raw_call(raw_call: &str) -> String {
+    raw_call
+        .split('(')
+        .next()
+        .unwrap_or(raw_call)
+        .trim()
+        .to_string()
+}
+
+/// Scans every dataset/perturbation for a model's run and reports which of the
+/// dispatchable functions offered to the model (from each problem's `function` field)
+/// were never actually called, to help dataset authors spot underused tools.
+/// Writes `coverage.json` ({"called": [...], "never_called": [...]}) under
+/// `BASE_SCORE_PATH/<model>/`.
+#[pyfunction]
+pub fn analyze_function_coverage(model_name: String, enable_fc: bool) {
+    let model_safe_name = if enable_fc {
+        format!("{}-FC", model_name.replace("/", "-"))
+    } else {
+        model_name.replace("/", "-")
+    };
 
+    let mut offered_functions: HashSet<String> = HashSet::new();
+    let mut called_functions: HashSet<String> = HashSet::new();
+
+    for perturbation_type in PerturbationType::all_perturbations() {
+        let perturbation_folder_name = perturbation_type.to_folder_name();
+        for (dataset_name, dataset_trait) in DATASETS.iter() {
+            let problem_folder_path = match perturbation_type {
+                PerturbationType::NoPerturbation | PerturbationType::Transition => {
+                    BASE_DATASET_PATH.join("original_modified")
+                }
+                _ => BASE_DATASET_PATH.join(perturbation_folder_name.clone()),
+            };
+            let problem_path = problem_folder_path.join(dataset_name.to_string() + ".json");
             let result_path = BASE_OUTPUT_PATH
                 .join(model_safe_name.clone())
                 .join(perturbation_folder_name.clone())
                 .join(dataset_name.to_string() + "_result.json");
 
-            
-
-            // Skip if result file doesn't exist
-            if !result_path.exists() {
-                eprintln!("Result file not found: {:?}, skipping...", result_path);
+            if !problem_path.exists() || !result_path.exists() {
                 continue;
             }
+            let Ok(problem_entries) = load_json_lines(&problem_path) else {
+                continue;
+            };
+            let Ok(result_entries) = load_json_lines(&result_path) else {
+                continue;
+            };
 
-            let problem_entries =
-                load_json_lines(&problem_path).expect("Failed to read problem file");
-            let result_entries = load_json_lines(&result_path).expect("Failed to read result file");
-            let possible_answer_entries = load_json_lines(&possible_answer_path)
-                .expect("Failed to read possible answer file");
-
-            let evaluation_results: Vec<serde_json::Value> = match dataset_trait.evaluation_type {
-                EvaluationType::NormalSingleTurn => evaluate_normal_single_turn(
-                    &result_entries,
-                    &problem_entries,
-                    &possible_answer_entries,
-                    enable_fc,
-                ),
-                EvaluationType::NormalMultiTurn => evaluate_normal_multi_turn(
-                    &result_entries,
-                    &problem_entries,
-                    &possible_answer_entries,
-                    enable_fc,
-                ),
-                EvaluationType::SpecialIncomplete
-                | EvaluationType::SpecialErrorParam
-                | EvaluationType::SpecialIrrelevant => evaluate_special(
-                    &result_entries,
-                    &problem_entries,
-                    &possible_answer_entries,
-                    &dataset_trait.evaluation_type,
-                ),
-                EvaluationType::Agent => {
-                    evaluate_agent(&result_entries, &problem_entries, &possible_answer_entries)
+            for entry in &problem_entries {
+                if let Some(functions) = entry.get("function").and_then(|v| v.as_array()) {
+                    for function in functions {
+                        if let Some(name) = function.get("name").and_then(|v| v.as_str()) {
+                            offered_functions.insert(name.to_string());
+                        }
+                    }
                 }
-            };
+            }
 
-            let output_evaluation_path = BASE_SCORE_PATH
-                .join(model_safe_name.clone())
-                .join(perturbation_folder_name.clone())
-                .join(dataset_name.clone() + "_evaluation.json");
-
-            // Create directories if not exist
-            std::fs::create_dir_all(output_evaluation_path.parent().unwrap())
-                .expect("Failed to create directories for evaluation output");
-            write_json_lines_to_file(output_evaluation_path, &evaluation_results)
-                .expect("Failed to write evaluation results");
-
-            // Print summary
-            if let Some(first) = evaluation_results.first() {
-                if let Some(accuracy) = first.get("accuracy") {
-                    println!("Dataset: {} | Accuracy: {}", dataset_name, accuracy);
+            match dataset_trait.evaluation_type {
+                EvaluationType::Agent => {
+                    for entry in &result_entries {
+                        if let Ok(result) =
+                            serde_json::from_value::<AgentResultEntry>(entry.clone())
+                        {
+                            for raw_call in &result.output_function_calls {
+                                called_functions.insert(function_name_from_raw_call(raw_call));
+                            }
+                        }
+                    }
+                }
+                _ => {
+                    for entry in &result_entries {
+                        if let Ok(result) =
+                            serde_json::from_value::<NormalResultEntry>(entry.clone())
+                            && let Ok(calls) =
+                                decode_function_list_with_fc_mode(&result.result, enable_fc)
+                        {
+                            for call in calls {
+                                called_functions.insert(call.name);
+                            }
+                        }
+                    }
                 }
             }
         }
     }
+
+    let (called, never_called) = compute_coverage_lists(&offered_functions, &called_functions);
+
+    let coverage_path = BASE_SCORE_PATH
+        .join(model_safe_name.clone())
+        .join("coverage.json");
+    std::fs::create_dir_all(coverage_path.parent().unwrap())
+        .expect("Failed to create directories for coverage output");
+    write_json_lines_to_file(
+        coverage_path,
+        &vec![json!({
+            "called": called,
+            "never_called": never_called,
+        })],
+    )
+    .expect("Failed to write coverage report");
+}
+
+/// Splits the functions offered across a run into sorted (called, never-called)
+/// lists, factored out of [`analyze_function_coverage`] so the set logic can be
+/// exercised without standing up real dataset/result files on disk.
+fn compute_coverage_lists(
+    offered_functions: &HashSet<String>,
+    called_functions: &HashSet<String>,
+) -> (Vec<String>, Vec<String>) {
+    let mut never_called: Vec<String> = offered_functions
+        .difference(called_functions)
+        .cloned()
+        .collect();
+    never_called.sort();
+    let mut called: Vec<String> = called_functions.iter().cloned().collect();
+    called.sort();
+    (called, never_called)
 }
 
 /// Result of evaluation for a multi-turn entry
@@ -280,6 +658,11 @@ pub fn evaluate_normal_multi_turn(
     let mut correct_count = 0;
     // score_map: turn -> (item_index -> is_valid)
     let mut score_map: IndexMap<usize, IndexMap<usize, bool>> = IndexMap::new();
+    // per_turn_validity: item_index (the position within a conversation, confusingly named
+    // `item` below since it shares a loop with the per-group `turn` id) -> is_valid across
+    // every conversation that reaches that position; lets callers see where accuracy drops
+    // off as a conversation gets longer, distinct from `score_map`'s per-conversation view
+    let mut per_turn_validity: IndexMap<usize, Vec<bool>> = IndexMap::new();
 
     for id in result_entries_parsed.keys() {
         let result_entry = result_entries_parsed.get(id).expect("Missing result entry");
@@ -305,7 +688,7 @@ pub fn evaluate_normal_multi_turn(
             .expect("Failed to parse item index");
 
         let evaluation_result =
-            evaluate_one_normal(&result_entry.result, &possible_answer_entry.ground_truth, enable_fc);
+            evaluate_one_normal(&result_entry.result, &possible_answer_entry.ground_truth, enable_fc, resolve_numeric_tolerance());
         match evaluation_result {
             Ok(_) => {
                 correct_count += 1;
@@ -313,6 +696,7 @@ pub fn evaluate_normal_multi_turn(
                     .entry(turn)
                     .or_insert_with(IndexMap::new)
                     .insert(item, true);
+                per_turn_validity.entry(item).or_default().push(true);
                 results.push(NormalMultiTurnEvaluationResult {
                     id: result_entry.id.clone(),
                     turn,
@@ -327,11 +711,12 @@ pub fn evaluate_normal_multi_turn(
                     .entry(turn)
                     .or_insert_with(IndexMap::new)
                     .insert(item, false);
+                per_turn_validity.entry(item).or_default().push(false);
                 results.push(NormalMultiTurnEvaluationResult {
                     id: result_entry.id.clone(),
                     turn,
                     valid: false,
-                    error: Some(e),
+                    error: Some(e.message),
                     model_raw_output: result_entry.result.clone(),
                     possible_answer: possible_answer_entry.ground_truth.clone(),
                 });
@@ -345,6 +730,13 @@ pub fn evaluate_normal_multi_turn(
     } else {
         multi_turn_accuracy(&score_map)
     };
+    let per_turn_accuracy: HashMap<String, f64> = per_turn_validity
+        .iter()
+        .map(|(turn_index, valid_flags)| {
+            let accuracy = valid_flags.iter().filter(|v| **v).count() as f64 / valid_flags.len() as f64;
+            (turn_index.to_string(), accuracy)
+        })
+        .collect();
 
     // Insert summary at the beginning
     let summary = json!({
@@ -352,6 +744,7 @@ pub fn evaluate_normal_multi_turn(
         "correct_count": correct_count,
         "total_count": total_count,
         "process_accuracy": process_accuracy,
+        "per_turn_accuracy": per_turn_accuracy,
     });
     let results_serialized: Vec<serde_json::Value> = results
         .into_iter()
@@ -387,14 +780,6 @@ pub fn evaluate_normal_single_turn(
             (parsed.id.clone(), parsed)
         })
         .collect();
-    let problem_entries_parsed: IndexMap<String, NormalEntry> = problem_entries
-        .iter()
-        .map(|entry| {
-            let parsed: NormalEntry = serde_json::from_value(entry.clone())
-                .expect("Failed to parse problem entry into NormalEntry");
-            (parsed.id.clone(), parsed)
-        })
-        .collect();
     let possible_answer_entries_parsed: IndexMap<String, PossibleAnswerNormalHygienic> =
         possible_answer_entries
             .iter()
@@ -410,32 +795,36 @@ pub fn evaluate_normal_single_turn(
     let mut results: Vec<NormalEvaluationResult> = Vec::new();
     let total_count = result_len;
     let mut correct_count = 0;
+    let mut error_type_counts: HashMap<String, usize> = HashMap::new();
 
     for id in result_entries_parsed.keys() {
         let result_entry = result_entries_parsed.get(id).expect("Missing result entry");
-        let _problem_entry = problem_entries_parsed
-            .get(id)
-            .expect("Missing problem entry");
+        // the problem entry's `question` field is not consulted during scoring, so it is not
+        // parsed here; this also means entries that omit it (e.g. agent/special datasets
+        // routed through this evaluator) do not cause a spurious deserialization failure
         let possible_answer_entry = possible_answer_entries_parsed
             .get(id)
             .expect("Missing possible answer entry");
 
-        match evaluate_one_normal(&result_entry.result, &possible_answer_entry.ground_truth, enable_fc) {
+        match evaluate_one_normal(&result_entry.result, &possible_answer_entry.ground_truth, enable_fc, resolve_numeric_tolerance()) {
             Ok(_) => {
                 correct_count += 1;
                 results.push(NormalEvaluationResult {
                     id: id.clone(),
                     valid: true,
                     error: None,
+                    error_type: None,
                     model_raw_output: result_entry.result.clone(),
                     possible_answer: possible_answer_entry.ground_truth.clone(),
                 });
             }
             Err(e) => {
+                *error_type_counts.entry(e.error_type.clone()).or_insert(0) += 1;
                 results.push(NormalEvaluationResult {
                     id: id.clone(),
                     valid: false,
-                    error: Some(e),
+                    error: Some(e.message),
+                    error_type: Some(e.error_type),
                     model_raw_output: result_entry.result.clone(),
                     possible_answer: possible_answer_entry.ground_truth.clone(),
                 });
@@ -455,6 +844,7 @@ pub fn evaluate_normal_single_turn(
         "accuracy": accuracy,
         "correct_count": correct_count,
         "total_count": total_count,
+        "error_type_counts": error_type_counts,
     });
     let results_serialized: Vec<serde_json::Value> = results
         .into_iter()
@@ -466,18 +856,36 @@ pub fn evaluate_normal_single_turn(
     final_results
 }
 
+/// Default absolute tolerance [`values_equivalent`] allows between two numeric
+/// parameter values. It only absorbs floating-point noise: a rounded price such as
+/// `88.0` vs `88.00001` is still a mismatch at this default, and needs a looser
+/// tolerance set through [`resolve_numeric_tolerance`].
+pub const DEFAULT_NUMERIC_TOLERANCE: f64 = 1e-9;
+
+/// Resolves the numeric tolerance actually used by the evaluator, reading
+/// `ACEBENCH_NUMERIC_TOLERANCE` (consistent with how [`crate::paths`] resolves its
+/// roots from the environment) and falling back to [`DEFAULT_NUMERIC_TOLERANCE`] when
+/// unset or unparseable. This gives callers a real override instead of a hardcoded
+/// constant baked into every call site.
+pub fn resolve_numeric_tolerance() -> f64 {
+    std::env::var("ACEBENCH_NUMERIC_TOLERANCE")
+        .ok()
+        .and_then(|v| v.parse::<f64>().ok())
+        .unwrap_or(DEFAULT_NUMERIC_TOLERANCE)
+}
+
 pub fn check_functions_all_match(
     model_output_calls: &Vec<FunctionCallHygienic>,
     ground_truth_calls: &Vec<FunctionCallHygienic>,
+    numeric_tolerance: f64,
 ) -> Result<(), String> {
     if model_output_calls.len() != ground_truth_calls.len() {
         return Err("The number of function calls does not match the possible answer.".to_string());
     }
     for ground_truth_call in ground_truth_calls.iter() {
-        let Some(_) = model_output_calls
-            .iter()
-            .find(|&model_output_call| functions_equivalent(ground_truth_call, model_output_call))
-        else {
+        let Some(_) = model_output_calls.iter().find(|&model_output_call| {
+            functions_equivalent(ground_truth_call, model_output_call, numeric_tolerance)
+        }) else {
             return Err(format!(
                 "No matching function call for {} found in model's output function calls.",
                 ground_truth_call.name
@@ -487,35 +895,126 @@ pub fn check_functions_all_match(
     Ok(())
 }
 
+/// Error returned by [`evaluate_one_normal`]: a human-readable message plus a short
+/// machine-readable `error_type` tag (mirrors [`SpecialEvalError`]) so callers can
+/// aggregate dominant failure modes across a dataset.
+pub struct NormalEvalError {
+    pub message: String,
+    pub error_type: String,
+}
+
+/// Looks for a same-named call where an extra parameter the call has (but `expected`
+/// doesn't) carries the exact value `expected` wanted under a different, missing
+/// parameter name, e.g. the model passing `receiver` instead of `receiver_name`. This is
+/// reported as its own failure rather than the separate "extra_args"/"lack_args" it would
+/// otherwise produce, since those two tags alone don't tell a reader the real problem is
+/// a single misnamed parameter rather than an unrelated extra value plus an unrelated gap.
+fn detect_param_aliasing(
+    expected: &FunctionCallHygienic,
+    call: &FunctionCallHygienic,
+    numeric_tolerance: f64,
+) -> Option<(String, String)> {
+    let missing_params = expected.parameters.keys().filter(|k| !call.parameters.contains_key(*k));
+    for missing in missing_params {
+        let expected_value = &expected.parameters[missing];
+        let aliased = call
+            .parameters
+            .iter()
+            .filter(|(k, _)| !expected.parameters.contains_key(*k))
+            .find(|(_, v)| values_equivalent(expected_value, v, numeric_tolerance));
+        if let Some((extra, _)) = aliased {
+            return Some((extra.clone(), missing.clone()));
+        }
+    }
+    None
+}
+
+/// Classifies why no decoded call matched `expected`, for [`NormalEvalError::error_type`]:
+/// no call with the right name at all ("wrong_function_name"), a same-named call where a
+/// value was passed under the wrong parameter name ("param_name_mismatch", with a message
+/// naming both), a same-named call with fewer/more parameters ("lack_args"/"extra_args"),
+/// or a same-named, same-arity call whose values just don't match ("value_error").
+fn classify_normal_mismatch(
+    expected: &FunctionCallHygienic,
+    decoded: &[FunctionCallHygienic],
+    numeric_tolerance: f64,
+) -> (&'static str, Option<String>) {
+    let same_name: Vec<&FunctionCallHygienic> =
+        decoded.iter().filter(|call| call.name == expected.name).collect();
+    if same_name.is_empty() {
+        return ("wrong_function_name", None);
+    }
+    for call in &same_name {
+        if let Some((extra, missing)) = detect_param_aliasing(expected, call, numeric_tolerance) {
+            return (
+                "param_name_mismatch",
+                Some(format!(
+                    "parameter '{}' looks like it was meant to be '{}': the value matches but the parameter name doesn't",
+                    extra, missing
+                )),
+            );
+        }
+    }
+    for call in same_name {
+        if call.parameters.len() < expected.parameters.len() {
+            return ("lack_args", None);
+        }
+        if call.parameters.len() > expected.parameters.len() {
+            return ("extra_args", None);
+        }
+    }
+    ("value_error", None)
+}
+
 pub fn evaluate_one_normal(
     model_result_raw: &str,
     possible_answer_function_calls: &Vec<FunctionCallHygienic>,
     enable_fc: bool,
-) -> Result<(), String> {
+    numeric_tolerance: f64,
+) -> Result<(), NormalEvalError> {
     // Parse function calls based on mode
-    let mut decoded_function_calls = if enable_fc {
+    let parse_result = if enable_fc {
         // FC mode: parse <tool_call> format
-        decode_tool_call_format(model_result_raw)?
+        decode_tool_call_format(model_result_raw)
     } else {
-        // Non-FC mode: parse Python AST format [ApiName(key='value')]
-        let decoded_ast = parse_from_string_to_ast(model_result_raw)?;
-        parse_from_ast_to_structured(&decoded_ast, model_result_raw)?
+        // Non-FC mode: parse Python AST format [ApiName(key='value')], tolerating prose
+        // or markdown fencing a model wrapped the call list in.
+        let isolated =
+            extract_outermost_bracket_content(model_result_raw).unwrap_or(model_result_raw);
+        parse_from_string_to_ast(isolated)
+            .and_then(|decoded_ast| parse_from_ast_to_structured(&decoded_ast, isolated))
     };
+    let mut decoded_function_calls = parse_result.map_err(|message| NormalEvalError {
+        message,
+        error_type: "invalid_format".to_string(),
+    })?;
 
     // check function equivalence
     if decoded_function_calls.len() != possible_answer_function_calls.len() {
-        return Err("The number of function calls does not match the possible answer.".to_string());
+        return Err(NormalEvalError {
+            message: "The number of function calls does not match the possible answer.".to_string(),
+            error_type: "wrong_count".to_string(),
+        });
     }
 
     for possible_answer_function_call in possible_answer_function_calls.iter() {
-        let Some(pos) = decoded_function_calls
-            .iter()
-            .position(|fa| functions_equivalent(&possible_answer_function_call, fa))
-        else {
-            return Err(format!(
-                "No matching function call for {} found in model's output function calls.",
-                possible_answer_function_call.name
-            ));
+        let Some(pos) = decoded_function_calls.iter().position(|fa| {
+            functions_equivalent(possible_answer_function_call, fa, numeric_tolerance)
+        }) else {
+            let (error_type, detail) = classify_normal_mismatch(
+                possible_answer_function_call,
+                &decoded_function_calls,
+                numeric_tolerance,
+            );
+            return Err(NormalEvalError {
+                message: detail.unwrap_or_else(|| {
+                    format!(
+                        "No matching function call for {} found in model's output function calls.",
+                        possible_answer_function_call.name
+                    )
+                }),
+                error_type: error_type.to_string(),
+            });
         };
         // remove the matched one
         decoded_function_calls.swap_remove(pos);
@@ -523,7 +1022,11 @@ pub fn evaluate_one_normal(
     Ok(())
 }
 
-pub fn functions_equivalent(func1: &FunctionCallHygienic, func2: &FunctionCallHygienic) -> bool {
+pub fn functions_equivalent(
+    func1: &FunctionCallHygienic,
+    func2: &FunctionCallHygienic,
+    numeric_tolerance: f64,
+) -> bool {
     if func1.name != func2.name {
         return false;
     }
@@ -534,16 +1037,85 @@ pub fn functions_equivalent(func1: &FunctionCallHygienic, func2: &FunctionCallHy
         let Some(param_value2) = func2.parameters.get(param_name) else {
             return false;
         };
-        if !values_equivalent(param_value1, param_value2) {
+        if !values_equivalent(param_value1, param_value2, numeric_tolerance) {
             return false;
         }
     }
     true
 }
 
-pub fn values_equivalent(value1: &serde_json::Value, value2: &serde_json::Value) -> bool {
-    // todo: special handling for list and dict
-    value1 == value2
+/// How a model's string parameter value is matched against the ground truth.
+/// `Exact` is what [`values_equivalent`] uses for every function-parameter comparison:
+/// `Normal` single/multi-turn evaluation (via [`functions_equivalent`]), where a model
+/// that pads its answer with extra words around the expected value should not be
+/// credited. `Contains` is used by [`evaluate_one_pointing_out`] (`SpecialIncomplete`/
+/// `SpecialErrorParam`), where the model answers in free text and only needs to mention
+/// the flagged API/parameter name or value somewhere in its response.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum MatchMode {
+    Exact,
+    Contains,
+}
+
+fn strings_match(mode: MatchMode, model_value: &str, expected_value: &str) -> bool {
+    let model_value = model_value.trim().to_lowercase();
+    let expected_value = expected_value.trim().to_lowercase();
+    match mode {
+        MatchMode::Exact => model_value == expected_value,
+        MatchMode::Contains => model_value.contains(&expected_value),
+    }
+}
+
+pub fn values_equivalent(
+    value1: &serde_json::Value,
+    value2: &serde_json::Value,
+    numeric_tolerance: f64,
+) -> bool {
+    if value1 == value2 {
+        return true;
+    }
+    match (value1, value2) {
+        // collections of differing shape are never equivalent; otherwise compare
+        // element-wise so a loosely-typed ("any") scalar nested inside still matches
+        (Value::Array(a), Value::Array(b)) => {
+            a.len() == b.len()
+                && a.iter()
+                    .zip(b.iter())
+                    .all(|(x, y)| values_equivalent(x, y, numeric_tolerance))
+        }
+        (Value::Object(a), Value::Object(b)) => {
+            a.len() == b.len()
+                && a.iter().all(|(k, v)| {
+                    b.get(k).is_some_and(|v2| values_equivalent(v, v2, numeric_tolerance))
+                })
+        }
+        (Value::String(model_value), Value::String(expected_value)) => {
+            strings_match(MatchMode::Exact, model_value, expected_value)
+        }
+        // two numbers that aren't byte-for-byte equal may still be within tolerance, e.g.
+        // a price the model rounded converting it to/from a string
+        (Value::Number(a), Value::Number(b)) => match (a.as_f64(), b.as_f64()) {
+            (Some(a), Some(b)) => (a - b).abs() <= numeric_tolerance,
+            _ => standardized_scalar_string(value1) == standardized_scalar_string(value2),
+        },
+        // other scalars that aren't byte-for-byte equal may still be equivalent under an
+        // "any" parameter type, e.g. extra whitespace in a string; fall back to comparing
+        // their standardized form
+        _ => standardized_scalar_string(value1) == standardized_scalar_string(value2),
+    }
+}
+
+/// Loose, type-agnostic string form of a scalar JSON value, used to compare "any"-typed
+/// parameters where the model's and ground truth's JSON representations may not match
+/// exactly (e.g. `3` vs `3.0`, or differing string casing/whitespace).
+fn standardized_scalar_string(value: &serde_json::Value) -> String {
+    match value {
+        Value::String(s) => s.trim().to_lowercase(),
+        Value::Number(n) => n.as_f64().map_or_else(|| n.to_string(), |f| f.to_string()),
+        Value::Bool(b) => b.to_string(),
+        Value::Null => "null".to_string(),
+        other => other.to_string(),
+    }
 }
 
 pub fn evaluate_special(
@@ -601,7 +1173,7 @@ pub fn evaluate_special(
         let possible_answer = possible_answer_entries
             .get(id)
             .expect("Missing possible answer entry");
-        let evaluation_result: Result<(), String> = match evaluation_type {
+        let evaluation_result: Result<(), SpecialEvalError> = match evaluation_type {
             EvaluationType::SpecialIncomplete | EvaluationType::SpecialErrorParam => {
                 evaluate_one_pointing_out(&result_entry.result, possible_answer, evaluation_type)
             }
@@ -617,6 +1189,7 @@ pub fn evaluate_special(
                     id: id.clone(),
                     valid: true,
                     error: None,
+                    error_type: None,
                     model_raw_output: result_entry.result.clone(),
                 });
             }
@@ -624,7 +1197,8 @@ pub fn evaluate_special(
                 results.push(SpecialEvaluationResult {
                     id: id.clone(),
                     valid: false,
-                    error: Some(e),
+                    error: Some(e.message),
+                    error_type: Some(e.error_type),
                     model_raw_output: result_entry.result.clone(),
                 });
             }
@@ -651,32 +1225,134 @@ pub fn evaluate_special(
     final_results
 }
 
+/// Error type tag emitted when the model didn't point out the ground-truth missing
+/// parameters for a `SpecialIncomplete` question (see [`evaluate_one_pointing_out`]).
+const ERROR_TYPE_WRONG_MISSING_PARAMS: &str = "wrong_missing_params";
+/// Error type tag emitted when the model didn't point out the ground-truth incorrect
+/// parameter values for a `SpecialErrorParam` question (see [`evaluate_one_pointing_out`]).
+const ERROR_TYPE_WRONG_POINTING_OUT: &str = "wrong_pointing_out";
+/// Error type tag emitted when the model answered a `SpecialIrrelevant` question instead
+/// of refusing it (see [`evaluate_one_irrelevant`]).
+const ERROR_TYPE_SHOULD_HAVE_REFUSED: &str = "should_have_refused";
+
+/// Lowercases and collapses whitespace, so a canonical phrase is matched regardless of
+/// the model's casing or how it wraps the sentence across lines. When `normalize_fullwidth`
+/// is set, full-width CJK punctuation/spaces and full-width digits are first converted to
+/// their half-width ASCII equivalents, so a Chinese-language answer written with "，" or
+/// "３" still matches an answer written with "," or "3"; English-only comparisons should
+/// pass `false` so their behavior is unchanged.
+fn standardize_text(s: &str, normalize_fullwidth: bool) -> String {
+    let s = if normalize_fullwidth {
+        normalize_fullwidth_chars(s)
+    } else {
+        s.to_string()
+    };
+    s.to_lowercase().split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Converts full-width CJK punctuation/space/digits to their half-width ASCII
+/// equivalents: "，"/"．"/"。" to ","/"."/".", the full-width space U+3000 to a regular
+/// space, and the full-width digits U+FF10-U+FF19 to "0"-"9".
+fn normalize_fullwidth_chars(s: &str) -> String {
+    s.chars()
+        .map(|c| match c {
+            '，' => ',',
+            '．' | '。' => '.',
+            '\u{3000}' => ' ',
+            '\u{FF10}'..='\u{FF19}' => char::from_u32(c as u32 - 0xFEE0).unwrap_or(c),
+            _ => c,
+        })
+        .collect()
+}
+
+/// Parses the canonical `"Missing necessary parameters (p1, p2) for the api (ApiName)"`
+/// phrase out of a `SpecialIncomplete` answer, returning the claimed API name and the set
+/// of parameter names it flagged. Returns `None` if the model didn't follow the expected
+/// phrasing closely enough to extract a name and a parameter list, in which case the
+/// caller falls back to a looser substring check.
+fn extract_missing_params_claim(model_result_raw: &str) -> Option<(String, HashSet<String>)> {
+    let re = Regex::new(r"(?i)missing necessary parameters\s*\(([^)]*)\)\s*for the api\s*\(([^)]*)\)")
+        .expect("Failed to compile regex");
+    let captures = re.captures(model_result_raw)?;
+    let trim_token = |s: &str| s.trim().trim_matches(['\'', '"', '`']).to_string();
+    let params: HashSet<String> = captures[1]
+        .split(',')
+        .map(trim_token)
+        .filter(|s| !s.is_empty())
+        .collect();
+    let api_name = trim_token(&captures[2]);
+    if api_name.is_empty() || params.is_empty() {
+        return None;
+    }
+    Some((api_name, params))
+}
+
 pub fn evaluate_one_pointing_out(
     model_result_raw: &str,
     possible_answer: &serde_json::Value,
     evaluation_type: &EvaluationType,
-) -> Result<(), String> {
-    let phrase_required = match evaluation_type {
-        EvaluationType::SpecialIncomplete => "Missing necessary parameters",
-        EvaluationType::SpecialErrorParam => "There is incorrect value",
+) -> Result<(), SpecialEvalError> {
+    let (phrase_required, error_type) = match evaluation_type {
+        EvaluationType::SpecialIncomplete => {
+            ("Missing necessary parameters", ERROR_TYPE_WRONG_MISSING_PARAMS)
+        }
+        EvaluationType::SpecialErrorParam => {
+            ("There is incorrect value", ERROR_TYPE_WRONG_POINTING_OUT)
+        }
         _ => panic!("Unsupported evaluation type for pointing out evaluation"),
     };
     if !model_result_raw.contains(phrase_required) {
-        return Err(format!(
-            "No '{}' found in model output while answering an incomplete question.",
-            phrase_required
-        ));
+        return Err(SpecialEvalError {
+            message: format!(
+                "No '{}' found in model output while answering an incomplete question.",
+                phrase_required
+            ),
+            error_type: error_type.to_string(),
+        });
     }
     let possible_answer_parsed: PossibleAnswerPointingOutHygienic =
         serde_json::from_value(possible_answer.clone())
             .expect("Failed to parse possible answer into PossibleAnswerPointingOutHygienic");
+    // For SpecialIncomplete, prefer exact set equality on the claimed API name and missing
+    // parameters over the looser substring check: a model that names the wrong API, or
+    // flags an extra/missing parameter, should fail even though the required phrases and
+    // every individual token happen to appear somewhere in its answer.
+    if matches!(evaluation_type, EvaluationType::SpecialIncomplete)
+        && let Some((claimed_api, claimed_params)) = extract_missing_params_claim(model_result_raw)
+    {
+        for PointingOutHygienic { name, values } in possible_answer_parsed.ground_truth.iter() {
+            let expected_params: HashSet<String> = values.iter().cloned().collect();
+            if !claimed_api.eq_ignore_ascii_case(name) || claimed_params != expected_params {
+                return Err(SpecialEvalError {
+                    message: format!(
+                        "Expected missing parameters {:?} for api '{}', but the model claimed {:?} for api '{}'",
+                        values, name, claimed_params, claimed_api
+                    ),
+                    error_type: error_type.to_string(),
+                });
+            }
+        }
+        return Ok(());
+    }
+    // Standardize before comparing so a model that changes case/whitespace while
+    // transcribing the flagged parameter name/value back into its answer (e.g. "Goog" ->
+    // "GOOG") is still credited with pointing it out correctly.
+    // `name`/`values` come straight from the dataset and may contain Chinese-language
+    // content, so normalize full-width punctuation/digits as well as case/whitespace.
+    let standardized_output = standardize_text(model_result_raw, true);
     for PointingOutHygienic { name, values } in possible_answer_parsed.ground_truth.iter() {
-        if !model_result_raw.contains(name) || !values.iter().all(|v| model_result_raw.contains(v))
+        if !strings_match(MatchMode::Contains, &standardized_output, &standardize_text(name, true))
+            || !values.iter().all(|v| {
+                strings_match(MatchMode::Contains, &standardized_output, &standardize_text(v, true))
+            })
         {
-            return Err(format!(
-                "The user's instruction is missing necessary parameters / contains incorrect values ({:?}) for the ({}), but the model failed to correctly point it out",
-                values, name
-            ));
+            return Err(SpecialEvalError {
+                message: format!(
+                    "The user's instruction is missing necessary parameters / contains incorrect values ({:?}) for the ({}), but the model failed to correctly point it out",
+                    values, name
+                ),
+                error_type: error_type.to_string(),
+            });
         }
     }
     Ok(())
@@ -684,16 +1360,91 @@ pub fn evaluate_one_pointing_out(
 pub fn evaluate_one_irrelevant(
     model_result_raw: &str,
     possible_answer: &serde_json::Value,
-) -> Result<(), String> {
+) -> Result<(), SpecialEvalError> {
     let _possible_answer_parsed: PossibleAnswerIrrelevantHygienic =
         serde_json::from_value(possible_answer.clone())
             .expect("Failed to parse possible answer into PossibleAnswerIrrelevantHygienic");
-    if !model_result_raw.contains("the limitations of the function") {
-        return Err("The model failed to identify that the question is irrelevant to the available functions.".to_string());
+    let canonical_refusal = standardize_text(
+        "Due to the limitations of the function, I cannot solve this problem.",
+        false,
+    );
+    if !standardize_text(model_result_raw, false).contains(&canonical_refusal) {
+        return Err(SpecialEvalError {
+            message: "The model failed to identify that the question is irrelevant to the available functions.".to_string(),
+            error_type: ERROR_TYPE_SHOULD_HAVE_REFUSED.to_string(),
+        });
     }
     Ok(())
 }
 
+/// Checks that every milestone call in `mile_stones` (each a raw `"[func(...)]"` call-list
+/// literal, same format as `AgentResultEntry::output_function_calls`) appears somewhere
+/// among the calls actually made, matching on both function name and parameters via
+/// [`functions_equivalent`] so a milestone isn't satisfied by a same-named call with
+/// different arguments. Matching is order-independent: the milestone calls don't need to
+/// appear in the same step or in the same relative order as the output. A milestone entry
+/// that fails to parse (or decodes to no calls) is always reported missing, which is the
+/// safe default for drifted dataset formats. Returns whether every milestone was found,
+/// together with the milestone entries that could not be found.
+pub fn milestones_satisfied(
+    output_function_calls: &[String],
+    mile_stones: &[String],
+) -> (bool, Vec<String>) {
+    let output_calls: Vec<FunctionCallHygienic> = output_function_calls
+        .iter()
+        .flat_map(|raw| decode_function_list(raw).unwrap_or_default())
+        .collect();
+    let missing: Vec<String> = mile_stones
+        .iter()
+        .filter(|milestone| {
+            let milestone_calls = decode_function_list(milestone).unwrap_or_default();
+            milestone_calls.is_empty()
+                || !milestone_calls
+                    .iter()
+                    .all(|mc| output_calls.iter().any(|oc| functions_equivalent(mc, oc, resolve_numeric_tolerance())))
+        })
+        .cloned()
+        .collect();
+    (missing.is_empty(), missing)
+}
+
+/// Best-effort check that every required milestone was satisfied (see
+/// [`milestones_satisfied`]) somewhere in the agent's run. `mile_stone` is kept as a raw
+/// `Value` because datasets encode it inconsistently: usually a flat list of raw call-list
+/// strings (one required step per entry), but sometimes a list of alternative flat lists,
+/// any one of which satisfies the milestone. Returns the missing entries under whichever
+/// alternative comes closest to being satisfied; an empty result means the milestone
+/// requirement is met (or absent).
+fn missing_milestone_names(mile_stone: &Value, output_function_calls: &[String]) -> Vec<String> {
+    let Some(entries) = mile_stone.as_array() else {
+        return Vec::new();
+    };
+    let alternatives: Vec<Vec<String>> = if entries.iter().all(|v| v.is_string()) {
+        vec![
+            entries
+                .iter()
+                .filter_map(|v| v.as_str().map(str::to_string))
+                .collect(),
+        ]
+    } else {
+        entries
+            .iter()
+            .filter_map(|v| v.as_array())
+            .map(|alternative| {
+                alternative
+                    .iter()
+                    .filter_map(|v| v.as_str().map(str::to_string))
+                    .collect()
+            })
+            .collect()
+    };
+    alternatives
+        .into_iter()
+        .map(|required| milestones_satisfied(output_function_calls, &required).1)
+        .min_by_key(|missing| missing.len())
+        .unwrap_or_default()
+}
+
 pub fn evaluate_agent(
     result_entries: &Vec<serde_json::Value>,
     problem_entries: &Vec<serde_json::Value>,
@@ -751,16 +1502,43 @@ pub fn evaluate_agent(
             .get(id)
             .expect("Missing possible answer entry");
 
-        match result_entry
+        let world_state_check = result_entry
             .final_world_state
-            .equals_ground_truth(&possible_answer_entry.ground_truth)
-        {
-            Ok(_) => {
+            .equals_ground_truth(&possible_answer_entry.ground_truth);
+        let world_state_diff = result_entry
+            .final_world_state
+            .diff(&possible_answer_entry.ground_truth);
+        let missing_milestones = missing_milestone_names(
+            &possible_answer_entry.mile_stone,
+            &result_entry.output_function_calls,
+        );
+
+        match world_state_check {
+            Ok(_) if missing_milestones.is_empty() => {
                 correct_count += 1;
                 results.push(AgentEvaluationResult {
                     id: id.clone(),
                     valid: true,
                     error: None,
+                    world_state_diff,
+                    model_raw_output: result_entry.conversation.clone(),
+                    conversation: result_entry.conversation.clone(),
+                    final_world_state: result_entry.final_world_state.clone(),
+                    expected_world_state: possible_answer_entry.ground_truth.clone(),
+                    output_function_calls: result_entry.output_function_calls.clone(),
+                    expected_function_calls: possible_answer_entry.mile_stone.clone(),
+                });
+            }
+            Ok(_) => {
+                results.push(AgentEvaluationResult {
+                    id: id.clone(),
+                    valid: false,
+                    error: Some(format!(
+                        "Final world state matches, but required milestone functions were never called: {:?}",
+                        missing_milestones
+                    )),
+                    world_state_diff,
+                    model_raw_output: result_entry.conversation.clone(),
                     conversation: result_entry.conversation.clone(),
                     final_world_state: result_entry.final_world_state.clone(),
                     expected_world_state: possible_answer_entry.ground_truth.clone(),
@@ -776,6 +1554,8 @@ pub fn evaluate_agent(
                         "Model output does not match the ground truth world state: {}",
                         err
                     )),
+                    world_state_diff,
+                    model_raw_output: result_entry.conversation.clone(),
                     conversation: result_entry.conversation.clone(),
                     final_world_state: result_entry.final_world_state.clone(),
                     expected_world_state: possible_answer_entry.ground_truth.clone(),
@@ -807,3 +1587,618 @@ pub fn evaluate_agent(
     final_results.extend(results_serialized);
     final_results
 }
+
+#[cfg(test)]
+mod numeric_tolerance_tests {
+    use super::*;
+
+    // Env vars are process-global, so serialize access to ACEBENCH_NUMERIC_TOLERANCE
+    // across tests that set/unset it.
+    static ENV_LOCK: std::sync::Mutex<()> = std::sync::Mutex::new(());
+
+    #[test]
+    fn resolve_numeric_tolerance_defaults_without_env_var() {
+        let _guard = ENV_LOCK.lock().unwrap();
+        unsafe { std::env::remove_var("ACEBENCH_NUMERIC_TOLERANCE") };
+        assert_eq!(resolve_numeric_tolerance(), DEFAULT_NUMERIC_TOLERANCE);
+    }
+
+    #[test]
+    fn resolve_numeric_tolerance_reads_env_var_override() {
+        let _guard = ENV_LOCK.lock().unwrap();
+        unsafe { std::env::set_var("ACEBENCH_NUMERIC_TOLERANCE", "0.5") };
+        assert_eq!(resolve_numeric_tolerance(), 0.5);
+        unsafe { std::env::remove_var("ACEBENCH_NUMERIC_TOLERANCE") };
+    }
+
+    #[test]
+    fn price_within_tolerance_passes() {
+        let a = json!(88.0);
+        let b = json!(88.000_001);
+        assert!(values_equivalent(&a, &b, 1e-3));
+    }
+
+    #[test]
+    fn price_outside_tolerance_fails() {
+        let a = json!(88.0);
+        let b = json!(89.0);
+        assert!(!values_equivalent(&a, &b, 1e-3));
+    }
+}
+
+#[cfg(test)]
+mod match_mode_tests {
+    use super::*;
+
+    #[test]
+    fn contains_mode_matches_substring() {
+        assert!(strings_match(
+            MatchMode::Contains,
+            "I want a Margherita Pizza",
+            "Margherita Pizza"
+        ));
+    }
+
+    #[test]
+    fn exact_mode_rejects_substring() {
+        assert!(!strings_match(
+            MatchMode::Exact,
+            "I want a Margherita Pizza",
+            "Margherita Pizza"
+        ));
+    }
+}
+
+#[cfg(test)]
+mod model_raw_output_tests {
+    use super::*;
+
+    #[test]
+    fn format_and_value_failures_both_carry_model_raw_output() {
+        let result_entries = vec![
+            json!({"id": "1", "result": "not a valid function call"}),
+            json!({"id": "2", "result": "[get_products(keyword=\"wrong\")]"}),
+        ];
+        let problem_entries = vec![json!({"id": "1"}), json!({"id": "2"})];
+        let possible_answer_entries = vec![
+            json!({"id": "1", "ground_truth": [{"name": "get_products", "parameters": {"keyword": "pizza"}}]}),
+            json!({"id": "2", "ground_truth": [{"name": "get_products", "parameters": {"keyword": "pizza"}}]}),
+        ];
+
+        let results = evaluate_normal_single_turn(
+            &result_entries,
+            &problem_entries,
+            &possible_answer_entries,
+            false,
+        );
+
+        // first element is the summary; the rest are per-entry NormalEvaluationResult
+        for entry in &results[1..] {
+            let valid = entry.get("valid").unwrap().as_bool().unwrap();
+            assert!(!valid, "expected entry to fail: {:?}", entry);
+            let raw_output = entry.get("model_raw_output").and_then(|v| v.as_str()).unwrap();
+            assert!(!raw_output.is_empty());
+        }
+    }
+}
+
+#[cfg(test)]
+mod function_coverage_tests {
+    use super::*;
+
+    #[test]
+    fn uncalled_function_appears_in_never_called_list() {
+        let offered: HashSet<String> = ["get_products", "get_orders", "search_products"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        let called: HashSet<String> = ["get_products"].iter().map(|s| s.to_string()).collect();
+
+        let (called_list, never_called_list) = compute_coverage_lists(&offered, &called);
+
+        assert_eq!(called_list, vec!["get_products".to_string()]);
+        assert_eq!(
+            never_called_list,
+            vec!["get_orders".to_string(), "search_products".to_string()]
+        );
+    }
+}
+
+#[cfg(test)]
+mod optional_question_field_tests {
+    use super::*;
+
+    #[test]
+    fn evaluating_an_entry_without_a_question_field_does_not_error() {
+        let result_entries = vec![json!({
+            "id": "1",
+            "result": "[get_products(keyword=\"pizza\")]",
+        })];
+        // deliberately has no "question" field, as agent/special datasets routed
+        // through this evaluator may omit it
+        let problem_entries = vec![json!({"id": "1"})];
+        let possible_answer_entries = vec![json!({
+            "id": "1",
+            "ground_truth": [{"name": "get_products", "parameters": {"keyword": "pizza"}}],
+        })];
+
+        let results = evaluate_normal_single_turn(
+            &result_entries,
+            &problem_entries,
+            &possible_answer_entries,
+            false,
+        );
+
+        let valid = results[1].get("valid").unwrap().as_bool().unwrap();
+        assert!(valid, "expected entry to succeed: {:?}", results[1]);
+    }
+}
+
+#[cfg(test)]
+mod any_parameter_type_tests {
+    use super::*;
+
+    #[test]
+    fn a_string_value_is_scored_correctly_under_loose_comparison() {
+        assert!(values_equivalent(
+            &json!("  Pizza  "),
+            &json!("pizza"),
+            0.0,
+        ));
+    }
+
+    #[test]
+    fn an_object_value_is_scored_correctly_under_structural_comparison() {
+        let model_value = json!({"origin": "Beijing", "destination": "Shanghai"});
+        let expected_value = json!({"origin": "Beijing", "destination": "Shanghai"});
+        assert!(values_equivalent(&model_value, &expected_value, 0.0));
+
+        let mismatched_value = json!({"origin": "Beijing", "destination": "Nanjing"});
+        assert!(!values_equivalent(&model_value, &mismatched_value, 0.0));
+    }
+}
+
+#[cfg(test)]
+mod evaluate_dataset_validation_tests {
+    use super::*;
+
+    #[test]
+    #[should_panic(expected = "Unknown dataset")]
+    fn rejects_a_dataset_name_not_present_in_datasets() {
+        evaluate_dataset(
+            "some-model".to_string(),
+            "definitely-not-a-real-dataset".to_string(),
+            false,
+        );
+    }
+}
+
+#[cfg(test)]
+mod milestones_satisfied_tests {
+    use super::*;
+
+    #[test]
+    fn a_correct_world_state_with_a_skipped_milestone_still_fails() {
+        let output_calls = vec!["[send_message(sender_name='Eve', receiver_name='Frank', message='hi')]".to_string()];
+        let mile_stones = vec!["[send_message(sender_name='Eve', receiver_name='Frank', message='hi')]".to_string(), "[delete_message(message_id='1')]".to_string()];
+
+        let (satisfied, missing) = milestones_satisfied(&output_calls, &mile_stones);
+
+        assert!(!satisfied);
+        assert_eq!(missing, vec!["[delete_message(message_id='1')]".to_string()]);
+    }
+
+    #[test]
+    fn matching_milestones_in_any_order_are_satisfied() {
+        let output_calls = vec![
+            "[delete_message(message_id='1')]".to_string(),
+            "[send_message(sender_name='Eve', receiver_name='Frank', message='hi')]".to_string(),
+        ];
+        let mile_stones = vec![
+            "[send_message(sender_name='Eve', receiver_name='Frank', message='hi')]".to_string(),
+            "[delete_message(message_id='1')]".to_string(),
+        ];
+
+        let (satisfied, missing) = milestones_satisfied(&output_calls, &mile_stones);
+
+        assert!(satisfied);
+        assert!(missing.is_empty());
+    }
+}
+
+#[cfg(test)]
+mod evaluate_agent_tests {
+    use super::*;
+
+    fn agent_result_entry(id: &str, output_function_calls: Vec<String>) -> serde_json::Value {
+        json!({
+            "id": id,
+            "conversation": "",
+            "chat_messages": [],
+            "final_world_state": serde_json::to_value(WorldState::default()).unwrap(),
+            "output_function_calls": output_function_calls,
+            "turn_timestamps": [],
+        })
+    }
+
+    fn agent_problem_entry(id: &str) -> serde_json::Value {
+        json!({
+            "id": id,
+            "question": "Send a greeting to Frank",
+            "initial_config": {},
+            "path": [],
+            "function": [],
+            "involved_classes": ["MessageApi"],
+        })
+    }
+
+    fn agent_possible_answer_entry(id: &str, mile_stone: Vec<&str>) -> serde_json::Value {
+        json!({
+            "id": id,
+            "ground_truth": serde_json::to_value(WorldState::default()).unwrap(),
+            "mile_stone": mile_stone,
+        })
+    }
+
+    #[test]
+    fn a_correct_world_state_missing_a_milestone_call_is_marked_invalid() {
+        let result_entries = vec![agent_result_entry("1", vec![])];
+        let problem_entries = vec![agent_problem_entry("1")];
+        let possible_answer_entries = vec![agent_possible_answer_entry(
+            "1",
+            vec!["[send_message(sender_name='Eve', receiver_name='Frank', message='hi')]"],
+        )];
+
+        let results = evaluate_agent(&result_entries, &problem_entries, &possible_answer_entries);
+
+        assert_eq!(results[0]["correct_count"], 0);
+        let entry = &results[1];
+        assert_eq!(entry["valid"], false);
+        assert!(entry["error"]
+            .as_str()
+            .unwrap()
+            .contains("milestone"));
+    }
+
+    #[test]
+    fn a_correct_world_state_with_the_milestone_call_present_is_marked_valid() {
+        let result_entries = vec![agent_result_entry(
+            "1",
+            vec!["[send_message(sender_name='Eve', receiver_name='Frank', message='hi')]".to_string()],
+        )];
+        let problem_entries = vec![agent_problem_entry("1")];
+        let possible_answer_entries = vec![agent_possible_answer_entry(
+            "1",
+            vec!["[send_message(sender_name='Eve', receiver_name='Frank', message='hi')]"],
+        )];
+
+        let results = evaluate_agent(&result_entries, &problem_entries, &possible_answer_entries);
+
+        assert_eq!(results[0]["correct_count"], 1);
+        assert_eq!(results[1]["valid"], true);
+    }
+}
+
+#[cfg(test)]
+mod evaluate_one_irrelevant_tests {
+    use super::*;
+
+    fn possible_answer(ground_truth: &str) -> serde_json::Value {
+        json!({"id": "1", "ground_truth": ground_truth})
+    }
+
+    #[test]
+    fn a_model_that_declines_the_irrelevant_question_is_valid() {
+        let result = evaluate_one_irrelevant(
+            "Due to the limitations of the function, I cannot solve this problem.",
+            &possible_answer("irrelevant"),
+        );
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn a_model_that_answers_instead_of_refusing_fails_with_should_have_refused() {
+        let result = evaluate_one_irrelevant(
+            "[get_products(keyword='pizza')]",
+            &possible_answer("irrelevant"),
+        );
+        let err = result.unwrap_err();
+        assert_eq!(err.error_type, "should_have_refused");
+    }
+}
+
+#[cfg(test)]
+mod evaluate_one_pointing_out_error_param_tests {
+    use super::*;
+
+    fn possible_answer(name: &str, values: &[&str]) -> serde_json::Value {
+        json!({
+            "id": "1",
+            "ground_truth": [{"name": name, "values": values}],
+        })
+    }
+
+    #[test]
+    fn correctly_pointing_out_the_bad_value_passes() {
+        let result = evaluate_one_pointing_out(
+            "There is incorrect value ('GOOG') for the parameters (stock_symbol) of get_stock_price.",
+            &possible_answer("get_stock_price", &["GOOG"]),
+            &EvaluationType::SpecialErrorParam,
+        );
+        assert!(result.is_ok(), "{}", result.err().map(|e| e.message).unwrap_or_default());
+    }
+
+    #[test]
+    fn pointing_out_the_wrong_value_fails_with_wrong_pointing_out() {
+        let result = evaluate_one_pointing_out(
+            "There is incorrect value ('AAPL') for the parameters (stock_symbol) of get_stock_price.",
+            &possible_answer("get_stock_price", &["GOOG"]),
+            &EvaluationType::SpecialErrorParam,
+        );
+        let err = result.unwrap_err();
+        assert_eq!(err.error_type, "wrong_pointing_out");
+    }
+}
+
+#[cfg(test)]
+mod evaluate_one_pointing_out_incomplete_tests {
+    use super::*;
+
+    fn possible_answer(name: &str, values: &[&str]) -> serde_json::Value {
+        json!({
+            "id": "1",
+            "ground_truth": [{"name": name, "values": values}],
+        })
+    }
+
+    #[test]
+    fn claiming_the_exact_api_and_missing_params_passes() {
+        let result = evaluate_one_pointing_out(
+            "Missing necessary parameters (origin, destination) for the api (get_flight)",
+            &possible_answer("get_flight", &["origin", "destination"]),
+            &EvaluationType::SpecialIncomplete,
+        );
+        assert!(result.is_ok(), "{}", result.err().map(|e| e.message).unwrap_or_default());
+    }
+
+    #[test]
+    fn claiming_the_wrong_api_fails_with_wrong_missing_params() {
+        let result = evaluate_one_pointing_out(
+            "Missing necessary parameters (origin, destination) for the api (reserve_flight)",
+            &possible_answer("get_flight", &["origin", "destination"]),
+            &EvaluationType::SpecialIncomplete,
+        );
+        let err = result.unwrap_err();
+        assert_eq!(err.error_type, "wrong_missing_params");
+    }
+
+    #[test]
+    fn claiming_a_different_parameter_set_fails_with_wrong_missing_params() {
+        let result = evaluate_one_pointing_out(
+            "Missing necessary parameters (origin) for the api (get_flight)",
+            &possible_answer("get_flight", &["origin", "destination"]),
+            &EvaluationType::SpecialIncomplete,
+        );
+        let err = result.unwrap_err();
+        assert_eq!(err.error_type, "wrong_missing_params");
+    }
+}
+
+#[cfg(test)]
+mod standardize_text_fullwidth_tests {
+    use super::*;
+
+    #[test]
+    fn normalize_fullwidth_converts_cjk_punctuation_space_and_digits_to_ascii() {
+        assert_eq!(
+            standardize_text("Goog，market cap３００", true),
+            "goog,market cap300"
+        );
+    }
+
+    #[test]
+    fn without_normalization_fullwidth_punctuation_is_left_untouched() {
+        assert_eq!(standardize_text("Goog，300", false), "goog，300");
+    }
+
+    #[test]
+    fn pointing_out_error_param_matches_a_value_written_with_fullwidth_punctuation() {
+        let possible_answer = json!({
+            "id": "1",
+            "ground_truth": [{"name": "get_stock_price", "values": ["300,500"]}],
+        });
+        // the model echoes the flagged value with a full-width comma ("，" instead of ",")
+        let result = evaluate_one_pointing_out(
+            "There is incorrect value ('300，500') for the parameters (stock_symbol) of get_stock_price.",
+            &possible_answer,
+            &EvaluationType::SpecialErrorParam,
+        );
+        assert!(result.is_ok(), "{}", result.err().map(|e| e.message).unwrap_or_default());
+    }
+}
+
+#[cfg(test)]
+mod evaluate_normal_multi_turn_tests {
+    use super::*;
+
+    fn result_entry(id: &str, result: &str) -> serde_json::Value {
+        json!({"id": id, "result": result})
+    }
+
+    fn problem_entry(id: &str) -> serde_json::Value {
+        json!({"id": id, "question": "", "function": []})
+    }
+
+    fn possible_answer_entry(id: &str, ground_truth: serde_json::Value) -> serde_json::Value {
+        json!({"id": id, "ground_truth": ground_truth})
+    }
+
+    #[test]
+    fn a_turn_where_every_item_passes_scores_one_and_a_turn_with_a_failure_scores_zero() {
+        let ground_truth = json!([{"name": "get_products", "parameters": {"keyword": "pizza"}}]);
+        let result_entries = vec![
+            result_entry("a_0_1", "[get_products(keyword='pizza')]"),
+            result_entry("a_0_2", "[get_products(keyword='pizza')]"),
+            result_entry("a_1_1", "[get_products(keyword='wrong')]"),
+        ];
+        let problem_entries = vec![
+            problem_entry("a_0_1"),
+            problem_entry("a_0_2"),
+            problem_entry("a_1_1"),
+        ];
+        let possible_answer_entries = vec![
+            possible_answer_entry("a_0_1", ground_truth.clone()),
+            possible_answer_entry("a_0_2", ground_truth.clone()),
+            possible_answer_entry("a_1_1", ground_truth.clone()),
+        ];
+
+        let results = evaluate_normal_multi_turn(&result_entries, &problem_entries, &possible_answer_entries, false);
+
+        let summary = &results[0];
+        assert_eq!(summary["accuracy"], 0.5, "turn 0 passes entirely, turn 1 fails entirely: (1.0 + 0.0) / 2");
+        assert_eq!(summary["correct_count"], 2);
+        assert_eq!(summary["total_count"], 3);
+        assert_eq!(summary["per_turn_accuracy"]["1"], 0.5, "item 1 passed in turn 0 but failed in turn 1");
+        assert_eq!(summary["per_turn_accuracy"]["2"], 1.0, "item 2 only appears in turn 0, where it passed");
+    }
+}
+
+#[cfg(test)]
+mod error_type_counts_tests {
+    use super::*;
+
+    fn result_entry(id: &str, result: &str) -> serde_json::Value {
+        json!({"id": id, "result": result})
+    }
+
+    fn problem_entry(id: &str) -> serde_json::Value {
+        json!({"id": id, "question": "", "function": []})
+    }
+
+    fn possible_answer_entry(id: &str, ground_truth: serde_json::Value) -> serde_json::Value {
+        json!({"id": id, "ground_truth": ground_truth})
+    }
+
+    #[test]
+    fn the_summary_classifies_failures_by_error_type() {
+        let result_entries = vec![
+            result_entry("b_1", "[get_products(keyword='pizza')]"),
+            result_entry("b_2", "[get_weather(city='NYC')]"),
+        ];
+        let problem_entries = vec![problem_entry("b_1"), problem_entry("b_2")];
+        let possible_answer_entries = vec![
+            possible_answer_entry(
+                "b_1",
+                json!([{"name": "get_products", "parameters": {"keyword": "sushi"}}]),
+            ),
+            possible_answer_entry(
+                "b_2",
+                json!([{"name": "get_stock_price", "parameters": {"stock_symbol": "GOOG"}}]),
+            ),
+        ];
+
+        let results = evaluate_normal_single_turn(&result_entries, &problem_entries, &possible_answer_entries, false);
+
+        let summary = &results[0];
+        assert_eq!(summary["correct_count"], 0);
+        assert_eq!(summary["total_count"], 2);
+        assert_eq!(summary["error_type_counts"]["value_error"], 1, "same function name, wrong keyword value");
+        assert_eq!(summary["error_type_counts"]["wrong_function_name"], 1, "no call with the expected name at all");
+    }
+}
+
+#[cfg(test)]
+mod overall_summary_tests {
+    use super::*;
+
+    #[test]
+    fn dataset_family_buckets_every_evaluation_type_into_the_expected_family() {
+        assert_eq!(dataset_family(&EvaluationType::Agent), "agent");
+        assert_eq!(dataset_family(&EvaluationType::NormalMultiTurn), "multi_turn");
+        assert_eq!(dataset_family(&EvaluationType::SpecialIncomplete), "special");
+        assert_eq!(dataset_family(&EvaluationType::SpecialErrorParam), "special");
+        assert_eq!(dataset_family(&EvaluationType::SpecialIrrelevant), "special");
+        assert_eq!(dataset_family(&EvaluationType::NormalSingleTurn), "atom");
+    }
+
+    #[test]
+    fn write_overall_summary_rolls_up_totals_and_per_family_averages() {
+        let model_safe_name = "overall_summary_test_model";
+        let dataset_scores = vec![("atom", 1.0, 2, 2), ("atom", 0.0, 0, 2), ("agent", 0.5, 1, 2)];
+
+        write_overall_summary(model_safe_name, &dataset_scores);
+
+        let output_path = BASE_SCORE_PATH.join(model_safe_name).join("overall_summary.json");
+        let written = std::fs::read_to_string(&output_path).expect("overall_summary.json was not written");
+        let summary: serde_json::Value = serde_json::from_str(written.lines().next().unwrap()).unwrap();
+
+        assert_eq!(summary["total_correct"], 3);
+        assert_eq!(summary["total_count"], 6);
+        assert_eq!(summary["macro_average_accuracy"], 0.5, "(1.0 + 0.0 + 0.5) / 3");
+        assert_eq!(summary["family_averages"]["atom"], 0.5, "(1.0 + 0.0) / 2");
+        assert_eq!(summary["family_averages"]["agent"], 0.5);
+
+        std::fs::remove_dir_all(BASE_SCORE_PATH.join(model_safe_name)).ok();
+    }
+}
+
+#[cfg(test)]
+mod validate_datasets_tests {
+    use super::*;
+
+    #[test]
+    fn the_checked_in_fixtures_parse_cleanly_with_no_reported_errors() {
+        let errors = validate_datasets("validate_datasets_test_model".to_string());
+        assert!(
+            errors.is_empty(),
+            "expected the checked-in dataset/ground-truth fixtures to pass validation, got: {:?}",
+            errors
+        );
+    }
+}
+
+#[cfg(test)]
+mod param_name_mismatch_tests {
+    use super::*;
+
+    #[test]
+    fn using_receiver_instead_of_receiver_name_is_reported_as_a_single_param_name_mismatch() {
+        let possible_answer_function_calls = vec![FunctionCallHygienic {
+            name: "send_message".to_string(),
+            parameters: IndexMap::from([
+                ("receiver_name".to_string(), json!("Frank")),
+                ("message".to_string(), json!("hi")),
+            ]),
+        }];
+
+        let result = evaluate_one_normal(
+            "[send_message(receiver='Frank', message='hi')]",
+            &possible_answer_function_calls,
+            false,
+            0.0,
+        );
+
+        let err = result.unwrap_err();
+        assert_eq!(err.error_type, "param_name_mismatch");
+        assert!(err.message.contains("receiver"));
+        assert!(err.message.contains("receiver_name"));
+    }
+
+    #[test]
+    fn an_unrelated_extra_param_is_still_classified_as_extra_args() {
+        let possible_answer_function_calls = vec![FunctionCallHygienic {
+            name: "send_message".to_string(),
+            parameters: IndexMap::from([("receiver_name".to_string(), json!("Frank"))]),
+        }];
+
+        let result = evaluate_one_normal(
+            "[send_message(receiver_name='Frank', message='hi')]",
+            &possible_answer_function_calls,
+            false,
+            0.0,
+        );
+
+        let err = result.unwrap_err();
+        assert_eq!(err.error_type, "extra_args");
+    }
+}
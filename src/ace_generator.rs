@@ -16,11 +16,13 @@ use crate::{
         AceProblem, AceProblemState, AgentProblemState, DialogueEntry, DialogueParticipant,
         ProblemStatus, SingleTurnProblemState,
     },
+    base_api::ExecutionResult,
     datasets::DATASETS,
+    evaluate_parse::FunctionCallHygienic,
     paths::{BASE_DATASET_PATH, BASE_OUTPUT_PATH},
     perturbations::{self, PerturbationType},
     python_interface::PythonResponse,
-    utils::{load_json_lines, write_json_lines_to_file},
+    utils::{load_json_lines, load_json_lines_lenient, sort_entries_by_id, write_json_lines_to_file},
     world_state::WorldState,
 };
 
@@ -69,12 +71,43 @@ pub enum DatasetEntry {
     Normal(NormalEntry),
 }
 
+/// One turn of a reconstructed dialogue, in the role/content shape OpenAI-style chat
+/// APIs expect. `role` is one of `"user"`, `"assistant"`, or `"tool"`.
+#[derive(Serialize, Deserialize, Clone)]
+pub struct ChatMessage {
+    pub role: String,
+    pub content: String,
+}
+
+/// One traced turn of an agent run: the raw LLM response, the function calls
+/// parsed out of it, and the results of executing them against `WorldState`.
+/// Only populated when trace capture is enabled (see
+/// [`crate::ace_problem::AceProblem::set_enable_trace`]); see `AgentResultEntry::trace`.
+#[derive(Serialize, Deserialize, Clone)]
+pub struct TraceEntry {
+    pub raw_response: String,
+    pub function_calls: Vec<FunctionCallHygienic>,
+    pub execution_results: Vec<ExecutionResult>,
+}
+
 #[derive(Serialize, Deserialize, Clone)]
 pub struct AgentResultEntry {
     pub id: String,
     pub conversation: String,
+    /// `conversation` reconstructed as `{role, content}` turns (User→user,
+    /// Agent→assistant, Execution→tool), for tooling that consumes OpenAI-style
+    /// chat messages. Kept alongside `conversation` rather than replacing it, so
+    /// existing consumers of the flat string keep working.
+    pub chat_messages: Vec<ChatMessage>,
     pub final_world_state: WorldState,
     pub output_function_calls: Vec<String>,
+    /// Wall-clock time each dialogue entry was recorded, in history order; the gap
+    /// between consecutive entries profiles where time goes in a multi-turn/multi-step run
+    pub turn_timestamps: Vec<chrono::DateTime<chrono::Utc>>,
+    /// Per-turn (raw response, parsed function calls, execution results) trace,
+    /// empty unless trace capture was enabled for this run; see [`TraceEntry`].
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub trace: Vec<TraceEntry>,
 }
 
 #[derive(Serialize, Deserialize, Clone)]
@@ -110,6 +143,38 @@ pub struct DatasetTrait {
     pub evaluation_type: EvaluationType,
 }
 
+/// Checks that each tool definition in `funcs` has the shape the evaluator assumes
+/// (`name`, `parameters`, and well-formed `parameters.properties`/`parameters.required`
+/// when present), so a typo'd dataset schema fails loudly at load time instead of
+/// producing a confusing evaluation error much later.
+pub fn validate_function_schema(funcs: &[serde_json::Value]) -> Result<(), String> {
+    for func in funcs {
+        let Some(name) = func.get("name") else {
+            return Err(format!("tool definition is missing a 'name' field: {}", func));
+        };
+        let Some(name) = name.as_str() else {
+            return Err(format!("tool definition's 'name' field is not a string: {}", name));
+        };
+        let Some(parameters) = func.get("parameters") else {
+            return Err(format!("tool '{}' is missing a 'parameters' field", name));
+        };
+        if !parameters.is_object() {
+            return Err(format!("tool '{}''s 'parameters' field is not an object", name));
+        }
+        if let Some(properties) = parameters.get("properties")
+            && !properties.is_object()
+        {
+            return Err(format!("tool '{}''s 'parameters.properties' field is not an object", name));
+        }
+        if let Some(required) = parameters.get("required")
+            && !required.is_array()
+        {
+            return Err(format!("tool '{}''s 'parameters.required' field is not an array", name));
+        }
+    }
+    Ok(())
+}
+
 fn parse_entries_to_problems(
     entries: Vec<serde_json::Value>,
     perturbation_type: PerturbationType,
@@ -117,8 +182,18 @@ fn parse_entries_to_problems(
     output_file_path: impl AsRef<Path>,
     problem_type: &ProblemType,
 ) -> Vec<AceProblem> {
-    let existing_entries: Vec<serde_json::Value> =
-        load_json_lines(output_file_path.as_ref()).unwrap_or_default();
+    // A partially written output file (e.g. after an interrupted run) shouldn't crash
+    // startup: skip blank lines and warn about malformed ones instead of aborting.
+    let (existing_entries, warnings) =
+        load_json_lines_lenient(output_file_path.as_ref()).unwrap_or_default();
+    for (line_number, error) in &warnings {
+        eprintln!(
+            "Warning: skipping malformed record at {}:{}: {}",
+            output_file_path.as_ref().display(),
+            line_number,
+            error
+        );
+    }
     let existing_ids = existing_entries
         .iter()
         .map(|entry_value| {
@@ -146,6 +221,10 @@ fn parse_entries_to_problems(
             for entry_value in entries {
                 let entry: NormalEntry = serde_json::from_value(entry_value.clone())
                     .expect("failed to parse NormalEntry");
+                if let Err(e) = validate_function_schema(&entry.function) {
+                    eprintln!("Warning: skipping entry {} with invalid function schema: {}", entry.id, e);
+                    continue;
+                }
                 if existing_ids.contains(&entry.id) {
                     continue;
                 }
@@ -163,9 +242,10 @@ fn parse_entries_to_problems(
                     has_transition_perturbation,
                     time: Some(time),
                     profile: None,
-                    question: entry.question.clone(),
+                    question: perturbations::perturbed_question(&entry.question, &entry.id, perturbation_type),
                     first_turn: true,
                     prev_llm_response: None,
+                    result: None,
                 };
                 let problem = AceProblem {
                     identifier,
@@ -174,9 +254,16 @@ fn parse_entries_to_problems(
                     id: entry.id,
                     status: ProblemStatus::Waiting,
                     question: entry.question,
-                    function: entry.function,
+                    function: {
+                        let mut function = entry.function;
+                        perturbations::perturb_functions(&mut function, perturbation_type);
+                        function
+                    },
                     state: AceProblemState::SingleTurnNormal(single_turn_state),
                     output_file: output_file.clone(),
+                    dialogue_event_sink: None,
+                    max_dialogue_chars: None,
+                    attempt_count: 0,
                 };
                 problems.push(problem);
             }
@@ -187,6 +274,10 @@ fn parse_entries_to_problems(
             for entry_value in entries {
                 let entry: NormalEntry = serde_json::from_value(entry_value.clone())
                     .expect("failed to parse PreferenceEntry");
+                if let Err(e) = validate_function_schema(&entry.function) {
+                    eprintln!("Warning: skipping entry {} with invalid function schema: {}", entry.id, e);
+                    continue;
+                }
                 if existing_ids.contains(&entry.id) {
                     continue;
                 }
@@ -204,9 +295,10 @@ fn parse_entries_to_problems(
                     has_transition_perturbation,
                     time: None,
                     profile: Some(profile),
-                    question: entry.question.clone(),
+                    question: perturbations::perturbed_question(&entry.question, &entry.id, perturbation_type),
                     first_turn: true,
                     prev_llm_response: None,
+                    result: None,
                 };
                 let problem = AceProblem {
                     identifier,
@@ -215,72 +307,99 @@ fn parse_entries_to_problems(
                     id: entry.id,
                     status: ProblemStatus::Waiting,
                     question: entry.question,
-                    function: entry.function,
+                    function: {
+                        let mut function = entry.function;
+                        perturbations::perturb_functions(&mut function, perturbation_type);
+                        function
+                    },
                     state: AceProblemState::SingleTurnPreference(single_turn_state),
                     output_file: output_file.clone(),
+                    dialogue_event_sink: None,
+                    max_dialogue_chars: None,
+                    attempt_count: 0,
                 };
                 problems.push(problem);
             }
             problems
         }
         ProblemType::SingleTurnSpecial => {
-            // let mut problems: Vec<AceProblem> = Vec::new();
-            // for entry_value in entries {
-            //     let entry: NormalEntry = serde_json::from_value(entry_value.clone())
-            //         .expect("failed to parse NormalEntry for special");
-            //     if existing_ids.contains(&entry.id) {
-            //         continue;
-            //     }
-            //     let identifier = format!(
-            //         "{}_{}_{}",
-            //         perturbation_type.to_folder_name(),
-            //         dataset_name,
-            //         entry.id
-            //     );
-            //     let time = entry
-            //         .time
-            //         .clone()
-            //         .expect("Non-preference normal dataset should have time field");
-            //     let single_turn_state = SingleTurnProblemState {
-            //         time: Some(time),
-            //         profile: None,
-            //         question: entry.question.clone(),
-            //         first_turn: true,
-            //         prev_llm_response: None,
-            //     };
-            //     let problem = AceProblem {
-            //         identifier,
-            //         perturbation_type: perturbation_type.to_folder_name(),
-            //         dataset_name: dataset_name.clone(),
-            //         id: entry.id,
-            //         status: ProblemStatus::Waiting,
-            //         question: entry.question,
-            //         function: entry.function,
-            //         state: AceProblemState::SingleTurnSpecial(single_turn_state),
-            //         output_file: output_file.clone(),
-            //     };
-            //     problems.push(problem);
-            // }
-            // problems
-            panic!("Special single-turn datasets are not supported in this project.");
+            let mut problems: Vec<AceProblem> = Vec::new();
+            for entry_value in entries {
+                let entry: NormalEntry = serde_json::from_value(entry_value.clone())
+                    .expect("failed to parse NormalEntry for special");
+                if let Err(e) = validate_function_schema(&entry.function) {
+                    eprintln!("Warning: skipping entry {} with invalid function schema: {}", entry.id, e);
+                    continue;
+                }
+                if existing_ids.contains(&entry.id) {
+                    continue;
+                }
+                let identifier = format!(
+                    "{}_{}_{}",
+                    perturbation_type.to_folder_name(),
+                    dataset_name,
+                    entry.id
+                );
+                let time = entry
+                    .time
+                    .clone()
+                    .expect("Special single-turn dataset should have time field");
+                let single_turn_state = SingleTurnProblemState {
+                    has_transition_perturbation,
+                    time: Some(time),
+                    profile: None,
+                    question: perturbations::perturbed_question(&entry.question, &entry.id, perturbation_type),
+                    first_turn: true,
+                    prev_llm_response: None,
+                    result: None,
+                };
+                let problem = AceProblem {
+                    identifier,
+                    perturbation_type: perturbation_type.to_folder_name(),
+                    dataset_name: dataset_name.clone(),
+                    id: entry.id,
+                    status: ProblemStatus::Waiting,
+                    question: entry.question,
+                    function: {
+                        let mut function = entry.function;
+                        perturbations::perturb_functions(&mut function, perturbation_type);
+                        function
+                    },
+                    state: AceProblemState::SingleTurnSpecial(single_turn_state),
+                    output_file: output_file.clone(),
+                    dialogue_event_sink: None,
+                    max_dialogue_chars: None,
+                    attempt_count: 0,
+                };
+                problems.push(problem);
+            }
+            problems
         }
         ProblemType::AgentMultiTurn => {
             let mut problems: Vec<AceProblem> = Vec::new();
             for entry_value in entries {
                 let entry: AgentEntry = serde_json::from_value(entry_value)
                     .expect("failed to parse AgentEntry for multi-turn");
+                if let Err(e) = validate_function_schema(&entry.function) {
+                    eprintln!("Warning: skipping entry {} with invalid function schema: {}", entry.id, e);
+                    continue;
+                }
                 if existing_ids.contains(&entry.id) {
                     continue;
                 }
                 let world_state: WorldState =
                     serde_json::from_value(serde_json::to_value(&entry.initial_config).unwrap())
                         .unwrap_or_default();
+                if let Some(travel) = &world_state.travel {
+                    travel.validate().expect("Invalid initial Travel config");
+                }
                 let identifier = format!(
                     "{}_{}_{}",
                     perturbation_type.to_folder_name(),
                     dataset_name,
                     entry.id
                 );
+                let prompt_question = perturbations::perturbed_question(&entry.question, &entry.id, perturbation_type);
                 let problem = AceProblem {
                     identifier,
                     perturbation_type: perturbation_type.to_folder_name(),
@@ -288,14 +407,21 @@ fn parse_entries_to_problems(
                     id: entry.id,
                     status: ProblemStatus::Waiting,
                     question: entry.question.clone(),
-                    function: entry.function,
+                    function: {
+                        let mut function = entry.function;
+                        perturbations::perturb_functions(&mut function, perturbation_type);
+                        function
+                    },
                     state: AceProblemState::MultiTurn(AgentProblemState::new_multi_turn(
                         world_state.clone(),
                         entry.involved_classes.clone(),
-                        &entry.question,
+                        &prompt_question,
                         has_transition_perturbation,
                     )),
                     output_file: output_file.clone(),
+                    dialogue_event_sink: None,
+                    max_dialogue_chars: None,
+                    attempt_count: 0,
                 };
                 problems.push(problem);
             }
@@ -306,18 +432,26 @@ fn parse_entries_to_problems(
             for entry_value in entries {
                 let entry: AgentEntry = serde_json::from_value(entry_value)
                     .expect("failed to parse AgentEntry for multi-step");
+                if let Err(e) = validate_function_schema(&entry.function) {
+                    eprintln!("Warning: skipping entry {} with invalid function schema: {}", entry.id, e);
+                    continue;
+                }
                 if existing_ids.contains(&entry.id) {
                     continue;
                 }
                 let world_state: WorldState =
                     serde_json::from_value(serde_json::to_value(&entry.initial_config).unwrap())
                         .unwrap_or_default();
+                if let Some(travel) = &world_state.travel {
+                    travel.validate().expect("Invalid initial Travel config");
+                }
                 let identifier = format!(
                     "{}_{}_{}",
                     perturbation_type.to_folder_name(),
                     dataset_name,
                     entry.id
                 );
+                let prompt_question = perturbations::perturbed_question(&entry.question, &entry.id, perturbation_type);
                 let problem = AceProblem {
                     identifier,
                     perturbation_type: perturbation_type.to_folder_name(),
@@ -325,14 +459,21 @@ fn parse_entries_to_problems(
                     id: entry.id,
                     status: ProblemStatus::Waiting,
                     question: entry.question.clone(),
-                    function: entry.function,
+                    function: {
+                        let mut function = entry.function;
+                        perturbations::perturb_functions(&mut function, perturbation_type);
+                        function
+                    },
                     state: AceProblemState::MultiStep(AgentProblemState::new_multi_step(
                         world_state.clone(),
                         entry.involved_classes.clone(),
-                        &entry.question,
+                        &prompt_question,
                         has_transition_perturbation,
                     )),
                     output_file: output_file.clone(),
+                    dialogue_event_sink: None,
+                    max_dialogue_chars: None,
+                    attempt_count: 0,
                 };
                 problems.push(problem);
             }
@@ -348,6 +489,9 @@ pub struct AceGenerator {
     // needs to store all the tasks and results
     pub model_safe_name: String,
     pub enable_fc: bool, // Function calling mode
+    /// Whether agent problems should record a per-turn trace (see `TraceEntry`);
+    /// off by default, set via `set_enable_trace`.
+    pub enable_trace: bool,
     pub waiting_queue: VecDeque<AceProblem>,
     pub executing_pool: HashMap<String, AceProblem>,
     pub num_completed: usize,
@@ -360,11 +504,34 @@ impl AceGenerator {
     pub fn new(model_name: String, enable_fc: bool) -> Self {
         Self::new_helper(model_name, enable_fc)
     }
+
+    /// Same as `new`, but restricts the waiting queue to the given dataset and
+    /// perturbation-folder names, so a caller that only wants e.g.
+    /// `data_agent_multi_turn` under `no_perturbation` doesn't pay to load everything
+    /// else. Panics if any name doesn't match a registered dataset/perturbation.
+    #[staticmethod]
+    pub fn new_filtered(
+        model_name: String,
+        enable_fc: bool,
+        dataset_names: Vec<String>,
+        perturbations: Vec<String>,
+    ) -> Self {
+        Self::new_filtered_helper(model_name, enable_fc, dataset_names, perturbations)
+    }
     /// Returns a json string with the format {"identifier": str, "system_prompt": str, "user_prompt": str}
     pub fn next_task(&mut self) -> Option<String> {
         self.next_task_helper()
     }
 
+    /// Pops up to `n` waiting problems, moves each into `executing_pool`, and returns
+    /// their serialized `PythonTask`s, so a Python caller running several LLM calls
+    /// concurrently doesn't have to serialize on one `next_task` at a time. Returns fewer
+    /// than `n` (possibly zero) once the waiting queue runs dry. `receive_response`
+    /// matches by identifier, so responses may come back in any order.
+    pub fn next_tasks(&mut self, n: usize) -> Vec<String> {
+        std::iter::from_fn(|| self.next_task_helper()).take(n).collect()
+    }
+
     pub fn receive_response(&mut self, response: String) {
         self.receive_response_helper(response);
     }
@@ -372,9 +539,74 @@ impl AceGenerator {
     pub fn sort_all_files_after_generation(&mut self) {
         self.sort_all_files_after_generation_helper();
     }
+
+    /// Returns `(num_completed, executing_pool.len(), waiting_queue.len())` so a Python
+    /// driver can render a real progress bar and detect stalls, instead of only seeing
+    /// progress via the `println!`s inside `receive_response_helper`.
+    pub fn progress(&self) -> (usize, usize, usize) {
+        (
+            self.num_completed,
+            self.executing_pool.len(),
+            self.waiting_queue.len(),
+        )
+    }
+
+    /// True once both the waiting queue and executing pool are empty, i.e. there is no
+    /// more work left to dispatch or await a response for.
+    pub fn is_done(&self) -> bool {
+        self.waiting_queue.is_empty() && self.executing_pool.is_empty()
+    }
+
+    /// Enables or disables per-turn trace capture (see `TraceEntry`) for every agent
+    /// problem currently queued or executing, and for any problem already returned.
+    /// Off by default, since most runs don't need the extra memory and output size.
+    pub fn set_enable_trace(&mut self, enable: bool) {
+        self.enable_trace = enable;
+        for problem in self.waiting_queue.iter_mut() {
+            problem.set_enable_trace(enable);
+        }
+        for problem in self.executing_pool.values_mut() {
+            problem.set_enable_trace(enable);
+        }
+    }
 }
 impl AceGenerator {
     pub fn new_helper(model_name: String, enable_fc: bool) -> Self {
+        let all_dataset_names: Vec<String> = DATASETS.keys().cloned().collect();
+        let all_perturbation_names: Vec<String> = PerturbationType::all_perturbations()
+            .map(|perturbation_type| perturbation_type.to_folder_name())
+            .collect();
+        Self::new_filtered_helper(model_name, enable_fc, all_dataset_names, all_perturbation_names)
+    }
+
+    pub fn new_filtered_helper(
+        model_name: String,
+        enable_fc: bool,
+        dataset_names: Vec<String>,
+        perturbations: Vec<String>,
+    ) -> Self {
+        let dataset_names: Vec<String> = dataset_names
+            .into_iter()
+            .map(|dataset_name| {
+                if !DATASETS.contains_key(&dataset_name) {
+                    panic!(
+                        "Unknown dataset '{}'. Valid datasets: {:?}",
+                        dataset_name,
+                        DATASETS.keys().collect::<Vec<_>>()
+                    );
+                }
+                dataset_name
+            })
+            .collect();
+        let perturbation_types: Vec<PerturbationType> = perturbations
+            .into_iter()
+            .map(|perturbation_name| {
+                PerturbationType::from_folder_name(&perturbation_name).unwrap_or_else(|| {
+                    panic!("Unknown perturbation '{}'.", perturbation_name)
+                })
+            })
+            .collect();
+
         let mut waiting_queue = VecDeque::new();
         let executing_pool = HashMap::new();
         let model_safe_name = if enable_fc {
@@ -382,9 +614,10 @@ impl AceGenerator {
         } else {
             model_name.replace("/", "-")
         };
-        for perturbation_type in PerturbationType::all_perturbations() {
+        for perturbation_type in perturbation_types {
             let perturbation_folder_name = perturbation_type.to_folder_name();
-            for (dataset_name, dataset_trait) in DATASETS.iter() {
+            for dataset_name in dataset_names.iter() {
+                let dataset_trait = DATASETS.get(dataset_name).expect("validated above");
                 // let dataset_path = BASE_DATASET_PATH
                 //     .join(perturbation_folder_name.clone())
                 //     .join(dataset_name.to_string() + ".json");
@@ -425,6 +658,7 @@ impl AceGenerator {
         AceGenerator {
             model_safe_name,
             enable_fc,
+            enable_trace: false,
             waiting_queue,
             executing_pool,
             num_completed: 0,
@@ -449,14 +683,25 @@ impl AceGenerator {
             .executing_pool
             .remove(&response.identifier)
             .expect("The problem is not in the executing pool");
-        let completed = problem.handle_python_response(response, self.enable_fc);
+        let mut completed = problem.handle_python_response(response, self.enable_fc);
+        if !completed {
+            problem.attempt_count += 1;
+            if problem.attempts_exhausted() {
+                println!(
+                    "Problem {} exhausted its attempt budget, force-finalizing.",
+                    problem.identifier
+                );
+                problem.force_finalize();
+                completed = true;
+            }
+        }
         if !completed {
             problem.status = ProblemStatus::Waiting;
             println!(
                 "Problem {} not completed, re-added to waiting queue.",
                 problem.identifier
             );
-            self.waiting_queue.push_front(problem); // insert to the front of the queue to make problems finish early            
+            self.waiting_queue.push_front(problem); // insert to the front of the queue to make problems finish early
         } else {
             self.num_completed += 1;
             println!(
@@ -486,40 +731,7 @@ impl AceGenerator {
                 let is_multi_turn = dataset_name.contains("normal_multi_turn");
 
                 let mut entries = entries;
-                entries.sort_by(|a, b| {
-                    let id_a = a.get("id").and_then(|v| v.as_str()).unwrap_or("");
-                    let id_b = b.get("id").and_then(|v| v.as_str()).unwrap_or("");
-
-                    if is_multi_turn {
-                        // For multi_turn datasets, IDs are like "123_456"
-                        // Compare by first number (major), then second number (minor)
-                        let parse_multi_turn_id = |id: &str| -> (i64, i64) {
-                            let parts: Vec<&str> = id.split('_').collect();
-                            let major = parts.first().and_then(|s| s.parse().ok()).unwrap_or(0);
-                            let minor = parts.get(1).and_then(|s| s.parse().ok()).unwrap_or(0);
-                            (major, minor)
-                        };
-                        let (major_a, minor_a) = parse_multi_turn_id(id_a);
-                        let (major_b, minor_b) = parse_multi_turn_id(id_b);
-                        (major_a, minor_a).cmp(&(major_b, minor_b))
-                    } else {
-                        // For other datasets, extract trailing number from ID
-                        let extract_trailing_number = |id: &str| -> i64 {
-                            id.chars()
-                                .rev()
-                                .take_while(|c| c.is_ascii_digit())
-                                .collect::<String>()
-                                .chars()
-                                .rev()
-                                .collect::<String>()
-                                .parse()
-                                .unwrap_or(0)
-                        };
-                        let num_a = extract_trailing_number(id_a);
-                        let num_b = extract_trailing_number(id_b);
-                        num_a.cmp(&num_b)
-                    }
-                });
+                sort_entries_by_id(&mut entries, is_multi_turn);
 
                 if let Err(e) = write_json_lines_to_file(&output_path, &entries) {
                     println!(
@@ -534,3 +746,168 @@ impl AceGenerator {
         }
     }
 }
+
+#[cfg(test)]
+mod new_filtered_tests {
+    use super::*;
+
+    #[test]
+    fn scoping_to_one_dataset_and_one_perturbation_only_queues_that_combination() {
+        let model_safe_name = "synth537_test_model".to_string();
+        let generator = AceGenerator::new_filtered_helper(
+            model_safe_name.clone(),
+            false,
+            vec!["data_agent_multi_turn".to_string()],
+            vec!["no_perturbation".to_string()],
+        );
+
+        assert!(!generator.waiting_queue.is_empty());
+        assert_eq!(generator.total_num, generator.waiting_queue.len());
+        for problem in &generator.waiting_queue {
+            assert_eq!(problem.dataset_name, "data_agent_multi_turn");
+            assert_eq!(problem.perturbation_type, "no_perturbation");
+        }
+
+        std::fs::remove_dir_all(BASE_OUTPUT_PATH.join(&model_safe_name)).ok();
+    }
+
+    #[test]
+    #[should_panic(expected = "Unknown dataset")]
+    fn an_unknown_dataset_name_panics() {
+        AceGenerator::new_filtered_helper(
+            "synth537_test_model_bad_dataset".to_string(),
+            false,
+            vec!["definitely_not_a_real_dataset".to_string()],
+            vec!["no_perturbation".to_string()],
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Unknown perturbation")]
+    fn an_unknown_perturbation_name_panics() {
+        AceGenerator::new_filtered_helper(
+            "synth537_test_model_bad_perturbation".to_string(),
+            false,
+            vec!["data_agent_multi_turn".to_string()],
+            vec!["definitely_not_a_real_perturbation".to_string()],
+        );
+    }
+}
+
+#[cfg(test)]
+mod next_tasks_tests {
+    use super::*;
+
+    #[test]
+    fn dispatching_five_tasks_and_responding_out_of_order_completes_all_of_them() {
+        let model_safe_name = "synth538_test_model".to_string();
+        let mut generator = AceGenerator::new_filtered_helper(
+            model_safe_name.clone(),
+            false,
+            vec!["data_normal_single_turn_single_function".to_string()],
+            vec!["no_perturbation".to_string()],
+        );
+
+        let tasks = generator.next_tasks(5);
+        assert_eq!(tasks.len(), 5);
+        assert_eq!(generator.executing_pool.len(), 5);
+        assert_eq!(generator.waiting_queue.len(), generator.total_num - 5);
+
+        let identifiers: Vec<String> = tasks
+            .iter()
+            .map(|task| {
+                let parsed: serde_json::Value = serde_json::from_str(task).unwrap();
+                parsed["identifier"].as_str().unwrap().to_string()
+            })
+            .collect();
+
+        // respond in reverse order, not the order the tasks were dispatched in
+        for identifier in identifiers.iter().rev() {
+            let response = serde_json::json!({
+                "identifier": identifier,
+                "response": "[is_true(value=True)]",
+                "is_retry": false,
+            });
+            generator.receive_response_helper(response.to_string());
+        }
+
+        assert_eq!(generator.executing_pool.len(), 0, "every dispatched identifier should have matched a response");
+        assert_eq!(generator.num_completed, 5);
+
+        std::fs::remove_dir_all(BASE_OUTPUT_PATH.join(&model_safe_name)).ok();
+    }
+}
+
+#[cfg(test)]
+mod progress_tests {
+    use super::*;
+
+    #[test]
+    fn progress_and_is_done_track_the_queue_and_pool_as_tasks_are_dispatched_and_completed() {
+        let model_safe_name = "synth539_test_model".to_string();
+        let mut generator = AceGenerator::new_filtered_helper(
+            model_safe_name.clone(),
+            false,
+            vec!["data_normal_single_turn_single_function".to_string()],
+            vec!["no_perturbation".to_string()],
+        );
+
+        let total = generator.total_num;
+        assert_eq!(generator.progress(), (0, 0, total));
+        assert!(!generator.is_done());
+
+        let tasks = generator.next_tasks(total);
+        assert_eq!(generator.progress(), (0, total, 0));
+        assert!(!generator.is_done());
+
+        for task in &tasks {
+            let parsed: serde_json::Value = serde_json::from_str(task).unwrap();
+            let identifier = parsed["identifier"].as_str().unwrap().to_string();
+            let response = serde_json::json!({
+                "identifier": identifier,
+                "response": "[is_true(value=True)]",
+                "is_retry": false,
+            });
+            generator.receive_response_helper(response.to_string());
+        }
+
+        assert_eq!(generator.progress(), (total, 0, 0));
+        assert!(generator.is_done());
+
+        std::fs::remove_dir_all(BASE_OUTPUT_PATH.join(&model_safe_name)).ok();
+    }
+}
+
+#[cfg(test)]
+mod validate_function_schema_tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn rejects_missing_name() {
+        let funcs = vec![json!({
+            "parameters": {"type": "object", "properties": {}, "required": []}
+        })];
+        let err = validate_function_schema(&funcs).unwrap_err();
+        assert!(err.contains("missing a 'name' field"), "unexpected error: {}", err);
+    }
+
+    #[test]
+    fn rejects_non_array_required() {
+        let funcs = vec![json!({
+            "name": "get_products",
+            "parameters": {"type": "object", "properties": {}, "required": "product_id"}
+        })];
+        let err = validate_function_schema(&funcs).unwrap_err();
+        assert!(err.contains("'parameters.required'"), "unexpected error: {}", err);
+    }
+
+    #[test]
+    fn accepts_well_formed_schema() {
+        let funcs = vec![json!({
+            "name": "get_products",
+            "parameters": {"type": "object", "properties": {"product_id": {"type": "string"}}, "required": ["product_id"]}
+        })];
+        assert!(validate_function_schema(&funcs).is_ok());
+    }
+}
@@ -4,12 +4,15 @@ use atomic_refcell::AtomicRefCell;
 use indexmap::IndexMap;
 
 use crate::{
-    ace_generator::{AgentResultEntry, NormalResultEntry},
-    base_api::BaseApi,
+    ace_generator::{AgentResultEntry, ChatMessage, NormalResultEntry, TraceEntry},
+    base_api::{BaseApi, ExecutionResult},
     evaluate_parse::FunctionCallHygienic,
     food_services::FoodPlatform,
     message::MessageApi,
-    parse_ast::{contains_tool_calls_fc, decode_function_list, decode_tool_call_format},
+    parse_ast::{
+        contains_tool_calls_fc, decode_function_list, decode_function_list_lenient,
+        decode_tool_call_format, extract_outermost_bracket_content,
+    },
     prompts::{
         base_prompt_en, multi_step_agent_prompt_system_en, multi_step_agent_prompt_system_fc_en,
         multi_step_agent_prompt_user_en, multi_step_agent_prompt_user_fc_en,
@@ -17,22 +20,44 @@ use crate::{
         multi_turn_agent_prompt_user_en, multi_turn_agent_prompt_user_fc_en,
         system_prompt_for_normal_data_en, system_prompt_for_normal_data_fc_en,
         system_prompt_for_preference_data_en, system_prompt_for_preference_data_fc_en,
-        system_prompt_for_special_data_en, travel_prompt_en, user_prompt_en,
+        system_prompt_for_special_data_en, system_prompt_for_special_data_fc_en, travel_prompt_en,
+        user_prompt_en,
         user_simulation_init_prompt_en, user_simulation_system_prompt_base_en,
         user_simulation_system_prompt_travel_en,
     },
     python_interface::{PythonResponse, PythonTask},
     reminder::ReminderApi,
     travel::Travel,
+    utils::JsonLinesWriter,
     world_state::WorldState,
 };
 
-use std::io::Write;
-
 pub enum ProblemStatus {
     Waiting,
     Executing,
 }
+
+/// A small set of normalized phrases that mean "end the dialogue", per the
+/// `finish conversation` instruction in the agent prompts (see `prompts.rs`).
+const FINISH_SIGNAL_PHRASES: &[&str] = &[
+    "finish conversation",
+    "finish the conversation",
+    "finish_conversation",
+];
+
+/// Whether `response` is a request to end the dialogue, checked line-by-line on a
+/// trimmed, lowercased copy of each line rather than a substring match anywhere in
+/// the text. This avoids both misfiring on a model explaining "I will now finish
+/// conversation cleanup" and missing variants like "FINISH_CONVERSATION" or
+/// "finish the conversation".
+pub fn is_finish_signal(response: &str) -> bool {
+    response.lines().any(|line| {
+        let normalized = line.trim().to_lowercase();
+        FINISH_SIGNAL_PHRASES
+            .iter()
+            .any(|phrase| normalized == *phrase)
+    })
+}
 /// Sender/recipient in dialogue history
 /// Multi-turn has 3 participants: User, Agent, Execution
 /// Multi-step has 2 participants: Agent, Execution (User only appears in initial message)
@@ -51,6 +76,39 @@ pub struct DialogueEntry {
     pub recipient: DialogueParticipant,
     /// Message content - can be string or list (execution results)
     pub message: String,
+    /// Wall-clock time this entry was recorded, used to profile the gap between
+    /// an agent prompt and its response across a multi-turn/multi-step episode
+    pub recorded_at: chrono::DateTime<chrono::Utc>,
+}
+
+impl DialogueEntry {
+    pub fn new(sender: DialogueParticipant, recipient: DialogueParticipant, message: String) -> Self {
+        DialogueEntry {
+            sender,
+            recipient,
+            message,
+            recorded_at: chrono::Utc::now(),
+        }
+    }
+}
+
+/// Configures the simulated "API server is experiencing high latency" transition
+/// perturbation injected into agent tasks: what message to synthesize and at which
+/// step (`num_steps`, 1-indexed) it should fire, instead of the fixed message
+/// hardcoded to always fire before the first real execution.
+#[derive(Clone, Debug)]
+pub struct TransitionPerturbation {
+    pub message: String,
+    pub after_step: usize,
+}
+
+impl Default for TransitionPerturbation {
+    fn default() -> Self {
+        TransitionPerturbation {
+            message: "The API server is experiencing high latency due to network issues. Please retry your request.".to_string(),
+            after_step: 1,
+        }
+    }
 }
 
 /// Unified agent task state for both multi-turn and multi-step scenarios
@@ -62,6 +120,9 @@ pub struct AgentProblemState {
     // immutable fields
     // Whether this problem has transition perturbation
     pub has_transition_perturbation: bool,
+    /// Message and trigger step for the transition perturbation; see `TransitionPerturbation`.
+    /// Only consulted while `has_transition_perturbation` is set.
+    pub transition_perturbation: TransitionPerturbation,
     // Whether the transition has been perturbed
     pub perturbed: bool,
     /// Initial configuration used to initialize WorldState (kept for reference/reset)
@@ -83,6 +144,12 @@ pub struct AgentProblemState {
     // pub inference_data: RefCell<String>,
     /// Function calls made during execution (milestones)
     pub mile_stones: Vec<String>,
+    /// Per-turn trace of (raw LLM response, parsed function calls, execution
+    /// results), recorded only while `trace_enabled` is set. Off by default;
+    /// enabled via [`AceProblem::set_enable_trace`] / [`AceGenerator::set_enable_trace`],
+    /// since most runs don't need the extra memory and output size.
+    pub trace: Vec<TraceEntry>,
+    trace_enabled: bool,
 }
 
 impl AgentProblemState {
@@ -96,19 +163,18 @@ impl AgentProblemState {
         world_state.populate_with_involved_classes(&involved_classes);
         Self {
             has_transition_perturbation,
+            transition_perturbation: TransitionPerturbation::default(),
             perturbed: false,
             initial_config,
             involved_classes,
             question: None, // multi-step doesn't need user simulation
             num_steps: 0,
             world_state,
-            dialogue_history: vec![DialogueEntry {
-                sender: DialogueParticipant::User,
-                recipient: DialogueParticipant::Agent,
-                message: question.to_string(),
-            }],
+            dialogue_history: vec![DialogueEntry::new(DialogueParticipant::User, DialogueParticipant::Agent, question.to_string())],
             // inference_data: RefCell::new(String::new()),
             mile_stones: Vec::new(),
+            trace: Vec::new(),
+            trace_enabled: false,
         }
     }
     pub fn new_multi_turn(
@@ -121,6 +187,7 @@ impl AgentProblemState {
         world_state.populate_with_involved_classes(&involved_classes);
         Self {
             has_transition_perturbation,
+            transition_perturbation: TransitionPerturbation::default(),
             perturbed: false,
             initial_config,
             involved_classes,
@@ -130,8 +197,50 @@ impl AgentProblemState {
             dialogue_history: Vec::new(), // needs to call api user to get started
             // inference_data: RefCell::new(String::new()),
             mile_stones: Vec::new(),
+            trace: Vec::new(),
+            trace_enabled: false,
         }
     }
+    /// Enables per-turn trace capture (see `trace`). Off by default.
+    pub fn set_trace_enabled(&mut self, enabled: bool) {
+        self.trace_enabled = enabled;
+    }
+    /// Overrides the transition-perturbation message and/or trigger step (see
+    /// `TransitionPerturbation`). Only takes effect while `has_transition_perturbation` is set.
+    pub fn set_transition_perturbation(&mut self, transition_perturbation: TransitionPerturbation) {
+        self.transition_perturbation = transition_perturbation;
+    }
+    /// Records one turn's (raw response, parsed function calls, execution results)
+    /// into `trace`, but only while trace capture is enabled, so a disabled run
+    /// doesn't pay for the extra `Clone`s.
+    fn record_trace_entry(
+        &mut self,
+        raw_response: String,
+        function_calls: Vec<FunctionCallHygienic>,
+        execution_results: Vec<ExecutionResult>,
+    ) {
+        if self.trace_enabled {
+            self.trace.push(TraceEntry {
+                raw_response,
+                function_calls,
+                execution_results,
+            });
+        }
+    }
+    pub fn turn_timestamps(&self) -> Vec<chrono::DateTime<chrono::Utc>> {
+        self.dialogue_history
+            .iter()
+            .map(|entry| entry.recorded_at)
+            .collect()
+    }
+    /// Total character count across every message in `dialogue_history`, used to
+    /// enforce a context budget independent of the turn-count limit.
+    pub fn dialogue_char_count(&self) -> usize {
+        self.dialogue_history
+            .iter()
+            .map(|entry| entry.message.len())
+            .sum()
+    }
     pub fn get_inference_message(&self) -> String {
         let mut inference_message = String::new();
         for entry in &self.dialogue_history {
@@ -145,6 +254,25 @@ impl AgentProblemState {
         inference_message
     }
 
+    /// Reconstructs `dialogue_history` as OpenAI-style chat messages: User→user,
+    /// Agent→assistant, Execution→tool.
+    pub fn chat_messages(&self) -> Vec<ChatMessage> {
+        self.dialogue_history
+            .iter()
+            .map(|entry| {
+                let role = match entry.sender {
+                    DialogueParticipant::User => "user",
+                    DialogueParticipant::Agent => "assistant",
+                    DialogueParticipant::Execution => "tool",
+                };
+                ChatMessage {
+                    role: role.to_string(),
+                    content: entry.message.clone(),
+                }
+            })
+            .collect()
+    }
+
     /// Returns true if the state requires an LLM call (user or agent response).
     /// Valid states for LLM call:
     /// - Empty history (initial)
@@ -182,6 +310,10 @@ pub struct SingleTurnProblemState {
     pub first_turn: bool,
     pub question: String,
     pub prev_llm_response: Option<String>,
+    /// The LLM's final response, captured once `handle_python_response` finalizes this
+    /// problem. `None` until then. Lets [`AceProblem::get_result`] hand back the answer
+    /// without re-parsing the output JSONL.
+    pub result: Option<String>,
 }
 
 pub enum AceProblemState {
@@ -204,11 +336,88 @@ pub struct AceProblem {
     pub function: Vec<serde_json::Value>,
     pub state: AceProblemState,
     pub output_file: Arc<AtomicRefCell<std::fs::File>>,
+    /// Optional sink for streaming dialogue events (as JSONL) for live monitoring.
+    /// Off by default; set via `set_dialogue_event_sink`.
+    #[allow(clippy::type_complexity)]
+    pub dialogue_event_sink: Option<Arc<dyn Fn(&str) + Send + Sync>>,
+    /// Total character budget across `AgentProblemState::dialogue_history` for
+    /// multi-turn/multi-step problems. `None` means use `DEFAULT_MAX_DIALOGUE_CHARS`;
+    /// set via `set_max_dialogue_chars`. This is independent of `MAX_TURNS`: a
+    /// conversation can blow the character budget well before it blows the turn count.
+    pub max_dialogue_chars: Option<usize>,
+    /// Number of times this problem has been re-queued after `handle_python_response`
+    /// returned `false` without completing. A backstop against livelock for cases the
+    /// normal per-state budget (e.g. `MAX_TURNS`) doesn't cover, such as the Python side
+    /// repeatedly marking responses as retries; see [`AceProblem::attempts_exhausted`].
+    pub attempt_count: usize,
 }
 
 const MAX_TURNS: usize = 20;
+const MAX_ATTEMPTS: usize = 2 * MAX_TURNS;
+const DEFAULT_MAX_DIALOGUE_CHARS: usize = 60_000;
 
 impl AceProblem {
+    /// Registers a callback invoked with a JSONL-formatted dialogue event
+    /// (`{"id", "step", "sender", "recipient", "message"}`) every time a new
+    /// entry is appended to an agent problem's dialogue history. Off by default.
+    #[allow(clippy::type_complexity)]
+    pub fn set_dialogue_event_sink(&mut self, sink: Arc<dyn Fn(&str) + Send + Sync>) {
+        self.dialogue_event_sink = Some(sink);
+    }
+
+    /// Overrides the total dialogue-history character budget (see `max_dialogue_chars`).
+    pub fn set_max_dialogue_chars(&mut self, max_chars: usize) {
+        self.max_dialogue_chars = Some(max_chars);
+    }
+
+    /// Enables per-turn trace capture for agent problems (see `AgentProblemState::trace`).
+    /// No-op for single-turn problems, which don't have a turn-by-turn state to trace.
+    pub fn set_enable_trace(&mut self, enable: bool) {
+        match &mut self.state {
+            AceProblemState::MultiTurn(agent_problem_state)
+            | AceProblemState::MultiStep(agent_problem_state) => {
+                agent_problem_state.set_trace_enabled(enable);
+            }
+            AceProblemState::SingleTurnNormal(_)
+            | AceProblemState::SingleTurnPreference(_)
+            | AceProblemState::SingleTurnSpecial(_) => {}
+        }
+    }
+
+    /// Overrides the transition-perturbation message and trigger step for agent problems
+    /// (see `TransitionPerturbation`). No-op for single-turn problems, whose fixed retry
+    /// prompt (see `build_python_task`) doesn't go through this config.
+    pub fn set_transition_perturbation(&mut self, transition_perturbation: TransitionPerturbation) {
+        match &mut self.state {
+            AceProblemState::MultiTurn(agent_problem_state)
+            | AceProblemState::MultiStep(agent_problem_state) => {
+                agent_problem_state.set_transition_perturbation(transition_perturbation);
+            }
+            AceProblemState::SingleTurnNormal(_)
+            | AceProblemState::SingleTurnPreference(_)
+            | AceProblemState::SingleTurnSpecial(_) => {}
+        }
+    }
+
+    #[allow(clippy::type_complexity)]
+    fn emit_dialogue_event(
+        id: &str,
+        sink: &Option<Arc<dyn Fn(&str) + Send + Sync>>,
+        step: usize,
+        entry: &DialogueEntry,
+    ) {
+        if let Some(sink) = sink {
+            let event = serde_json::json!({
+                "id": id,
+                "step": step,
+                "sender": format!("{:?}", entry.sender),
+                "recipient": format!("{:?}", entry.recipient),
+                "message": entry.message,
+            });
+            sink(&event.to_string());
+        }
+    }
+
     /// The LLM task is going to be executed by python, and it will produce a response with the same identifier
     /// after receiving the response, the internal state will be updated accordingly
     pub fn build_python_task(&self, enable_fc: bool) -> PythonTask {
@@ -291,23 +500,43 @@ impl AceProblem {
                     tools,
                 }
             }
-            AceProblemState::SingleTurnSpecial(_single_turn_state) => {
-                // let function_str =
-                //     serde_json::to_string(&self.function).expect("failed to serialize function");
-                // let system_prompt = system_prompt_for_special_data_en(
-                //     single_turn_state.time.as_ref().unwrap(),
-                //     &function_str,
-                // );
-                // let user_prompt = user_prompt_en(&single_turn_state.question);
-                // PythonTask {
-                //     identifier: self.identifier.clone(),
-                //     system_prompt,
-                //     user_prompt,
-                //     role: "assistant".to_string(),
-                // }
-                panic!(
-                    "Single-turn special problems are not supported in the current implementation"
-                );
+            AceProblemState::SingleTurnSpecial(single_turn_state) => {
+                let system_prompt = if enable_fc {
+                    system_prompt_for_special_data_fc_en(single_turn_state.time.as_ref().unwrap())
+                } else {
+                    let function_str =
+                        serde_json::to_string(&self.function).expect("failed to serialize function");
+                    system_prompt_for_special_data_en(
+                        single_turn_state.time.as_ref().unwrap(),
+                        &function_str,
+                    )
+                };
+                let user_prompt = if single_turn_state.has_transition_perturbation
+                    && !single_turn_state.first_turn
+                {
+                    let mut user_prompt = user_prompt_en(&single_turn_state.question);
+                    let Some(prev_response) = &single_turn_state.prev_llm_response else {
+                        panic!("Single-turn special problem missing previous LLM response");
+                    };
+                    println!("'Time-out, retry' fired for special");
+                    user_prompt.push_str(format!("\nassistant: {}\ntool: The API server is experiencing high latency due to network issues. Please retry your request.\nassistant: ", prev_response).as_str());
+                    user_prompt
+                } else {
+                    assert!(single_turn_state.first_turn);
+                    user_prompt_en(&single_turn_state.question)
+                };
+                let tools = if enable_fc {
+                    Some(self.function.clone())
+                } else {
+                    None
+                };
+                PythonTask {
+                    identifier: self.identifier.clone(),
+                    system_prompt,
+                    user_prompt,
+                    role: "assistant".to_string(),
+                    tools,
+                }
             }
             AceProblemState::MultiStep(agent_problem_state) => {
                 // Assert: state requires LLM response (not pending execution)
@@ -521,6 +750,9 @@ impl AceProblem {
         assert!(self.identifier == response.identifier);
         // the status will be updated outside the function
         // this function is to update the internal state based on the response
+        let id = self.id.clone();
+        let dialogue_event_sink = self.dialogue_event_sink.clone();
+        let max_dialogue_chars = self.max_dialogue_chars.unwrap_or(DEFAULT_MAX_DIALOGUE_CHARS);
         match &mut self.state {
             AceProblemState::SingleTurnNormal(single_turn_state)
             | AceProblemState::SingleTurnPreference(single_turn_state)
@@ -532,15 +764,13 @@ impl AceProblem {
                     single_turn_state.prev_llm_response = Some(response.response.clone());
                     return false;
                 }
+                single_turn_state.result = Some(response.response.clone());
                 let normal_result_entry = NormalResultEntry {
                     id: self.id.clone(),
                     result: response.response,
                 };
-                let entry_serialized = serde_json::to_string(&normal_result_entry)
-                    .expect("failed to serialize normal result entry");
-                let mut file_ref = self.output_file.borrow_mut();
-
-                writeln!(file_ref, "{}", entry_serialized)
+                JsonLinesWriter::new(self.output_file.clone())
+                    .write_line(&normal_result_entry)
                     .expect("failed to write normal result entry");
                 true
             }
@@ -555,14 +785,20 @@ impl AceProblem {
                     "handle_python_response called but state was pending execution"
                 );
 
-                agent_problem_state.num_steps += 1;
-                if agent_problem_state.num_steps > MAX_TURNS {
-                    // to do: finalize and write to file
-                    Self::agent_finish_conversation(
-                        self.id.clone(),
-                        agent_problem_state,
-                        &self.output_file,
-                    );
+                if response.is_retry {
+                    // this response replaces the one already recorded for the current
+                    // step, so the step count must not move and the stale entry is dropped
+                    agent_problem_state.dialogue_history.pop();
+                } else {
+                    agent_problem_state.num_steps += 1;
+                }
+                if Self::finish_if_over_budget(
+                    &id,
+                    &dialogue_event_sink,
+                    max_dialogue_chars,
+                    agent_problem_state,
+                    &self.output_file,
+                ) {
                     return true;
                 }
                 // when receiving the response, the last recipient must be the agent
@@ -572,14 +808,11 @@ impl AceProblem {
                     .expect("In multi-step, dialogue history is initialized with user question")
                     .recipient;
                 assert!(matches!(last_recipient, DialogueParticipant::Agent));
-                let new_history_entry = DialogueEntry {
-                    sender: DialogueParticipant::Agent,
-                    recipient: DialogueParticipant::Execution,
-                    message: response.response.clone(),
-                };
+                let new_history_entry = DialogueEntry::new(DialogueParticipant::Agent, DialogueParticipant::Execution, response.response.clone());
+                Self::emit_dialogue_event(&id, &dialogue_event_sink, agent_problem_state.num_steps, &new_history_entry);
                 agent_problem_state.dialogue_history.push(new_history_entry);
 
-                if response.response.contains("finish conversation") {
+                if is_finish_signal(&response.response) {
                     // to do: finalize and write to file
                     Self::agent_finish_conversation(
                         self.id.clone(),
@@ -596,23 +829,17 @@ impl AceProblem {
                         Err(e) => {
                             // In FC mode, if no <tool_call> tags found, it's likely a question
                             if !contains_tool_calls_fc(&response.response) {
-                                let new_history_entry = DialogueEntry {
-                                    sender: DialogueParticipant::Execution,
-                                    recipient: DialogueParticipant::Agent,
-                                    message: "Please do not ask me any questions, use the known conditions to solve the problem".to_string(),
-                                };
+                                let new_history_entry = DialogueEntry::new(DialogueParticipant::Execution, DialogueParticipant::Agent, "Please do not ask me any questions, use the known conditions to solve the problem".to_string());
                                 println!(
                                     "The agent is trying to ask a question: {}",
                                     response.response
                                 );
-                                agent_problem_state.dialogue_history.push(new_history_entry);
+                                Self::emit_dialogue_event(&id, &dialogue_event_sink, agent_problem_state.num_steps, &new_history_entry);
+                agent_problem_state.dialogue_history.push(new_history_entry);
                             } else {
-                                let new_history_entry = DialogueEntry {
-                                    sender: DialogueParticipant::Execution,
-                                    recipient: DialogueParticipant::Agent,
-                                    message: format!("Failed to parse function calls: {}", e),
-                                };
-                                agent_problem_state.dialogue_history.push(new_history_entry);
+                                let new_history_entry = DialogueEntry::new(DialogueParticipant::Execution, DialogueParticipant::Agent, format!("Failed to parse function calls: {}", e));
+                                Self::emit_dialogue_event(&id, &dialogue_event_sink, agent_problem_state.num_steps, &new_history_entry);
+                agent_problem_state.dialogue_history.push(new_history_entry);
                             }
                             return false;
                         }
@@ -623,23 +850,17 @@ impl AceProblem {
                         Ok(funcs) => funcs,
                         Err(e) => {
                             if !response.response.starts_with("[") {
-                                let new_history_entry = DialogueEntry {
-                                    sender: DialogueParticipant::Execution,
-                                    recipient: DialogueParticipant::Agent,
-                                    message: "Please do not ask me any questions, use the known conditions to solve the problem".to_string(),
-                                };
+                                let new_history_entry = DialogueEntry::new(DialogueParticipant::Execution, DialogueParticipant::Agent, "Please do not ask me any questions, use the known conditions to solve the problem".to_string());
                                 println!(
                                     "The agent is trying to ask a question: {}",
                                     response.response
                                 );
-                                agent_problem_state.dialogue_history.push(new_history_entry);
+                                Self::emit_dialogue_event(&id, &dialogue_event_sink, agent_problem_state.num_steps, &new_history_entry);
+                agent_problem_state.dialogue_history.push(new_history_entry);
                             } else {
-                                let new_history_entry = DialogueEntry {
-                                    sender: DialogueParticipant::Execution,
-                                    recipient: DialogueParticipant::Agent,
-                                    message: format!("Failed to parse function calls: {}", e),
-                                };
-                                agent_problem_state.dialogue_history.push(new_history_entry);
+                                let new_history_entry = DialogueEntry::new(DialogueParticipant::Execution, DialogueParticipant::Agent, format!("Failed to parse function calls: {}", e));
+                                Self::emit_dialogue_event(&id, &dialogue_event_sink, agent_problem_state.num_steps, &new_history_entry);
+                agent_problem_state.dialogue_history.push(new_history_entry);
                             }
                             return false;
                         }
@@ -648,36 +869,37 @@ impl AceProblem {
                 agent_problem_state
                     .mile_stones
                     .push(response.response.clone());
-                if agent_problem_state.has_transition_perturbation && !agent_problem_state.perturbed
+                if agent_problem_state.has_transition_perturbation
+                    && !agent_problem_state.perturbed
+                    && agent_problem_state.num_steps >= agent_problem_state.transition_perturbation.after_step
                 {
                     agent_problem_state.perturbed = true;
                     // synthesize a dialogue entry from execution to agent
-                    let new_history_entry = DialogueEntry {
-                        sender: DialogueParticipant::Execution,
-                        recipient: DialogueParticipant::Agent,
-                        message: "The API server is experiencing high latency due to network issues. Please retry your request.".to_string(),
-                    };
+                    let new_history_entry = DialogueEntry::new(DialogueParticipant::Execution, DialogueParticipant::Agent, agent_problem_state.transition_perturbation.message.clone());
                     println!("'Time-out, retry' fired for multi-step");
-                    agent_problem_state.dialogue_history.push(new_history_entry);
+                    Self::emit_dialogue_event(&id, &dialogue_event_sink, agent_problem_state.num_steps, &new_history_entry);
+                agent_problem_state.dialogue_history.push(new_history_entry);
                 } else {
                     let execution_results = agent_problem_state
                         .world_state
                         .execute_function_calls(&function_call_list);
+                    agent_problem_state.record_trace_entry(
+                        response.response.clone(),
+                        function_call_list.clone(),
+                        execution_results.clone(),
+                    );
                     let execution_message = serde_json::to_string(&execution_results)
                         .expect("failed to serialize execution results");
-                    let new_history_entry = DialogueEntry {
-                        sender: DialogueParticipant::Execution,
-                        recipient: DialogueParticipant::Agent,
-                        message: execution_message,
-                    };
-                    agent_problem_state.dialogue_history.push(new_history_entry);
+                    let new_history_entry = DialogueEntry::new(DialogueParticipant::Execution, DialogueParticipant::Agent, execution_message);
+                    Self::emit_dialogue_event(&id, &dialogue_event_sink, agent_problem_state.num_steps, &new_history_entry);
+                agent_problem_state.dialogue_history.push(new_history_entry);
                 }
                 // println!("conversation: {}", agent_problem_state.get_inference_message());
                 println!(
                     "Problem {} turn {} response: {}",
                     self.id, agent_problem_state.num_steps, response.response
                 );
-                
+
                 // Post-condition: state should be ready for next LLM call
                 assert!(
                     agent_problem_state.needs_llm_response(),
@@ -696,25 +918,31 @@ impl AceProblem {
                     "handle_python_response called but state was pending execution"
                 );
 
-                agent_problem_state.num_steps += 1;
+                if response.is_retry {
+                    // this response replaces the one already recorded for the current
+                    // step, so the step count must not move and the stale entry is dropped
+                    if !agent_problem_state.dialogue_history.is_empty() {
+                        agent_problem_state.dialogue_history.pop();
+                    }
+                } else {
+                    agent_problem_state.num_steps += 1;
+                }
 
-                if agent_problem_state.num_steps > MAX_TURNS {
-                    Self::agent_finish_conversation(
-                        self.id.clone(),
-                        agent_problem_state,
-                        &self.output_file,
-                    );
+                if Self::finish_if_over_budget(
+                    &id,
+                    &dialogue_event_sink,
+                    max_dialogue_chars,
+                    agent_problem_state,
+                    &self.output_file,
+                ) {
                     return true;
                 }
 
                 if agent_problem_state.dialogue_history.is_empty() {
                     // This is the user's initial message
-                    let new_history_entry = DialogueEntry {
-                        sender: DialogueParticipant::User,
-                        recipient: DialogueParticipant::Agent,
-                        message: response.response.clone(),
-                    };
-                    agent_problem_state.dialogue_history.push(new_history_entry);
+                    let new_history_entry = DialogueEntry::new(DialogueParticipant::User, DialogueParticipant::Agent, response.response.clone());
+                    Self::emit_dialogue_event(&id, &dialogue_event_sink, agent_problem_state.num_steps, &new_history_entry);
+                agent_problem_state.dialogue_history.push(new_history_entry);
                     // Post-condition: now agent needs to respond
                     assert!(
                         agent_problem_state.needs_llm_response(),
@@ -732,14 +960,11 @@ impl AceProblem {
                 match last_recipient {
                     DialogueParticipant::User => {
                         // User responded, add to history
-                        let new_history_entry = DialogueEntry {
-                            sender: DialogueParticipant::User,
-                            recipient: DialogueParticipant::Agent,
-                            message: response.response.clone(),
-                        };
-                        agent_problem_state.dialogue_history.push(new_history_entry);
+                        let new_history_entry = DialogueEntry::new(DialogueParticipant::User, DialogueParticipant::Agent, response.response.clone());
+                        Self::emit_dialogue_event(&id, &dialogue_event_sink, agent_problem_state.num_steps, &new_history_entry);
+                agent_problem_state.dialogue_history.push(new_history_entry);
 
-                        if response.response.contains("finish conversation") {
+                        if is_finish_signal(&response.response) {
                             Self::agent_finish_conversation(
                                 self.id.clone(),
                                 agent_problem_state,
@@ -756,14 +981,11 @@ impl AceProblem {
                     }
                     DialogueParticipant::Agent | DialogueParticipant::Execution => {
                         // Agent responded (after receiving from user or execution)
-                        let new_history_entry = DialogueEntry {
-                            sender: DialogueParticipant::Agent,
-                            recipient: DialogueParticipant::Execution,
-                            message: response.response.clone(),
-                        };
-                        agent_problem_state.dialogue_history.push(new_history_entry);
+                        let new_history_entry = DialogueEntry::new(DialogueParticipant::Agent, DialogueParticipant::Execution, response.response.clone());
+                        Self::emit_dialogue_event(&id, &dialogue_event_sink, agent_problem_state.num_steps, &new_history_entry);
+                agent_problem_state.dialogue_history.push(new_history_entry);
 
-                        if response.response.contains("finish conversation") {
+                        if is_finish_signal(&response.response) {
                             Self::agent_finish_conversation(
                                 self.id.clone(),
                                 agent_problem_state,
@@ -794,22 +1016,23 @@ impl AceProblem {
                                             "MultiTurn: after agent message to user, state should need LLM response"
                                         );
                                     } else {
-                                        let new_history_entry = DialogueEntry {
-                                            sender: DialogueParticipant::Execution,
-                                            recipient: DialogueParticipant::Agent,
-                                            message: format!("Failed to parse function calls: {}", e),
-                                        };
-                                        agent_problem_state.dialogue_history.push(new_history_entry);
+                                        let new_history_entry = DialogueEntry::new(DialogueParticipant::Execution, DialogueParticipant::Agent, format!("Failed to parse function calls: {}", e));
+                                        Self::emit_dialogue_event(&id, &dialogue_event_sink, agent_problem_state.num_steps, &new_history_entry);
+                agent_problem_state.dialogue_history.push(new_history_entry);
                                     }
                                     return false;
                                 }
                             }
                         } else {
-                            // Non-FC mode: parse Python AST format [ApiName(key='value')]
-                            match decode_function_list(&response.response) {
+                            // Non-FC mode: parse Python AST format [ApiName(key='value')], tolerating
+                            // surrounding prose/markdown fences so a valid call isn't misread as
+                            // conversational text just because it's wrapped or indented.
+                            match decode_function_list_lenient(&response.response) {
                                 Ok(funcs) => funcs,
                                 Err(e) => {
-                                    if !response.response.starts_with("[") {
+                                    if extract_outermost_bracket_content(response.response.trim()).is_none() {
+                                        // No bracketed call structure anywhere in the response, so this is
+                                        // genuinely conversational text, not a malformed call.
                                         // Agent is not making a function call, relay message to user
                                         // Change recipient from Execution to User
                                         agent_problem_state
@@ -824,12 +1047,9 @@ impl AceProblem {
                                             "MultiTurn: after agent message to user, state should need LLM response"
                                         );
                                     } else {
-                                        let new_history_entry = DialogueEntry {
-                                            sender: DialogueParticipant::Execution,
-                                            recipient: DialogueParticipant::Agent,
-                                            message: format!("Failed to parse function calls: {}", e),
-                                        };
-                                        agent_problem_state.dialogue_history.push(new_history_entry);
+                                        let new_history_entry = DialogueEntry::new(DialogueParticipant::Execution, DialogueParticipant::Agent, format!("Failed to parse function calls: {}", e));
+                                        Self::emit_dialogue_event(&id, &dialogue_event_sink, agent_problem_state.num_steps, &new_history_entry);
+                agent_problem_state.dialogue_history.push(new_history_entry);
                                     }
                                     return false;
                                 }
@@ -843,29 +1063,29 @@ impl AceProblem {
 
                         if agent_problem_state.has_transition_perturbation
                             && !agent_problem_state.perturbed
+                            && agent_problem_state.num_steps >= agent_problem_state.transition_perturbation.after_step
                         {
                             agent_problem_state.perturbed = true;
                             // synthesize a dialogue entry from execution to agent
-                            let new_history_entry = DialogueEntry {
-                                sender: DialogueParticipant::Execution,
-                                recipient: DialogueParticipant::Agent,
-                                message: "The API server is experiencing high latency due to network issues. Please retry your request.".to_string(),
-                            };
+                            let new_history_entry = DialogueEntry::new(DialogueParticipant::Execution, DialogueParticipant::Agent, agent_problem_state.transition_perturbation.message.clone());
                             println!("'Time-out, retry' fired for multi-step");
-                            agent_problem_state.dialogue_history.push(new_history_entry);
+                            Self::emit_dialogue_event(&id, &dialogue_event_sink, agent_problem_state.num_steps, &new_history_entry);
+                agent_problem_state.dialogue_history.push(new_history_entry);
                         } else {
                             let execution_results = agent_problem_state
                                 .world_state
                                 .execute_function_calls(&function_call_list);
+                            agent_problem_state.record_trace_entry(
+                                response.response.clone(),
+                                function_call_list.clone(),
+                                execution_results.clone(),
+                            );
                             let execution_message = serde_json::to_string(&execution_results)
                                 .expect("failed to serialize execution results");
 
-                            let new_history_entry = DialogueEntry {
-                                sender: DialogueParticipant::Execution,
-                                recipient: DialogueParticipant::Agent,
-                                message: execution_message,
-                            };
-                            agent_problem_state.dialogue_history.push(new_history_entry);
+                            let new_history_entry = DialogueEntry::new(DialogueParticipant::Execution, DialogueParticipant::Agent, execution_message);
+                            Self::emit_dialogue_event(&id, &dialogue_event_sink, agent_problem_state.num_steps, &new_history_entry);
+                agent_problem_state.dialogue_history.push(new_history_entry);
                         }
 
                         // println!("conversation: {}", agent_problem_state.get_inference_message());
@@ -873,12 +1093,13 @@ impl AceProblem {
                             "Problem {} turn {} response: {}",
                             self.id, agent_problem_state.num_steps, response.response
                         );
-                        if agent_problem_state.num_steps > MAX_TURNS {
-                            Self::agent_finish_conversation(
-                                self.id.clone(),
-                                agent_problem_state,
-                                &self.output_file,
-                            );
+                        if Self::finish_if_over_budget(
+                            &id,
+                            &dialogue_event_sink,
+                            max_dialogue_chars,
+                            agent_problem_state,
+                            &self.output_file,
+                        ) {
                             return true;
                         }
                         // Post-condition: now agent needs to respond to execution result
@@ -893,6 +1114,71 @@ impl AceProblem {
         }
     }
 
+    /// Checks the turn-count and dialogue-character budgets and, if either is
+    /// exceeded, force-finishes the conversation (recording a note in the history
+    /// when it was the character budget that tripped, since that's not otherwise
+    /// visible in the output). Returns whether the conversation was finished.
+    #[allow(clippy::type_complexity)]
+    fn finish_if_over_budget(
+        id: &str,
+        dialogue_event_sink: &Option<Arc<dyn Fn(&str) + Send + Sync>>,
+        max_dialogue_chars: usize,
+        agent_problem_state: &mut AgentProblemState,
+        output_file: &Arc<AtomicRefCell<std::fs::File>>,
+    ) -> bool {
+        if agent_problem_state.num_steps > MAX_TURNS {
+            Self::agent_finish_conversation(id.to_string(), agent_problem_state, output_file);
+            return true;
+        }
+        if agent_problem_state.dialogue_char_count() > max_dialogue_chars {
+            let new_history_entry = DialogueEntry::new(
+                DialogueParticipant::Execution,
+                DialogueParticipant::Agent,
+                "Context budget exceeded: dialogue history exceeded the character budget for this conversation.".to_string(),
+            );
+            Self::emit_dialogue_event(id, dialogue_event_sink, agent_problem_state.num_steps, &new_history_entry);
+            agent_problem_state.dialogue_history.push(new_history_entry);
+            Self::agent_finish_conversation(id.to_string(), agent_problem_state, output_file);
+            return true;
+        }
+        false
+    }
+
+    /// True once this problem has been re-queued `MAX_ATTEMPTS` times without
+    /// completing. `AceGenerator::receive_response_helper` checks this before
+    /// re-queuing and force-finalizes instead once it's true.
+    pub fn attempts_exhausted(&self) -> bool {
+        self.attempt_count >= MAX_ATTEMPTS
+    }
+
+    /// Finalizes a problem that exhausted its attempt budget: writes whatever partial
+    /// result already exists (the agent dialogue so far, or an empty single-turn
+    /// result) so the run can proceed instead of looping on a problem that never
+    /// completes on its own.
+    pub fn force_finalize(&mut self) {
+        match &self.state {
+            AceProblemState::SingleTurnNormal(_)
+            | AceProblemState::SingleTurnPreference(_)
+            | AceProblemState::SingleTurnSpecial(_) => {
+                let normal_result_entry = NormalResultEntry {
+                    id: self.id.clone(),
+                    result: String::new(),
+                };
+                JsonLinesWriter::new(self.output_file.clone())
+                    .write_line(&normal_result_entry)
+                    .expect("failed to write normal result entry");
+            }
+            AceProblemState::MultiStep(agent_problem_state)
+            | AceProblemState::MultiTurn(agent_problem_state) => {
+                Self::agent_finish_conversation(
+                    self.id.clone(),
+                    agent_problem_state,
+                    &self.output_file,
+                );
+            }
+        }
+    }
+
     fn agent_finish_conversation(
         id: String,
         agent_problem_state: &AgentProblemState,
@@ -914,18 +1200,859 @@ impl AceProblem {
             final_world_state: agent_problem_state.world_state.clone(),
             output_function_calls: agent_problem_state.mile_stones.clone(),
             conversation: agent_problem_state.get_inference_message(),
+            chat_messages: agent_problem_state.chat_messages(),
+            turn_timestamps: agent_problem_state.turn_timestamps(),
+            trace: agent_problem_state.trace.clone(),
         };
-        let entry_serialized = serde_json::to_string(&agent_result_entry)
-            .expect("failed to serialize agent result entry");
-        let mut file_ref = output_file.borrow_mut();
-        writeln!(file_ref, "{}", entry_serialized).expect("failed to write agent result entry");
+        JsonLinesWriter::new(output_file.clone())
+            .write_line(&agent_result_entry)
+            .expect("failed to write agent result entry");
     }
 
-    /// Get the LLM response result for completed problems
-    /// This is used for evaluation/output
+    /// Get the LLM response result for completed single-turn problems.
+    /// `None` for agent problems (`MultiTurn`/`MultiStep`), whose results are written
+    /// straight to `output_file` via [`Self::agent_finish_conversation`] instead of being
+    /// kept in memory, and for single-turn problems that haven't finished yet.
     pub fn get_result(&self) -> Option<&str> {
-        // For single-turn problems, the result is stored after receive_python_response
-        // Currently we don't store it, but this method provides the interface
-        None
+        match &self.state {
+            AceProblemState::SingleTurnNormal(single_turn_state)
+            | AceProblemState::SingleTurnPreference(single_turn_state)
+            | AceProblemState::SingleTurnSpecial(single_turn_state) => {
+                single_turn_state.result.as_deref()
+            }
+            AceProblemState::MultiStep(_) | AceProblemState::MultiTurn(_) => None,
+        }
+    }
+}
+
+/// A throwaway file for `AceProblem::output_file`, since most of the tests below
+/// never inspect what gets written, only the in-memory state; `tag` just keeps the
+/// scratch filenames distinguishable across test modules for easier debugging.
+#[cfg(test)]
+fn scratch_output_file(tag: &str) -> std::fs::File {
+    static COUNTER: std::sync::atomic::AtomicUsize = std::sync::atomic::AtomicUsize::new(0);
+    let n = COUNTER.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+    let path = std::env::temp_dir().join(format!("ace_problem_{}_test_{}_{}.jsonl", tag, std::process::id(), n));
+    std::fs::OpenOptions::new()
+        .create(true)
+        .write(true)
+        .truncate(true)
+        .open(path)
+        .expect("failed to create scratch output file")
+}
+
+#[cfg(test)]
+mod retry_bookkeeping_tests {
+    use super::*;
+
+    fn new_multi_turn_problem() -> AceProblem {
+        let agent_state = AgentProblemState::new_multi_turn(
+            WorldState::default(),
+            vec!["MessageApi".to_string()],
+            "Ask the agent something, then answer its follow-up question",
+            false,
+        );
+        AceProblem {
+            identifier: "test_identifier".to_string(),
+            perturbation_type: "base".to_string(),
+            dataset_name: "test".to_string(),
+            id: "test_id".to_string(),
+            status: ProblemStatus::Waiting,
+            question: "Ask the agent something, then answer its follow-up question".to_string(),
+            function: Vec::new(),
+            state: AceProblemState::MultiTurn(agent_state),
+            output_file: Arc::new(AtomicRefCell::new(scratch_output_file("test_output"))),
+            dialogue_event_sink: None,
+            max_dialogue_chars: None,
+            attempt_count: 0,
+        }
+    }
+
+    fn multi_turn_snapshot(problem: &AceProblem) -> (usize, usize) {
+        match &problem.state {
+            AceProblemState::MultiTurn(agent_state) => {
+                (agent_state.num_steps, agent_state.dialogue_history.len())
+            }
+            _ => panic!("expected MultiTurn state"),
+        }
+    }
+
+    fn non_retry(response: &str) -> PythonResponse {
+        PythonResponse {
+            identifier: "test_identifier".to_string(),
+            response: response.to_string(),
+            is_retry: false,
+        }
+    }
+
+    #[test]
+    fn retry_response_leaves_step_count_and_history_length_unchanged() {
+        let mut problem = new_multi_turn_problem();
+
+        // User's opening message.
+        problem.handle_python_response(non_retry("I have a question about my account."), false);
+        // Agent asks a clarifying question instead of calling a function, which
+        // flips the last entry's recipient to User rather than appending one.
+        problem.handle_python_response(non_retry("Sure, what's your user id?"), false);
+        // User answers; this is the response we're about to retry.
+        problem.handle_python_response(non_retry("It's user1."), false);
+
+        let before_retry = multi_turn_snapshot(&problem);
+
+        problem.handle_python_response(
+            PythonResponse {
+                identifier: "test_identifier".to_string(),
+                response: "It's actually user2.".to_string(),
+                is_retry: true,
+            },
+            false,
+        );
+
+        let after_retry = multi_turn_snapshot(&problem);
+
+        assert_eq!(after_retry, before_retry, "a retry should replace the prior dialogue entry in place, not grow the history or advance num_steps");
+    }
+}
+
+#[cfg(test)]
+mod dialogue_event_sink_tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    #[test]
+    fn sink_captures_streamed_events_in_order_for_a_short_episode() {
+        let agent_state = AgentProblemState::new_multi_step(
+            WorldState::default(),
+            vec!["MessageApi".to_string()],
+            "Send a greeting to Frank",
+            false,
+        );
+        let mut problem = AceProblem {
+            identifier: "test_identifier".to_string(),
+            perturbation_type: "base".to_string(),
+            dataset_name: "test".to_string(),
+            id: "test_id".to_string(),
+            status: ProblemStatus::Waiting,
+            question: "Send a greeting to Frank".to_string(),
+            function: Vec::new(),
+            state: AceProblemState::MultiStep(agent_state),
+            output_file: Arc::new(AtomicRefCell::new(scratch_output_file("sink_test"))),
+            dialogue_event_sink: None,
+            max_dialogue_chars: None,
+            attempt_count: 0,
+        };
+
+        let events: Arc<Mutex<Vec<String>>> = Arc::new(Mutex::new(Vec::new()));
+        let events_for_sink = events.clone();
+        problem.set_dialogue_event_sink(Arc::new(move |event| {
+            events_for_sink.lock().unwrap().push(event.to_string());
+        }));
+
+        problem.handle_python_response(
+            PythonResponse {
+                identifier: "test_identifier".to_string(),
+                response: "[send_message(sender_name='Eve', receiver_name='Frank', message='hi')]".to_string(),
+                is_retry: false,
+            },
+            false,
+        );
+
+        let captured = events.lock().unwrap();
+        // One call that parses and executes successfully appends two dialogue
+        // entries (the agent's call, then the execution result).
+        assert_eq!(captured.len(), 2);
+        let parsed: Vec<serde_json::Value> = captured
+            .iter()
+            .map(|event| serde_json::from_str(event).unwrap())
+            .collect();
+        assert_eq!(parsed[0]["sender"], "Agent");
+        assert_eq!(parsed[0]["recipient"], "Execution");
+        assert_eq!(parsed[1]["sender"], "Execution");
+        assert_eq!(parsed[1]["recipient"], "Agent");
+        for event in &parsed {
+            assert_eq!(event["id"], "test_id");
+        }
+    }
+}
+
+#[cfg(test)]
+mod turn_timestamp_tests {
+    use super::*;
+
+    #[test]
+    fn timestamps_are_monotonically_non_decreasing_across_an_episode() {
+        let agent_state = AgentProblemState::new_multi_step(
+            WorldState::default(),
+            vec!["MessageApi".to_string()],
+            "Send a greeting to Frank",
+            false,
+        );
+        let mut problem = AceProblem {
+            identifier: "test_identifier".to_string(),
+            perturbation_type: "base".to_string(),
+            dataset_name: "test".to_string(),
+            id: "test_id".to_string(),
+            status: ProblemStatus::Waiting,
+            question: "Send a greeting to Frank".to_string(),
+            function: Vec::new(),
+            state: AceProblemState::MultiStep(agent_state),
+            output_file: Arc::new(AtomicRefCell::new(scratch_output_file("timestamp_test"))),
+            dialogue_event_sink: None,
+            max_dialogue_chars: None,
+            attempt_count: 0,
+        };
+
+        problem.handle_python_response(
+            PythonResponse {
+                identifier: "test_identifier".to_string(),
+                response: "[send_message(sender_name='Eve', receiver_name='Frank', message='hi')]".to_string(),
+                is_retry: false,
+            },
+            false,
+        );
+
+        let timestamps = match &problem.state {
+            AceProblemState::MultiStep(agent_state) => agent_state.turn_timestamps(),
+            _ => panic!("expected MultiStep state"),
+        };
+        assert!(timestamps.len() >= 3, "expected at least the opening entry plus the call/result pair");
+        for (a, b) in timestamps.iter().zip(timestamps.iter().skip(1)) {
+            assert!(a <= b, "timestamps must be monotonically non-decreasing");
+        }
+    }
+}
+
+#[cfg(test)]
+mod dialogue_char_budget_tests {
+    use super::*;
+
+    fn scratch_output_path() -> std::path::PathBuf {
+        static COUNTER: std::sync::atomic::AtomicUsize = std::sync::atomic::AtomicUsize::new(0);
+        let n = COUNTER.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+        std::env::temp_dir().join(format!("ace_problem_budget_test_{}_{}.jsonl", std::process::id(), n))
+    }
+
+    #[test]
+    fn a_tiny_character_budget_force_finishes_the_episode_with_a_note() {
+        let agent_state = AgentProblemState::new_multi_step(
+            WorldState::default(),
+            vec!["MessageApi".to_string()],
+            "Send a greeting to Frank",
+            false,
+        );
+        let output_path = scratch_output_path();
+        let output_file = std::fs::OpenOptions::new()
+            .create(true)
+            .write(true)
+            .truncate(true)
+            .open(&output_path)
+            .expect("failed to create scratch output file");
+        let mut problem = AceProblem {
+            identifier: "test_identifier".to_string(),
+            perturbation_type: "base".to_string(),
+            dataset_name: "test".to_string(),
+            id: "test_id".to_string(),
+            status: ProblemStatus::Waiting,
+            question: "Send a greeting to Frank".to_string(),
+            function: Vec::new(),
+            state: AceProblemState::MultiStep(agent_state),
+            output_file: Arc::new(AtomicRefCell::new(output_file)),
+            dialogue_event_sink: None,
+            max_dialogue_chars: None,
+            attempt_count: 0,
+        };
+        problem.set_max_dialogue_chars(10);
+
+        problem.handle_python_response(
+            PythonResponse {
+                identifier: "test_identifier".to_string(),
+                response: "[send_message(sender_name='Eve', receiver_name='Frank', message='hi')]".to_string(),
+                is_retry: false,
+            },
+            false,
+        );
+
+        let written = std::fs::read_to_string(&output_path).expect("failed to read scratch output file");
+        std::fs::remove_file(&output_path).ok();
+        assert!(
+            !written.is_empty(),
+            "a tiny budget should force-finish the conversation and write a result entry"
+        );
+        assert!(
+            written.contains("Context budget exceeded"),
+            "expected a context-budget-exceeded note in the written result, got: {}",
+            written
+        );
+    }
+}
+
+#[cfg(test)]
+mod attempt_cap_tests {
+    use super::*;
+
+    fn scratch_output_path() -> std::path::PathBuf {
+        static COUNTER: std::sync::atomic::AtomicUsize = std::sync::atomic::AtomicUsize::new(0);
+        let n = COUNTER.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+        std::env::temp_dir().join(format!("ace_problem_attempt_cap_test_{}_{}.jsonl", std::process::id(), n))
+    }
+
+    /// A response that never parses as a function call and is always retried, so
+    /// `num_steps` never advances and `finish_if_over_budget`'s `MAX_TURNS` check never
+    /// fires — this is the livelock `attempt_count`/`attempts_exhausted` is meant to
+    /// catch instead.
+    fn always_invalid_retry() -> PythonResponse {
+        PythonResponse {
+            identifier: "test_identifier".to_string(),
+            response: "this is not a function call".to_string(),
+            is_retry: true,
+        }
+    }
+
+    #[test]
+    fn a_problem_that_only_ever_gets_invalid_retries_is_force_finalized_once_its_attempt_budget_is_exhausted() {
+        let agent_state = AgentProblemState::new_multi_turn(
+            WorldState::default(),
+            vec!["MessageApi".to_string()],
+            "Ask the agent something, then answer its follow-up question",
+            false,
+        );
+        let output_path = scratch_output_path();
+        let output_file = std::fs::OpenOptions::new()
+            .create(true)
+            .write(true)
+            .truncate(true)
+            .open(&output_path)
+            .expect("failed to create scratch output file");
+        let mut problem = AceProblem {
+            identifier: "test_identifier".to_string(),
+            perturbation_type: "base".to_string(),
+            dataset_name: "test".to_string(),
+            id: "test_id".to_string(),
+            status: ProblemStatus::Waiting,
+            question: "Ask the agent something, then answer its follow-up question".to_string(),
+            function: Vec::new(),
+            state: AceProblemState::MultiTurn(agent_state),
+            output_file: Arc::new(AtomicRefCell::new(output_file)),
+            dialogue_event_sink: None,
+            max_dialogue_chars: None,
+            attempt_count: 0,
+        };
+
+        // Mirror what `AceGenerator::receive_response_helper` does on each re-queue:
+        // bump `attempt_count` on a non-completion and force-finalize once exhausted.
+        let mut finalized = false;
+        for _ in 0..1000 {
+            let completed = problem.handle_python_response(always_invalid_retry(), false);
+            if completed {
+                finalized = true;
+                break;
+            }
+            problem.attempt_count += 1;
+            if problem.attempts_exhausted() {
+                problem.force_finalize();
+                finalized = true;
+                break;
+            }
+        }
+
+        assert!(finalized, "a problem stuck on invalid retries must eventually terminate instead of looping forever");
+
+        let written = std::fs::read_to_string(&output_path).expect("failed to read scratch output file");
+        std::fs::remove_file(&output_path).ok();
+        assert!(
+            !written.is_empty(),
+            "the exhausted problem should have force-finalized a result entry"
+        );
+    }
+}
+
+#[cfg(test)]
+mod get_result_tests {
+    use super::*;
+
+    fn scratch_output_path() -> std::path::PathBuf {
+        static COUNTER: std::sync::atomic::AtomicUsize = std::sync::atomic::AtomicUsize::new(0);
+        let n = COUNTER.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+        std::env::temp_dir().join(format!("ace_problem_get_result_test_{}_{}.jsonl", std::process::id(), n))
+    }
+
+    fn new_single_turn_problem() -> (AceProblem, std::path::PathBuf) {
+        let single_turn_state = SingleTurnProblemState {
+            has_transition_perturbation: false,
+            time: None,
+            profile: None,
+            first_turn: true,
+            question: "What's 2 + 2?".to_string(),
+            prev_llm_response: None,
+            result: None,
+        };
+        let output_path = scratch_output_path();
+        let output_file = std::fs::OpenOptions::new()
+            .create(true)
+            .write(true)
+            .truncate(true)
+            .open(&output_path)
+            .expect("failed to create scratch output file");
+        let problem = AceProblem {
+            identifier: "test_identifier".to_string(),
+            perturbation_type: "base".to_string(),
+            dataset_name: "test".to_string(),
+            id: "test_id".to_string(),
+            status: ProblemStatus::Waiting,
+            question: "What's 2 + 2?".to_string(),
+            function: Vec::new(),
+            state: AceProblemState::SingleTurnNormal(single_turn_state),
+            output_file: Arc::new(AtomicRefCell::new(output_file)),
+            dialogue_event_sink: None,
+            max_dialogue_chars: None,
+            attempt_count: 0,
+        };
+        (problem, output_path)
+    }
+
+    #[test]
+    fn get_result_is_none_until_the_single_turn_problem_finishes_then_returns_the_response() {
+        let (mut problem, output_path) = new_single_turn_problem();
+
+        assert_eq!(problem.get_result(), None, "no response has been handled yet");
+
+        let completed = problem.handle_python_response(
+            PythonResponse {
+                identifier: "test_identifier".to_string(),
+                response: "[calculate(expression='2 + 2')]".to_string(),
+                is_retry: false,
+            },
+            false,
+        );
+
+        std::fs::remove_file(&output_path).ok();
+
+        assert!(completed, "single-turn problems finish after one response");
+        assert_eq!(problem.get_result(), Some("[calculate(expression='2 + 2')]"));
+    }
+}
+
+#[cfg(test)]
+mod single_turn_special_tests {
+    use super::*;
+
+    fn scratch_output_path() -> std::path::PathBuf {
+        static COUNTER: std::sync::atomic::AtomicUsize = std::sync::atomic::AtomicUsize::new(0);
+        let n = COUNTER.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+        std::env::temp_dir().join(format!("ace_problem_special_test_{}_{}.jsonl", std::process::id(), n))
+    }
+
+    fn new_special_problem() -> (AceProblem, std::path::PathBuf) {
+        let single_turn_state = SingleTurnProblemState {
+            has_transition_perturbation: false,
+            time: Some("2024-01-01 00:00:00".to_string()),
+            profile: None,
+            first_turn: true,
+            question: "What's the weather like tomorrow?".to_string(),
+            prev_llm_response: None,
+            result: None,
+        };
+        let output_path = scratch_output_path();
+        let output_file = std::fs::OpenOptions::new()
+            .create(true)
+            .write(true)
+            .truncate(true)
+            .open(&output_path)
+            .expect("failed to create scratch output file");
+        let problem = AceProblem {
+            identifier: "test_identifier".to_string(),
+            perturbation_type: "base".to_string(),
+            dataset_name: "test".to_string(),
+            id: "test_id".to_string(),
+            status: ProblemStatus::Waiting,
+            question: "What's the weather like tomorrow?".to_string(),
+            function: Vec::new(),
+            state: AceProblemState::SingleTurnSpecial(single_turn_state),
+            output_file: Arc::new(AtomicRefCell::new(output_file)),
+            dialogue_event_sink: None,
+            max_dialogue_chars: None,
+            attempt_count: 0,
+        };
+        (problem, output_path)
+    }
+
+    #[test]
+    fn building_a_python_task_for_a_special_problem_uses_the_special_system_prompt_and_finishes_in_one_response() {
+        let (problem, output_path) = new_special_problem();
+
+        let task = problem.build_python_task(false);
+
+        assert_eq!(task.identifier, "test_identifier");
+        assert_eq!(task.role, "assistant");
+        assert!(task.user_prompt.contains("What's the weather like tomorrow?"));
+        assert!(task.system_prompt.contains("2024-01-01 00:00:00"));
+
+        let mut problem = problem;
+        let completed = problem.handle_python_response(
+            PythonResponse {
+                identifier: "test_identifier".to_string(),
+                response: "[get_weather(date='tomorrow')]".to_string(),
+                is_retry: false,
+            },
+            false,
+        );
+
+        std::fs::remove_file(&output_path).ok();
+
+        assert!(completed, "special single-turn problems finish after one response, like normal and preference");
+        assert_eq!(problem.get_result(), Some("[get_weather(date='tomorrow')]"));
+    }
+}
+
+#[cfg(test)]
+mod fc_mode_tools_tests {
+    use super::*;
+
+    fn new_normal_problem() -> AceProblem {
+        let single_turn_state = SingleTurnProblemState {
+            has_transition_perturbation: false,
+            time: Some("2024-01-01 00:00:00".to_string()),
+            profile: None,
+            first_turn: true,
+            question: "What's the weather like tomorrow?".to_string(),
+            prev_llm_response: None,
+            result: None,
+        };
+        AceProblem {
+            identifier: "test_identifier".to_string(),
+            perturbation_type: "base".to_string(),
+            dataset_name: "test".to_string(),
+            id: "test_id".to_string(),
+            status: ProblemStatus::Waiting,
+            question: "What's the weather like tomorrow?".to_string(),
+            function: vec![serde_json::json!({
+                "name": "get_weather",
+                "description": "Get the weather for a date",
+                "parameters": {"type": "object", "properties": {"date": {"type": "string"}}},
+            })],
+            state: AceProblemState::SingleTurnNormal(single_turn_state),
+            output_file: Arc::new(AtomicRefCell::new(tempfile())),
+            dialogue_event_sink: None,
+            max_dialogue_chars: None,
+            attempt_count: 0,
+        }
+    }
+
+    fn tempfile() -> std::fs::File {
+        static COUNTER: std::sync::atomic::AtomicUsize = std::sync::atomic::AtomicUsize::new(0);
+        let n = COUNTER.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+        let path = std::env::temp_dir().join(format!("ace_problem_fc_mode_test_{}_{}.jsonl", std::process::id(), n));
+        std::fs::OpenOptions::new()
+            .create(true)
+            .write(true)
+            .truncate(true)
+            .open(path)
+            .expect("failed to create scratch output file")
+    }
+
+    #[test]
+    fn fc_mode_places_functions_in_tools_and_omits_them_from_the_prompt() {
+        let problem = new_normal_problem();
+
+        let fc_task = problem.build_python_task(true);
+        assert_eq!(fc_task.tools.as_deref(), Some(problem.function.as_slice()));
+        assert!(
+            !fc_task.system_prompt.contains("get_weather"),
+            "FC mode should not inline the function schema into the system prompt"
+        );
+
+        let prompt_task = problem.build_python_task(false);
+        assert_eq!(prompt_task.tools, None);
+        assert!(
+            prompt_task.system_prompt.contains("get_weather"),
+            "prompt mode must keep inlining the function schema into the system prompt"
+        );
+    }
+}
+
+#[cfg(test)]
+mod chat_messages_tests {
+    use super::*;
+
+    #[test]
+    fn reconstructing_a_small_dialogue_maps_each_participant_to_its_openai_role() {
+        let mut agent_state = AgentProblemState::new_multi_turn(
+            WorldState::default(),
+            vec!["MessageApi".to_string()],
+            "Ask the agent to send a message",
+            false,
+        );
+        agent_state.dialogue_history = vec![
+            DialogueEntry::new(DialogueParticipant::User, DialogueParticipant::Agent, "send a message to Frank".to_string()),
+            DialogueEntry::new(DialogueParticipant::Agent, DialogueParticipant::Execution, "send_message(receiver_name='Frank')".to_string()),
+            DialogueEntry::new(DialogueParticipant::Execution, DialogueParticipant::Agent, "Message successfully sent to Frank.".to_string()),
+        ];
+
+        let chat_messages = agent_state.chat_messages();
+
+        assert_eq!(chat_messages.len(), 3);
+        assert_eq!(chat_messages[0].role, "user");
+        assert_eq!(chat_messages[0].content, "send a message to Frank");
+        assert_eq!(chat_messages[1].role, "assistant");
+        assert_eq!(chat_messages[1].content, "send_message(receiver_name='Frank')");
+        assert_eq!(chat_messages[2].role, "tool");
+        assert_eq!(chat_messages[2].content, "Message successfully sent to Frank.");
+    }
+}
+
+#[cfg(test)]
+mod trace_capture_tests {
+    use super::*;
+
+    #[test]
+    fn a_two_step_task_with_trace_capture_enabled_produces_a_two_entry_trace() {
+        let agent_state = AgentProblemState::new_multi_step(
+            WorldState::default(),
+            vec!["MessageApi".to_string()],
+            "Send a greeting to Frank, then to Grace",
+            false,
+        );
+        let mut problem = AceProblem {
+            identifier: "test_identifier".to_string(),
+            perturbation_type: "base".to_string(),
+            dataset_name: "test".to_string(),
+            id: "test_id".to_string(),
+            status: ProblemStatus::Waiting,
+            question: "Send a greeting to Frank, then to Grace".to_string(),
+            function: Vec::new(),
+            state: AceProblemState::MultiStep(agent_state),
+            output_file: Arc::new(AtomicRefCell::new(scratch_output_file("trace_test"))),
+            dialogue_event_sink: None,
+            max_dialogue_chars: None,
+            attempt_count: 0,
+        };
+        problem.set_enable_trace(true);
+
+        problem.handle_python_response(
+            PythonResponse {
+                identifier: "test_identifier".to_string(),
+                response: "[send_message(sender_name='Eve', receiver_name='Frank', message='hi')]".to_string(),
+                is_retry: false,
+            },
+            false,
+        );
+        problem.handle_python_response(
+            PythonResponse {
+                identifier: "test_identifier".to_string(),
+                response: "[send_message(sender_name='Eve', receiver_name='Grace', message='hi')]".to_string(),
+                is_retry: false,
+            },
+            false,
+        );
+
+        let trace = match &problem.state {
+            AceProblemState::MultiStep(agent_state) => &agent_state.trace,
+            _ => panic!("expected MultiStep state"),
+        };
+        assert_eq!(trace.len(), 2);
+        assert!(trace[0].raw_response.contains("Frank"));
+        assert!(trace[1].raw_response.contains("Grace"));
+        assert_eq!(trace[0].function_calls.len(), 1);
+        assert_eq!(trace[0].execution_results.len(), 1);
+    }
+
+    #[test]
+    fn trace_capture_disabled_by_default_records_nothing() {
+        let agent_state = AgentProblemState::new_multi_step(
+            WorldState::default(),
+            vec!["MessageApi".to_string()],
+            "Send a greeting to Frank",
+            false,
+        );
+        let mut problem = AceProblem {
+            identifier: "test_identifier".to_string(),
+            perturbation_type: "base".to_string(),
+            dataset_name: "test".to_string(),
+            id: "test_id".to_string(),
+            status: ProblemStatus::Waiting,
+            question: "Send a greeting to Frank".to_string(),
+            function: Vec::new(),
+            state: AceProblemState::MultiStep(agent_state),
+            output_file: Arc::new(AtomicRefCell::new(scratch_output_file("trace_test"))),
+            dialogue_event_sink: None,
+            max_dialogue_chars: None,
+            attempt_count: 0,
+        };
+
+        problem.handle_python_response(
+            PythonResponse {
+                identifier: "test_identifier".to_string(),
+                response: "[send_message(sender_name='Eve', receiver_name='Frank', message='hi')]".to_string(),
+                is_retry: false,
+            },
+            false,
+        );
+
+        let trace = match &problem.state {
+            AceProblemState::MultiStep(agent_state) => &agent_state.trace,
+            _ => panic!("expected MultiStep state"),
+        };
+        assert!(trace.is_empty(), "trace capture is off by default");
+    }
+}
+
+#[cfg(test)]
+mod is_finish_signal_tests {
+    use super::*;
+
+    #[test]
+    fn recognizes_the_finish_signal_regardless_of_case_surrounding_whitespace_or_phrasing() {
+        assert!(is_finish_signal("finish conversation"));
+        assert!(is_finish_signal("FINISH_CONVERSATION"));
+        assert!(is_finish_signal("  finish the conversation  "));
+        assert!(is_finish_signal("I'm done here.\nfinish conversation"));
+    }
+
+    #[test]
+    fn does_not_misfire_on_the_phrase_appearing_mid_sentence() {
+        assert!(!is_finish_signal("I will now finish conversation cleanup"));
+        assert!(!is_finish_signal("Let's finish conversation soon"));
+        assert!(!is_finish_signal("[send_message(receiver_name='Frank', message='hi')]"));
+    }
+}
+
+#[cfg(test)]
+mod multi_turn_call_vs_prose_tests {
+    use super::*;
+
+    fn new_multi_turn_problem() -> AceProblem {
+        let agent_state = AgentProblemState::new_multi_turn(
+            WorldState::default(),
+            vec!["MessageApi".to_string()],
+            "Ask the agent to send a message",
+            false,
+        );
+        AceProblem {
+            identifier: "test_identifier".to_string(),
+            perturbation_type: "base".to_string(),
+            dataset_name: "test".to_string(),
+            id: "test_id".to_string(),
+            status: ProblemStatus::Waiting,
+            question: "Ask the agent to send a message".to_string(),
+            function: Vec::new(),
+            state: AceProblemState::MultiTurn(agent_state),
+            output_file: Arc::new(AtomicRefCell::new(scratch_output_file("multi_turn_call_vs_prose_test"))),
+            dialogue_event_sink: None,
+            max_dialogue_chars: None,
+            attempt_count: 0,
+        }
+    }
+
+    fn last_recipient(problem: &AceProblem) -> DialogueParticipant {
+        match &problem.state {
+            AceProblemState::MultiTurn(agent_state) => {
+                agent_state.dialogue_history.last().unwrap().recipient
+            }
+            _ => panic!("expected MultiTurn state"),
+        }
+    }
+
+    fn non_retry(response: &str) -> PythonResponse {
+        PythonResponse {
+            identifier: "test_identifier".to_string(),
+            response: response.to_string(),
+            is_retry: false,
+        }
+    }
+
+    #[test]
+    fn a_call_preceded_by_whitespace_is_still_recognized_as_a_call_not_relayed_to_the_user() {
+        let mut problem = new_multi_turn_problem();
+
+        // User's opening message.
+        problem.handle_python_response(non_retry("Please send a greeting to Frank."), false);
+        // Agent's call, indented/whitespace-prefixed the way some models format it.
+        problem.handle_python_response(
+            non_retry("  [send_message(sender_name='Eve', receiver_name='Frank', message='hi')]"),
+            false,
+        );
+
+        assert_eq!(
+            last_recipient(&problem),
+            DialogueParticipant::Agent,
+            "a recognized call should be routed to execution (recipient stays Agent->Execution), not relayed to the user"
+        );
+    }
+
+    #[test]
+    fn a_genuinely_conversational_reply_with_no_bracketed_call_is_relayed_to_the_user() {
+        let mut problem = new_multi_turn_problem();
+
+        problem.handle_python_response(non_retry("Please send a greeting to Frank."), false);
+        problem.handle_python_response(non_retry("Sure, what would you like the message to say?"), false);
+
+        assert_eq!(
+            last_recipient(&problem),
+            DialogueParticipant::User,
+            "plain conversational text with no bracketed call should be relayed to the user"
+        );
+    }
+}
+
+#[cfg(test)]
+mod transition_perturbation_tests {
+    use super::*;
+
+    #[test]
+    fn a_custom_perturbation_fires_at_its_configured_step_with_its_configured_message_instead_of_executing() {
+        let mut agent_state = AgentProblemState::new_multi_step(
+            WorldState::default(),
+            vec!["MessageApi".to_string()],
+            "Send a greeting to Frank, then to Grace",
+            true,
+        );
+        agent_state.set_transition_perturbation(TransitionPerturbation {
+            message: "Custom outage notice, please retry.".to_string(),
+            after_step: 2,
+        });
+        let mut problem = AceProblem {
+            identifier: "test_identifier".to_string(),
+            perturbation_type: "base".to_string(),
+            dataset_name: "test".to_string(),
+            id: "test_id".to_string(),
+            status: ProblemStatus::Waiting,
+            question: "Send a greeting to Frank, then to Grace".to_string(),
+            function: Vec::new(),
+            state: AceProblemState::MultiStep(agent_state),
+            output_file: Arc::new(AtomicRefCell::new(scratch_output_file("transition_perturbation_test"))),
+            dialogue_event_sink: None,
+            max_dialogue_chars: None,
+            attempt_count: 0,
+        };
+
+        // Step 1: below the configured trigger step, so it executes normally.
+        problem.handle_python_response(
+            PythonResponse {
+                identifier: "test_identifier".to_string(),
+                response: "[send_message(sender_name='Eve', receiver_name='Frank', message='hi')]".to_string(),
+                is_retry: false,
+            },
+            false,
+        );
+        // Step 2: reaches the configured trigger step, so the custom message fires
+        // instead of executing the call.
+        problem.handle_python_response(
+            PythonResponse {
+                identifier: "test_identifier".to_string(),
+                response: "[send_message(sender_name='Eve', receiver_name='Grace', message='hi')]".to_string(),
+                is_retry: false,
+            },
+            false,
+        );
+
+        let agent_state = match &problem.state {
+            AceProblemState::MultiStep(agent_state) => agent_state,
+            _ => panic!("expected MultiStep state"),
+        };
+        assert!(agent_state.perturbed);
+        assert_eq!(
+            agent_state.dialogue_history.last().unwrap().message,
+            "Custom outage notice, please retry."
+        );
     }
 }
@@ -20,6 +20,9 @@ impl ExecutionResult {
             message,
         }
     }
+    pub fn is_success(&self) -> bool {
+        self.status
+    }
 }
 
 /// Base API state - shared by MessageApi, ReminderApi, FoodPlatform
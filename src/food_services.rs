@@ -27,6 +27,12 @@ pub struct Merchant {
     pub merchant_id: String,
     pub service_type: String,
     pub menu: Vec<MenuItem>,
+    #[serde(default = "default_delivery_fee")]
+    pub delivery_fee: NotNan<f64>,
+}
+
+fn default_delivery_fee() -> NotNan<f64> {
+    NotNan::new(0.0).unwrap()
 }
 
 /// Order item
@@ -40,10 +46,32 @@ pub struct OrderItem {
 fn default_quantity() -> u32 {
     1
 }
+
+/// Accepts either a JSON integer or a numeric string for `quantity`, since models
+/// frequently emit quantities as strings (e.g. `"2"`), which `u32`'s default
+/// deserializer rejects outright.
+fn deserialize_quantity<'de, D>(deserializer: D) -> Result<u32, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum IntOrString {
+        Int(u32),
+        String(String),
+    }
+    match IntOrString::deserialize(deserializer)? {
+        IntOrString::Int(quantity) => Ok(quantity),
+        IntOrString::String(quantity) => quantity
+            .parse()
+            .map_err(|_| serde::de::Error::custom(format!("quantity is not a valid number: {}", quantity))),
+    }
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
 pub struct ArgumentItem {
     pub product: String,
-    #[serde(default = "default_quantity")]
+    #[serde(default = "default_quantity", deserialize_with = "deserialize_quantity")]
     pub quantity: u32,
 }
 
@@ -77,6 +105,15 @@ pub struct LoginFoodPlatformArgs {
     pub password: String,
 }
 #[derive(Clone, Deserialize)]
+pub struct LogoutFoodPlatformArgs {
+    pub username: String,
+}
+#[derive(Clone, Deserialize)]
+pub struct RechargeBalanceArgs {
+    pub username: String,
+    pub amount: NotNan<f64>,
+}
+#[derive(Clone, Deserialize)]
 pub struct CheckBalanceArgs {
     pub user_name: String,
 }
@@ -87,6 +124,11 @@ pub struct AddFoodDeliveryOrderArgs {
     pub items: Vec<ArgumentItem>, // (product_name, quantity)
 }
 #[derive(Clone, Deserialize)]
+pub struct CancelFoodOrderArgs {
+    pub username: String,
+    pub order_index: usize,
+}
+#[derive(Clone, Deserialize)]
 pub struct GetProductArgs {
     pub merchant_name: String,
 }
@@ -98,6 +140,14 @@ pub struct ViewOrdersArgs {
 pub struct SearchOrdersArgs {
     pub keyword: String,
 }
+#[derive(Clone, Deserialize)]
+pub struct SearchMerchantsArgs {
+    pub keyword: String,
+}
+#[derive(Clone, Deserialize)]
+pub struct SearchProductsArgs {
+    pub keyword: String,
+}
 impl Default for FoodPlatform {
     fn default() -> Self {
         let users: IndexMap<String, FoodUser> = vec![
@@ -168,6 +218,7 @@ impl Default for FoodPlatform {
                             price: NotNan::new(88.0).unwrap(),
                         },
                     ],
+                    delivery_fee: NotNan::new(5.0).unwrap(),
                 },
             ),
             (
@@ -185,6 +236,7 @@ impl Default for FoodPlatform {
                             price: NotNan::new(45.0).unwrap(),
                         },
                     ],
+                    delivery_fee: NotNan::new(3.0).unwrap(),
                 },
             ),
             (
@@ -202,6 +254,7 @@ impl Default for FoodPlatform {
                             price: NotNan::new(88.0).unwrap(),
                         },
                     ],
+                    delivery_fee: NotNan::new(6.0).unwrap(),
                 },
             ),
             (
@@ -219,6 +272,7 @@ impl Default for FoodPlatform {
                             price: NotNan::new(22.0).unwrap(),
                         },
                     ],
+                    delivery_fee: NotNan::new(2.0).unwrap(),
                 },
             ),
             (
@@ -236,6 +290,7 @@ impl Default for FoodPlatform {
                             price: NotNan::new(99.0).unwrap(),
                         },
                     ],
+                    delivery_fee: NotNan::new(8.0).unwrap(),
                 },
             ),
             (
@@ -253,6 +308,7 @@ impl Default for FoodPlatform {
                             price: NotNan::new(78.0).unwrap(),
                         },
                     ],
+                    delivery_fee: NotNan::new(4.0).unwrap(),
                 },
             ),
         ]
@@ -290,6 +346,13 @@ impl FoodPlatform {
         self.logged_in_users.push(username.to_string());
         ExecutionResult::success(format!("User {} has successfully logged in!", username))
     }
+    pub fn logout_food_platform(&mut self, username: String) -> ExecutionResult {
+        let Some(position) = self.logged_in_users.iter().position(|u| u == &username) else {
+            return ExecutionResult::error(format!("{} is not logged in", username));
+        };
+        self.logged_in_users.remove(position);
+        ExecutionResult::success(format!("User {} has successfully logged out!", username))
+    }
     pub fn view_logged_in_users(&self) -> ExecutionResult {
         if self.logged_in_users.is_empty() {
             return ExecutionResult::error("No users are currently logged in to the food platform".to_string());
@@ -298,6 +361,22 @@ impl FoodPlatform {
     }
     // unify the return type to ExecutionResult, unlike the original implementation
     // this is much easier to handle, and does not affect the functionality much
+    pub fn recharge_balance(&mut self, username: String, amount: NotNan<f64>) -> ExecutionResult {
+        if !self.logged_in_users.contains(&username.to_string()) {
+            return ExecutionResult::error(format!("User {} is not logged in to the food platform", username));
+        }
+        if amount.into_inner() <= 0.0 {
+            return ExecutionResult::error(format!("Invalid recharge amount {}", amount));
+        }
+        let Some(user) = self.users.get_mut(&username) else {
+            return ExecutionResult::error(format!("User {} does not exist", username));
+        };
+        user.balance += amount;
+        ExecutionResult::success(format!(
+            "User {}'s balance has been recharged to {}",
+            username, user.balance
+        ))
+    }
     pub fn check_balance(&self, user_name: String) -> ExecutionResult {
         match self.users.get(&user_name) {
             Some(user) => ExecutionResult::success(format!("User {} has a balance of {}", user_name, user.balance)),
@@ -334,6 +413,8 @@ impl FoodPlatform {
                 price_per_unit: product.price,
             });
         }
+        let delivery_fee = merchant.delivery_fee;
+        total_price += delivery_fee;
         // Check if the balance is sufficient
         let user = self.users.get_mut(&username).unwrap();
         if total_price > user.balance {
@@ -349,9 +430,33 @@ impl FoodPlatform {
         };
         self.orders.push(order);
         ExecutionResult::success(format!(
-            "Food delivery order successfully placed with {}. Total amount: {} yuan",
-            merchant_name, total_price
-        ))    
+            "Food delivery order successfully placed with {}. Total amount: {} yuan (including {} yuan delivery fee)",
+            merchant_name, total_price, delivery_fee
+        ))
+    }
+    // order_index is 0-based into the user's own orders, in the order they were placed,
+    // not a global index into self.orders
+    pub fn cancel_food_order(&mut self, username: String, order_index: usize) -> ExecutionResult {
+        if !self.logged_in_users.contains(&username.to_string()) {
+            return ExecutionResult::error(format!("User {} is not logged in to the food platform", username));
+        }
+        let Some(global_index) = self
+            .orders
+            .iter()
+            .enumerate()
+            .filter(|(_, order)| order.user_name == username)
+            .nth(order_index)
+            .map(|(global_index, _)| global_index)
+        else {
+            return ExecutionResult::error(format!("Order {} does not belong to {} or does not exist", order_index, username));
+        };
+        let order = self.orders.remove(global_index);
+        let user = self.users.get_mut(&username).unwrap();
+        user.balance += order.total_price;
+        ExecutionResult::success(format!(
+            "Order {} with {} has been cancelled and {} yuan refunded to {}",
+            order_index, order.merchant_name, order.total_price, username
+        ))
     }
     // the output format is slightly different from the original implementation for convenience
     pub fn get_products(&self, merchant_name: String) -> ExecutionResult {
@@ -362,6 +467,38 @@ impl FoodPlatform {
         ExecutionResult::success(format!("Products for {}: {}", merchant_name, products_str))
     }
 
+    pub fn search_products(&self, keyword: String) -> ExecutionResult {
+        let keyword_lower = keyword.to_lowercase();
+        let matched_products: Vec<serde_json::Value> = self
+            .merchant_list
+            .as_ref()
+            .unwrap()
+            .iter()
+            .flat_map(|(merchant_name, merchant)| {
+                merchant
+                    .menu
+                    .iter()
+                    .filter(|item| item.product.to_lowercase().contains(&keyword_lower))
+                    .map(|item| {
+                        serde_json::json!({
+                            "merchant_name": merchant_name,
+                            "product": item.product,
+                            "price": item.price,
+                        })
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .collect();
+        if matched_products.is_empty() {
+            return ExecutionResult::error(format!("No products found matching '{}'.", keyword));
+        }
+        ExecutionResult::success(format!(
+            "Matched products for keyword '{}': {}",
+            keyword,
+            serde_json::to_string(&matched_products).unwrap()
+        ))
+    }
+
     pub fn view_orders(&self, user_name: String) -> ExecutionResult {
         let user_orders: Vec<&FoodOrder> = self
             .orders
@@ -375,7 +512,56 @@ impl FoodPlatform {
         ExecutionResult::success(format!("Orders for {}: {}", user_name, orders_str))
     }
 
+    pub fn view_all_merchants(&self) -> ExecutionResult {
+        let merchants: Vec<serde_json::Value> = self
+            .merchant_list
+            .as_ref()
+            .unwrap()
+            .iter()
+            .map(|(merchant_name, merchant)| {
+                serde_json::json!({
+                    "merchant_name": merchant_name,
+                    "service_type": merchant.service_type,
+                })
+            })
+            .collect();
+        if merchants.is_empty() {
+            return ExecutionResult::error("No merchants are available on the food platform".to_string());
+        }
+        ExecutionResult::success(format!("Merchants: {}", serde_json::to_string(&merchants).unwrap()))
+    }
+
+    pub fn search_merchants(&self, keyword: String) -> ExecutionResult {
+        let keyword_lower = keyword.to_lowercase();
+        let matched_merchants: Vec<serde_json::Value> = self
+            .merchant_list
+            .as_ref()
+            .unwrap()
+            .iter()
+            .filter(|(merchant_name, merchant)| {
+                merchant_name.to_lowercase().contains(&keyword_lower)
+                    || merchant.service_type.to_lowercase().contains(&keyword_lower)
+            })
+            .map(|(merchant_name, merchant)| {
+                serde_json::json!({
+                    "merchant_name": merchant_name,
+                    "service_type": merchant.service_type,
+                })
+            })
+            .collect();
+        if matched_merchants.is_empty() {
+            return ExecutionResult::error(format!("No merchants found matching '{}'.", keyword));
+        }
+        ExecutionResult::success(format!(
+            "Matched merchants for keyword '{}': {}",
+            keyword,
+            serde_json::to_string(&matched_merchants).unwrap()
+        ))
+    }
+
     pub fn search_orders(&self, keyword: String) -> ExecutionResult {
+        // `to_lowercase`/`contains` below operate on chars, not bytes, so multibyte
+        // merchant names (e.g. "海底捞") match correctly without any extra handling.
         let matched_orders: Vec<&FoodOrder> = self
             .orders
             .iter()
@@ -410,3 +596,276 @@ impl FoodPlatform {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod search_orders_unicode_tests {
+    use super::*;
+
+    #[test]
+    fn search_orders_matches_a_chinese_merchant_name() {
+        let mut platform = FoodPlatform::default();
+        platform.orders.push(FoodOrder {
+            user_name: "Eve".to_string(),
+            merchant_name: "海底捞".to_string(),
+            items: vec![OrderItem {
+                product: "Hot Pot Set".to_string(),
+                quantity: 1,
+                price_per_unit: NotNan::new(98.0).unwrap(),
+            }],
+            total_price: NotNan::new(98.0).unwrap(),
+        });
+
+        let result = platform.search_orders("海底捞".to_string());
+        assert!(result.is_success(), "{}", result.message);
+        assert!(result.message.contains("海底捞"));
+    }
+}
+
+#[cfg(test)]
+mod search_products_tests {
+    use super::*;
+
+    #[test]
+    fn searching_pizza_returns_dominos_items_across_merchants() {
+        let platform = FoodPlatform::default();
+        let result = platform.search_products("pizza".to_string());
+        assert!(result.is_success(), "{}", result.message);
+        assert!(result.message.contains("Domino's"));
+        assert!(result.message.contains("Margherita Pizza"));
+        assert!(result.message.contains("Super Supreme Pizza"));
+    }
+
+    #[test]
+    fn returns_an_error_when_nothing_matches() {
+        let platform = FoodPlatform::default();
+        let result = platform.search_products("nonexistent dish".to_string());
+        assert!(!result.is_success());
+    }
+}
+
+#[cfg(test)]
+mod search_merchants_tests {
+    use super::*;
+
+    #[test]
+    fn a_service_type_keyword_finds_the_matching_merchant_case_insensitively() {
+        let platform = FoodPlatform::default();
+        let result = platform.search_merchants("hotpot".to_string());
+        assert!(result.is_success(), "{}", result.message);
+        assert!(result.message.contains("Hotpot"));
+        assert!(!result.message.contains("Pizza"));
+    }
+
+    #[test]
+    fn a_merchant_name_keyword_also_matches() {
+        let platform = FoodPlatform::default();
+        let result = platform.search_merchants("domino".to_string());
+        assert!(result.is_success(), "{}", result.message);
+        assert!(result.message.contains("Domino's"));
+    }
+
+    #[test]
+    fn returns_an_error_when_nothing_matches() {
+        let platform = FoodPlatform::default();
+        let result = platform.search_merchants("nonexistent cuisine".to_string());
+        assert!(!result.is_success());
+    }
+}
+
+#[cfg(test)]
+mod delivery_fee_tests {
+    use super::*;
+
+    #[test]
+    fn a_balance_that_covers_the_item_price_but_not_the_delivery_fee_is_rejected() {
+        let mut platform = FoodPlatform::default();
+        platform.base_api.wifi = true;
+        platform.login_food_platform("Eve".to_string(), "password123".to_string());
+        // Margherita Pizza costs exactly 68.0, and Domino's charges a 5.0 delivery fee;
+        // a balance equal to the item price alone must fail only because of the fee.
+        platform.users.get_mut("Eve").unwrap().balance = NotNan::new(68.0).unwrap();
+
+        let items = vec![ArgumentItem { product: "Margherita Pizza".to_string(), quantity: 1 }];
+        let thin_balance_result = platform.add_food_delivery_order("Eve".to_string(), "Domino's".to_string(), items.clone());
+        assert!(!thin_balance_result.is_success(), "a balance covering only the item price should fail once the delivery fee is added");
+        assert!(thin_balance_result.message.contains("Insufficient balance"));
+
+        platform.users.get_mut("Eve").unwrap().balance = NotNan::new(73.0).unwrap();
+        let covered_result = platform.add_food_delivery_order("Eve".to_string(), "Domino's".to_string(), items);
+        assert!(covered_result.is_success(), "{}", covered_result.message);
+        assert!(covered_result.message.contains("delivery fee"));
+    }
+}
+
+#[cfg(test)]
+mod view_all_merchants_tests {
+    use super::*;
+
+    #[test]
+    fn every_default_merchant_is_listed() {
+        let platform = FoodPlatform::default();
+        let result = platform.view_all_merchants();
+        assert!(result.is_success(), "{}", result.message);
+
+        for merchant_name in platform.merchant_list.as_ref().unwrap().keys() {
+            assert!(
+                result.message.contains(merchant_name.as_str()),
+                "expected {} in: {}",
+                merchant_name,
+                result.message
+            );
+        }
+        assert_eq!(platform.merchant_list.as_ref().unwrap().len(), 6);
+    }
+}
+
+#[cfg(test)]
+mod logout_food_platform_tests {
+    use super::*;
+
+    #[test]
+    fn logging_out_an_unlogged_in_user_is_an_error() {
+        let mut platform = FoodPlatform::default();
+        let result = platform.logout_food_platform("Eve".to_string());
+        assert!(!result.is_success());
+        assert!(result.message.contains("not logged in"));
+    }
+
+    #[test]
+    fn logging_out_after_logging_in_rejects_a_subsequent_order() {
+        let mut platform = FoodPlatform::default();
+        platform.base_api.wifi = true;
+
+        let login_result = platform.login_food_platform("Eve".to_string(), "password123".to_string());
+        assert!(login_result.is_success(), "{}", login_result.message);
+        assert!(platform.logged_in_users.contains(&"Eve".to_string()));
+
+        let logout_result = platform.logout_food_platform("Eve".to_string());
+        assert!(logout_result.is_success(), "{}", logout_result.message);
+        assert!(!platform.logged_in_users.contains(&"Eve".to_string()));
+
+        let order_result = platform.add_food_delivery_order(
+            "Eve".to_string(),
+            "Domino's".to_string(),
+            vec![ArgumentItem { product: "Margherita Pizza".to_string(), quantity: 1 }],
+        );
+        assert!(!order_result.is_success());
+        assert!(order_result.message.contains("is not logged in"));
+    }
+}
+
+#[cfg(test)]
+mod cancel_food_order_tests {
+    use super::*;
+
+    #[test]
+    fn cancelling_an_order_refunds_the_full_price_and_makes_the_balance_whole() {
+        let mut platform = FoodPlatform::default();
+        platform.base_api.wifi = true;
+        platform.login_food_platform("Eve".to_string(), "password123".to_string());
+
+        let balance_before = platform.users.get("Eve").unwrap().balance;
+
+        let order_result = platform.add_food_delivery_order(
+            "Eve".to_string(),
+            "Domino's".to_string(),
+            vec![ArgumentItem { product: "Margherita Pizza".to_string(), quantity: 1 }],
+        );
+        assert!(order_result.is_success(), "{}", order_result.message);
+        assert!(platform.users.get("Eve").unwrap().balance < balance_before);
+
+        let cancel_result = platform.cancel_food_order("Eve".to_string(), 0);
+        assert!(cancel_result.is_success(), "{}", cancel_result.message);
+        assert_eq!(platform.users.get("Eve").unwrap().balance, balance_before);
+        assert!(platform.orders.iter().all(|order| order.user_name != "Eve"));
+    }
+
+    #[test]
+    fn cancelling_an_order_that_does_not_belong_to_the_user_is_an_error() {
+        let mut platform = FoodPlatform::default();
+        platform.base_api.wifi = true;
+        platform.login_food_platform("Eve".to_string(), "password123".to_string());
+
+        let result = platform.cancel_food_order("Eve".to_string(), 0);
+        assert!(!result.is_success());
+        assert!(result.message.contains("does not belong"));
+    }
+
+    #[test]
+    fn cancelling_while_not_logged_in_is_an_error() {
+        let mut platform = FoodPlatform::default();
+        let result = platform.cancel_food_order("Eve".to_string(), 0);
+        assert!(!result.is_success());
+        assert!(result.message.contains("is not logged in"));
+    }
+}
+
+#[cfg(test)]
+mod recharge_balance_tests {
+    use super::*;
+
+    #[test]
+    fn an_order_that_fails_on_the_original_balance_succeeds_after_a_recharge() {
+        let mut platform = FoodPlatform::default();
+        platform.base_api.wifi = true;
+        platform.login_food_platform("Grace".to_string(), "password789".to_string());
+
+        let items = vec![ArgumentItem { product: "Super Supreme Pizza".to_string(), quantity: 2 }];
+        let failed_order = platform.add_food_delivery_order("Grace".to_string(), "Domino's".to_string(), items.clone());
+        assert!(!failed_order.is_success(), "Grace's starting balance of 150 should not cover two Super Supreme Pizzas plus delivery");
+
+        let recharge_result = platform.recharge_balance("Grace".to_string(), NotNan::new(100.0).unwrap());
+        assert!(recharge_result.is_success(), "{}", recharge_result.message);
+
+        let order_result = platform.add_food_delivery_order("Grace".to_string(), "Domino's".to_string(), items);
+        assert!(order_result.is_success(), "{}", order_result.message);
+    }
+
+    #[test]
+    fn a_non_positive_recharge_amount_is_rejected() {
+        let mut platform = FoodPlatform::default();
+        platform.base_api.wifi = true;
+        platform.login_food_platform("Grace".to_string(), "password789".to_string());
+
+        let result = platform.recharge_balance("Grace".to_string(), NotNan::new(0.0).unwrap());
+        assert!(!result.is_success());
+        assert!(result.message.contains("Invalid recharge amount"));
+    }
+
+    #[test]
+    fn recharging_while_not_logged_in_is_an_error() {
+        let mut platform = FoodPlatform::default();
+        let result = platform.recharge_balance("Grace".to_string(), NotNan::new(50.0).unwrap());
+        assert!(!result.is_success());
+        assert!(result.message.contains("is not logged in"));
+    }
+}
+
+#[cfg(test)]
+mod argument_item_quantity_deserialization_tests {
+    use super::*;
+
+    #[test]
+    fn a_numeric_string_quantity_is_coerced_to_a_u32() {
+        let item: ArgumentItem = serde_json::from_str(r#"{"product":"x","quantity":"3"}"#).unwrap();
+        assert_eq!(item, ArgumentItem { product: "x".to_string(), quantity: 3 });
+    }
+
+    #[test]
+    fn an_integer_quantity_deserializes_as_before() {
+        let item: ArgumentItem = serde_json::from_str(r#"{"product":"x","quantity":3}"#).unwrap();
+        assert_eq!(item, ArgumentItem { product: "x".to_string(), quantity: 3 });
+    }
+
+    #[test]
+    fn a_missing_quantity_falls_back_to_the_default_of_one() {
+        let item: ArgumentItem = serde_json::from_str(r#"{"product":"x"}"#).unwrap();
+        assert_eq!(item, ArgumentItem { product: "x".to_string(), quantity: 1 });
+    }
+
+    #[test]
+    fn a_non_numeric_string_quantity_is_rejected() {
+        let result: Result<ArgumentItem, _> = serde_json::from_str(r#"{"product":"x","quantity":"a lot"}"#);
+        assert!(result.is_err());
+    }
+}
@@ -21,5 +21,10 @@ pub mod perturbations;
 #[pymodule]
 pub mod rust_code {
     #[pymodule_export]
-    use super::{ace_evaluator::evaluate_all_results, ace_generator::AceGenerator};
+    use super::{
+        ace_evaluator::{
+            analyze_function_coverage, evaluate_all_results, evaluate_dataset, validate_datasets,
+        },
+        ace_generator::AceGenerator,
+    };
 }
@@ -20,6 +20,15 @@ pub struct Message {
     pub message: String,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub time: Option<String>, // Optional - new messages may not have time
+    #[serde(default)]
+    pub read: bool,
+}
+
+fn default_current_date() -> String {
+    // Must postdate every seeded message's `time` (the latest is 2024-09-09) so that a
+    // freshly sent message actually sorts as the newest one, instead of reintroducing
+    // the very "new messages look older than old ones" bug this field exists to fix.
+    "2024-09-10".to_string()
 }
 
 /// Message API state
@@ -34,6 +43,10 @@ pub struct MessageApi {
     #[serde(default)]
     pub user_list: Option<IndexMap<String, MessageUser>>, // key: user name (e.g., "Eve")
     pub inbox: IndexMap<String, Message>,          // key: message_id
+    // the evaluation "now" stamped onto messages sent via send_message when a call
+    // doesn't supply its own time, mirroring Travel::current_time
+    #[serde(default = "default_current_date")]
+    pub current_date: String,
     #[serde(default)]
     pub message_id_counter: Option<usize>,
 }
@@ -61,6 +74,24 @@ pub struct SearchMessagesArgs {
     pub keyword: String,
 }
 
+#[derive(Deserialize, Clone)]
+pub struct GetMessageByIdArgs {
+    pub message_id: usize,
+}
+#[derive(Deserialize, Clone)]
+pub struct MarkMessageReadArgs {
+    pub message_id: usize,
+}
+#[derive(Deserialize, Clone)]
+pub struct ReplyToMessageArgs {
+    pub message_id: usize,
+    pub reply_text: String,
+}
+#[derive(Deserialize, Clone)]
+pub struct ViewUnreadMessagesArgs {
+    pub user_name: String,
+}
+
 impl Default for MessageApi {
     fn default() -> Self {
         
@@ -123,36 +154,42 @@ impl Default for MessageApi {
                 receiver_id: "USR101".to_string(),
                 message: "Hey Frank, don't forget about our meeting on 2024-06-11 at 4 PM in Conference Room 1.".to_string(),
                 time: Some("2024-06-09".to_string()),
+                read: false,
             }),
             ("2".to_string(), Message {
                 sender_id: "USR101".to_string(),
                 receiver_id: "USR102".to_string(),
                 message: "Can you help me order a \"Margherita Pizza\" delivery? The merchant is Domino's.".to_string(),
                 time: Some("2024-03-09".to_string()),
+                read: false,
             }),
             ("3".to_string(), Message {
                 sender_id: "USR102".to_string(),
                 receiver_id: "USR103".to_string(),
                 message: "Please check the milk tea delivery options available from Heytea and purchase a cheaper milk tea for me. After making the purchase, remember to reply to me with \"Already bought.\"".to_string(),
                 time: Some("2023-12-05".to_string()),
+                read: false,
             }),
             ("4".to_string(), Message {
                 sender_id: "USR103".to_string(),
                 receiver_id: "USR102".to_string(),
                 message: "No problem Helen, I can assist you.".to_string(),
                 time: Some("2024-09-09".to_string()),
+                read: true,
             }),
             ("5".to_string(), Message {
                 sender_id: "USR104".to_string(),
                 receiver_id: "USR105".to_string(),
                 message: "Isaac, are you available for a call?".to_string(),
                 time: Some("2024-06-06".to_string()),
+                read: false,
             }),
             ("6".to_string(), Message {
                 sender_id: "USR105".to_string(),
                 receiver_id: "USR104".to_string(),
                 message: "Yes Jack, let's do it in 30 minutes.".to_string(),
                 time: Some("2024-01-15".to_string()),
+                read: true,
             }),
         ].into_iter().collect();
         let user_list = Some(user_list);
@@ -163,12 +200,25 @@ impl Default for MessageApi {
             max_capacity,
             user_list,
             inbox,
+            current_date: default_current_date(),
             message_id_counter,
         }
     }
 }
 
 impl MessageApi {
+    // centralizes the max_capacity check and id-counter bookkeeping that every
+    // inbox-inserting method needs, so capacity is enforced uniformly
+    fn try_insert_message(&mut self, message: Message) -> Result<String, ExecutionResult> {
+        if self.inbox.len() >= self.max_capacity.unwrap() {
+            return Err(ExecutionResult::error("Inbox capacity is full. You need to ask the user which message to delete.".to_string()));
+        }
+        *self.message_id_counter.as_mut().unwrap() += 1;
+        let message_id = self.message_id_counter.unwrap().to_string();
+        self.inbox.insert(message_id.clone(), message);
+        Ok(message_id)
+    }
+
     pub fn send_message(
         &mut self,
         sender_name: String,
@@ -181,9 +231,6 @@ impl MessageApi {
         if !self.base_api.wifi {
             return ExecutionResult::error("Wi-Fi is turned off, cannot send messages at this time".to_string());
         }
-        if self.inbox.len() >= self.max_capacity.unwrap() {
-            return ExecutionResult::error("Inbox capacity is full. You need to ask the user which message to delete.".to_string());
-        }
         let (Some(sender), Some(receiver)) = (
             self.user_list.as_ref().unwrap().get(&sender_name),
             self.user_list.as_ref().unwrap().get(&receiver_name),
@@ -193,21 +240,46 @@ impl MessageApi {
         let sender_id = &sender.user_id;
         let receiver_id = &receiver.user_id;
 
-        // Add the message to the inbox
-        *self.message_id_counter.as_mut().unwrap() += 1;
-        self.inbox.insert(
-            self.message_id_counter.unwrap().to_string(),
-            Message {
-                sender_id: sender_id.clone(),
-                receiver_id: receiver_id.clone(),
-                message: message.to_string(),
-                time: None,
-            },
-        );
+        let new_message = Message {
+            sender_id: sender_id.clone(),
+            receiver_id: receiver_id.clone(),
+            message: message.to_string(),
+            time: Some(self.current_date.clone()),
+            read: false,
+        };
+        if let Err(error) = self.try_insert_message(new_message) {
+            return error;
+        }
 
         ExecutionResult::success(format!("Message successfully sent to {}.", receiver_name))
     }
 
+    pub fn reply_to_message(&mut self, message_id: usize, reply_text: String) -> ExecutionResult {
+        if !self.base_api.logged_in {
+            return ExecutionResult::error("Device not logged in, unable to send message".to_string());
+        }
+        if !self.base_api.wifi {
+            return ExecutionResult::error("Wi-Fi is turned off, cannot send messages at this time".to_string());
+        }
+        let message_id = message_id.to_string();
+        let Some(original) = self.inbox.get(&message_id) else {
+            return ExecutionResult::error("Message ID does not exist".to_string());
+        };
+        let reply = Message {
+            sender_id: original.receiver_id.clone(),
+            receiver_id: original.sender_id.clone(),
+            message: reply_text,
+            time: Some(self.current_date.clone()),
+            read: false,
+        };
+
+        if let Err(error) = self.try_insert_message(reply) {
+            return error;
+        }
+
+        ExecutionResult::success(format!("Reply to message ID {} has been sent.", message_id))
+    }
+
     pub fn delete_message(&mut self, message_id: usize) -> ExecutionResult {
         let message_id = message_id.to_string();
         if !self.base_api.logged_in {
@@ -220,6 +292,75 @@ impl MessageApi {
         ExecutionResult::success(format!("Message ID {} has been successfully deleted.", message_id))
     }
 
+    // falls back to the raw id when user_list is unavailable or doesn't contain it
+    fn resolve_user_name(&self, user_id: &str) -> String {
+        self.user_list
+            .as_ref()
+            .and_then(|user_list| {
+                user_list
+                    .iter()
+                    .find(|(_, user)| user.user_id == user_id)
+                    .map(|(name, _)| name.clone())
+            })
+            .unwrap_or_else(|| user_id.to_string())
+    }
+
+    // raw sender_id/receiver_id ("USR100") are opaque to the LLM; substitute human names
+    // when user_list is available while leaving the underlying Message (and ground-truth
+    // comparison, which operates on raw ids) untouched
+    fn message_display_json(&self, id: &str, message: &Message) -> serde_json::Value {
+        serde_json::json!({
+            "message_id": id,
+            "sender": self.resolve_user_name(&message.sender_id),
+            "receiver": self.resolve_user_name(&message.receiver_id),
+            "message": message.message,
+            "time": message.time,
+            "read": message.read,
+        })
+    }
+
+    pub fn get_message_by_id(&self, message_id: usize) -> ExecutionResult {
+        let message_id = message_id.to_string();
+        let Some(message) = self.inbox.get(&message_id) else {
+            return ExecutionResult::error("Message ID does not exist".to_string());
+        };
+        let message_json = self.message_display_json(&message_id, message);
+        ExecutionResult::success(format!("Message: {}", message_json))
+    }
+
+    pub fn mark_message_read(&mut self, message_id: usize) -> ExecutionResult {
+        let message_id = message_id.to_string();
+        if !self.base_api.logged_in {
+            return ExecutionResult::error("Device not logged in, unable to mark message as read".to_string());
+        }
+        let Some(message) = self.inbox.get_mut(&message_id) else {
+            return ExecutionResult::error("Message ID does not exist".to_string());
+        };
+        message.read = true;
+        ExecutionResult::success(format!("Message ID {} has been marked as read.", message_id))
+    }
+
+    pub fn view_unread_messages(&self, user_name: String) -> ExecutionResult {
+        if !self.base_api.logged_in {
+            return ExecutionResult::error("Device not logged in, unable to view message information".to_string());
+        }
+        let Some(user) = self.user_list.as_ref().unwrap().get(&user_name) else {
+            return ExecutionResult::error("User does not exist".to_string());
+        };
+        let user_id = &user.user_id;
+        let unread_messages: IndexMap<String, Message> = self
+            .inbox
+            .iter()
+            .filter(|(_, msg)| msg.receiver_id == *user_id && !msg.read)
+            .map(|(id, msg)| (id.clone(), msg.clone())) // clone
+            .collect();
+        if unread_messages.is_empty() {
+            return ExecutionResult::error("No unread messages found".to_string());
+        }
+        let messages_str = serde_json::to_string(&unread_messages).unwrap();
+        ExecutionResult::success(format!("Unread messages: {}", messages_str))
+    }
+
     pub fn view_messages_between_users(
         &self,
         sender_name: String,
@@ -237,11 +378,11 @@ impl MessageApi {
         let sender_id = &sender.user_id;
         let receiver_id = &receiver.user_id;
 
-        let messages_between_users: IndexMap<String, Message> = self
+        let messages_between_users: Vec<serde_json::Value> = self
             .inbox
             .iter()
             .filter(|(_, msg)| msg.sender_id == *sender_id && msg.receiver_id == *receiver_id)
-            .map(|(id, msg)| (id.clone(), msg.clone())) // clone
+            .map(|(id, msg)| self.message_display_json(id, msg))
             .collect();
 
         if messages_between_users.is_empty() {
@@ -258,14 +399,14 @@ impl MessageApi {
             return ExecutionResult::error("User does not exist".to_string());
         };
         let user_id = &user.user_id;
-        let matched_messages: IndexMap<String, Message> = self
+        let matched_messages: Vec<serde_json::Value> = self
             .inbox
             .iter()
             .filter(|(_, msg)| {
                 (msg.sender_id == *user_id || msg.receiver_id == *user_id)
                     && msg.message.to_lowercase().contains(&keyword.to_lowercase())
             })
-            .map(|(id, msg)| (id.clone(), msg.clone())) // clone
+            .map(|(id, msg)| self.message_display_json(id, msg))
             .collect();
         if matched_messages.is_empty() {
             return ExecutionResult::error("No related message records found".to_string());
@@ -325,6 +466,23 @@ impl MessageApi {
         let earliest_message_id = earliest_message.0.clone();
         ExecutionResult::success(format!("The earliest message ID is {}", earliest_message_id))
     }
+
+    pub fn get_inbox_utilization(&self) -> ExecutionResult {
+        if !self.base_api.logged_in {
+            return ExecutionResult::error("Device not logged in, unable to check inbox utilization.".to_string());
+        }
+        let used = self.inbox.len();
+        let capacity = self.max_capacity.unwrap();
+        let remaining = capacity.saturating_sub(used);
+        ExecutionResult::success(
+            serde_json::json!({
+                "used": used,
+                "capacity": capacity,
+                "remaining": remaining,
+            })
+            .to_string(),
+        )
+    }
     pub fn equals_ground_truth(&self, ground_truth: &MessageApi) -> Result<(), String> {
 
         self.base_api.equals_ground_truth(&ground_truth.base_api)?;
@@ -339,6 +497,9 @@ impl MessageApi {
         if self.inbox != ground_truth.inbox {
             return Err(format!("inbox does not match ground truth. Expected: {:?}, got: {:?}", ground_truth.inbox, self.inbox));
         }
+        if self.current_date != ground_truth.current_date {
+            return Err(format!("current_date does not match ground truth. Expected: {}, got: {}", ground_truth.current_date, self.current_date));
+        }
         // if self.message_id_counter != ground_truth.message_id_counter {
         //     return Err(format!("message_id_counter does not match ground truth. Expected: {}, got: {}", ground_truth.message_id_counter, self.message_id_counter));
         // }
@@ -348,3 +509,159 @@ impl MessageApi {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod inbox_utilization_tests {
+    use super::*;
+
+    #[test]
+    fn utilization_reflects_seeded_counts_and_updates_after_send_and_delete() {
+        let mut api = MessageApi::default();
+        api.base_api.wifi = true;
+        let seeded_used = api.inbox.len();
+        let capacity = api.max_capacity.unwrap();
+
+        let result = api.get_inbox_utilization();
+        let value: serde_json::Value = serde_json::from_str(&result.message).unwrap();
+        assert_eq!(value["used"], seeded_used);
+        assert_eq!(value["capacity"], capacity);
+        assert_eq!(value["remaining"], capacity - seeded_used);
+
+        assert!(api.delete_message(1).is_success());
+        let after_delete: serde_json::Value = serde_json::from_str(&api.get_inbox_utilization().message).unwrap();
+        assert_eq!(after_delete["used"], seeded_used - 1);
+        assert_eq!(after_delete["remaining"], capacity - seeded_used + 1);
+
+        assert!(api.send_message("Eve".to_string(), "Frank".to_string(), "hi".to_string()).is_success());
+        let after_send: serde_json::Value = serde_json::from_str(&api.get_inbox_utilization().message).unwrap();
+        assert_eq!(after_send["used"], seeded_used);
+        assert_eq!(after_send["remaining"], capacity - seeded_used);
+    }
+}
+
+#[cfg(test)]
+mod mark_message_read_tests {
+    use super::*;
+
+    #[test]
+    fn marking_frank_s_only_unread_message_as_read_removes_it_from_the_unread_list() {
+        let mut api = MessageApi::default();
+
+        let before = api.view_unread_messages("Frank".to_string());
+        assert!(before.is_success(), "{}", before.message);
+        assert!(before.message.contains("\"1\""));
+
+        let mark_result = api.mark_message_read(1);
+        assert!(mark_result.is_success(), "{}", mark_result.message);
+
+        let after = api.view_unread_messages("Frank".to_string());
+        assert!(!after.is_success(), "Frank had only message 1 unread, so no unread messages should remain");
+        assert!(after.message.contains("No unread messages"));
+    }
+}
+
+#[cfg(test)]
+mod send_message_timestamp_tests {
+    use super::*;
+
+    #[test]
+    fn a_freshly_sent_message_becomes_the_latest_by_id() {
+        let mut api = MessageApi::default();
+        api.base_api.wifi = true;
+        assert!(api.delete_message(1).is_success(), "make room in the inbox before sending");
+
+        let send_result = api.send_message("Eve".to_string(), "Frank".to_string(), "hi".to_string());
+        assert!(send_result.is_success(), "{}", send_result.message);
+        let new_id = api.message_id_counter.unwrap().to_string();
+
+        let latest_result = api.get_latest_message_id();
+        assert!(latest_result.is_success(), "{}", latest_result.message);
+        assert!(
+            latest_result.message.contains(&new_id),
+            "expected freshly sent message {} to be the latest, got: {}",
+            new_id,
+            latest_result.message
+        );
+    }
+}
+
+#[cfg(test)]
+mod reply_to_message_tests {
+    use super::*;
+
+    #[test]
+    fn replying_to_message_3_reverses_the_original_sender_and_receiver() {
+        let mut api = MessageApi::default();
+        api.base_api.wifi = true;
+        assert!(api.delete_message(1).is_success(), "make room in the inbox before replying");
+        let original = api.inbox.get("3").unwrap().clone();
+
+        let reply_result = api.reply_to_message(3, "got it, thanks!".to_string());
+        assert!(reply_result.is_success(), "{}", reply_result.message);
+
+        let new_id = api.message_id_counter.unwrap().to_string();
+        let reply = api.inbox.get(&new_id).unwrap();
+        assert_eq!(reply.sender_id, original.receiver_id);
+        assert_eq!(reply.receiver_id, original.sender_id);
+        assert_eq!(reply.message, "got it, thanks!");
+    }
+}
+
+#[cfg(test)]
+mod get_message_by_id_tests {
+    use super::*;
+
+    #[test]
+    fn looking_up_an_existing_message_resolves_sender_and_receiver_names() {
+        let api = MessageApi::default();
+
+        let result = api.get_message_by_id(3);
+        assert!(result.is_success(), "{}", result.message);
+        assert!(result.message.contains("Grace"));
+        assert!(result.message.contains("Helen"));
+    }
+
+    #[test]
+    fn looking_up_a_missing_message_id_is_an_error() {
+        let api = MessageApi::default();
+
+        let result = api.get_message_by_id(9999);
+        assert!(!result.is_success());
+        assert!(result.message.contains("does not exist"));
+    }
+}
+
+#[cfg(test)]
+mod message_display_name_tests {
+    use super::*;
+
+    #[test]
+    fn viewing_messages_between_users_substitutes_names_for_raw_ids() {
+        let api = MessageApi::default();
+
+        let result = api.view_messages_between_users("Eve".to_string(), "Frank".to_string());
+        assert!(result.is_success(), "{}", result.message);
+        assert!(result.message.contains("Eve"));
+        assert!(result.message.contains("Frank"));
+        assert!(!result.message.contains("USR100"));
+        assert!(!result.message.contains("USR101"));
+    }
+}
+
+#[cfg(test)]
+mod try_insert_message_tests {
+    use super::*;
+
+    #[test]
+    fn the_7th_message_into_a_full_capacity_6_inbox_is_rejected_with_the_existing_message() {
+        let mut api = MessageApi::default();
+        api.base_api.wifi = true;
+        assert_eq!(api.inbox.len(), 6);
+        assert_eq!(api.max_capacity, Some(6));
+
+        let result = api.send_message("Eve".to_string(), "Frank".to_string(), "one too many".to_string());
+        assert!(!result.is_success());
+        assert_eq!(result.message, "Inbox capacity is full. You need to ask the user which message to delete.");
+        assert_eq!(api.inbox.len(), 6, "the rejected message must not be inserted");
+    }
+}
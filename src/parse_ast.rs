@@ -35,25 +35,40 @@ pub fn parse_from_string_to_ast(function_calls: &str) -> Result<Vec<ast::Expr>,
 pub fn parse_from_ast_to_structured(
     function_calls_ast: &[ast::Expr],
     raw_function_calls: &str,
+) -> Result<Vec<FunctionCallHygienic>, String> {
+    parse_from_ast_to_structured_with_options(function_calls_ast, raw_function_calls, false)
+}
+
+/// Same as [`parse_from_ast_to_structured`], but with `allow_bareword_enum` a model's
+/// unquoted enum identifier (e.g. `cabin=Business`) is accepted and treated as the
+/// string literal `"Business"` instead of failing to parse.
+pub fn parse_from_ast_to_structured_with_options(
+    function_calls_ast: &[ast::Expr],
+    raw_function_calls: &str,
+    allow_bareword_enum: bool,
 ) -> Result<Vec<FunctionCallHygienic>, String> {
     let mut function_calls = Vec::new();
     for expr in function_calls_ast {
         let ast::Expr::Call(call_expr) = expr else {
             return Err("Expected a function call expression".to_string());
         };
-        let func_name = match &*call_expr.func {
-            ast::Expr::Name(name_expr) => name_expr.id.clone(),
-            _ => {
-                return Err(format!(
-                    "Unsupported function expression type: {:?}",
-                    call_expr.func
-                ));
-            }
-        };
+        // Accept `MessageApi.send_message(...)`/`api.get_products(...)`-style attribute
+        // chains in addition to bare names, then strip the leading class/module prefix:
+        // the dispatch table is keyed by bare method names.
+        let resolved_func_name = resolve_func_name(&call_expr.func)?;
+        let func_name = resolved_func_name
+            .rsplit('.')
+            .next()
+            .unwrap_or(&resolved_func_name)
+            .to_string();
         let mut parameters = IndexMap::new();
         for keyword in &call_expr.keywords {
             if let Some(arg_name) = &keyword.arg {
-                let arg_value = ast_expr_to_structured(&keyword.value, raw_function_calls)?;
+                let arg_value = ast_expr_to_structured_with_options(
+                    &keyword.value,
+                    raw_function_calls,
+                    allow_bareword_enum,
+                )?;
                 parameters.insert(arg_name.to_string(), arg_value);
             }
         }
@@ -66,26 +81,123 @@ pub fn parse_from_ast_to_structured(
     Ok(function_calls)
 }
 
+/// Joins a callee expression's attribute chain into a dotted name, e.g.
+/// `Travel.reserve_flight` for `ast::Expr::Attribute`, or just the identifier for a bare
+/// `ast::Expr::Name`. Used to accept models that qualify calls with a class/module name.
+fn resolve_func_name(expr: &ast::Expr) -> Result<String, String> {
+    match expr {
+        ast::Expr::Name(name_expr) => Ok(name_expr.id.to_string()),
+        ast::Expr::Attribute(attribute_expr) => {
+            let base = resolve_func_name(&attribute_expr.value)?;
+            Ok(format!("{}.{}", base, attribute_expr.attr))
+        }
+        _ => Err(format!("Unsupported function expression type: {:?}", expr)),
+    }
+}
+
 pub fn decode_function_list(function_calls: &str) -> Result<Vec<FunctionCallHygienic>, String> {
+    decode_function_list_with_options(function_calls, false)
+}
+
+/// Scans for the first outermost, balanced `[...]` span in `text`, tolerating any prose
+/// or markdown fencing around it (e.g. a model answering `` "Here you go:\n```python\n[foo(x=1)]\n```" ``).
+/// Mirrors `extract_outermost_bracket_content` from the original Python evaluator.
+pub fn extract_outermost_bracket_content(text: &str) -> Option<&str> {
+    let mut start = None;
+    let mut depth = 0usize;
+    for (i, ch) in text.char_indices() {
+        match ch {
+            '[' => {
+                if depth == 0 {
+                    start = Some(i);
+                }
+                depth += 1;
+            }
+            ']' => {
+                if depth > 0 {
+                    depth -= 1;
+                    if depth == 0 {
+                        if let Some(start) = start {
+                            return Some(&text[start..i + ch.len_utf8()]);
+                        }
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Same as [`decode_function_list`], but first tries to isolate the `[...]` call list from
+/// surrounding prose/markdown fences via [`extract_outermost_bracket_content`] before
+/// parsing, so wrapped responses like `` "Here you go:\n```python\n[foo(x=1)]\n```" `` still
+/// decode. Falls back to parsing the raw string if no bracketed span is found or the
+/// isolated span fails to parse (e.g. a `]` inside a string literal ended the span early),
+/// so it never does strictly worse than [`decode_function_list`].
+pub fn decode_function_list_lenient(function_calls: &str) -> Result<Vec<FunctionCallHygienic>, String> {
+    let isolated = extract_outermost_bracket_content(function_calls).unwrap_or(function_calls);
+    decode_function_list(isolated).or_else(|_| decode_function_list(function_calls))
+}
+
+/// Same as [`decode_function_list`], but with `allow_bareword_enum` an unquoted enum
+/// identifier in the call list (e.g. `cabin=Business`) is accepted as its literal text
+/// instead of failing the parse. Defaults to off in [`decode_function_list`] to preserve
+/// existing strictness.
+pub fn decode_function_list_with_options(
+    function_calls: &str,
+    allow_bareword_enum: bool,
+) -> Result<Vec<FunctionCallHygienic>, String> {
     let function_calls_ast = parse_from_string_to_ast(function_calls)?;
-    let function_calls_structured = parse_from_ast_to_structured(&function_calls_ast, function_calls)?;
-    Ok(function_calls_structured)
+    parse_from_ast_to_structured_with_options(&function_calls_ast, function_calls, allow_bareword_enum)
+}
+
+/// Parses a raw `"[func(...), ...]"` call-list string into AST nodes without converting
+/// to `FunctionCallHygienic`; a thin alias over [`parse_from_string_to_ast`] for callers
+/// that only need to validate shape before committing to the full structured decode.
+pub fn decode_ast(function_calls: &str) -> Result<Vec<ast::Expr>, String> {
+    parse_from_string_to_ast(function_calls)
+}
+
+/// Checks that every element of a decoded call list is actually a function call
+/// expression, i.e. that [`decode_ast`] didn't hand back something like a bare literal
+/// that happened to parse as a one-element list.
+pub fn is_function_call_format_valid(decoded: &[ast::Expr]) -> bool {
+    !decoded.is_empty() && decoded.iter().all(|expr| matches!(expr, ast::Expr::Call(_)))
 }
 
 
 
 pub fn ast_expr_to_structured(expr: &ast::Expr, raw_function_calls: &str) -> Result<serde_json::Value, String> {
+    ast_expr_to_structured_with_options(expr, raw_function_calls, false)
+}
+
+/// Same as [`ast_expr_to_structured`], but with `allow_bareword_enum` an unquoted name
+/// that isn't `True`/`False`/`None` is accepted as the string literal of its identifier
+/// text, instead of erroring.
+pub fn ast_expr_to_structured_with_options(
+    expr: &ast::Expr,
+    raw_function_calls: &str,
+    allow_bareword_enum: bool,
+) -> Result<serde_json::Value, String> {
     match expr {
         ast::Expr::Constant(c) => match &c.value {
+            // rustpython_parser already hands back an owned, UTF-8 `String` here, so
+            // multibyte string literals (e.g. Chinese merchant names, emoji) round-trip
+            // through `FunctionCallHygienic` and `values_equivalent` without extra handling.
             ast::Constant::Str(s) => Ok(serde_json::Value::String(s.to_string())),
             ast::Constant::Int(i) => {
-                // Try to convert to i64, fallback to string for big ints
-                let val = i.to_string().parse::<i64>().expect(&format!("Failed to parse integer: {}", i));
-                Ok(serde_json::Value::Number(serde_json::Number::from(val)))
+                // Most integer literals fit in i64; fall back to representing the literal
+                // as a JSON string so an oversized literal (e.g. a 30-digit id) doesn't
+                // panic the whole evaluation run.
+                match i.to_string().parse::<i64>() {
+                    Ok(val) => Ok(serde_json::Value::Number(serde_json::Number::from(val))),
+                    Err(_) => Ok(serde_json::Value::String(i.to_string())),
+                }
             }
-            ast::Constant::Float(f) => Ok(serde_json::Value::Number(
-                serde_json::Number::from_f64(*f).expect(&format!("failed to parse float: {}", f))
-            )),
+            ast::Constant::Float(f) => serde_json::Number::from_f64(*f)
+                .map(serde_json::Value::Number)
+                .ok_or_else(|| format!("Unsupported float constant (NaN or infinite): {}", f)),
             ast::Constant::Bool(b) => Ok(serde_json::Value::Bool(*b)),
             ast::Constant::None => Ok(serde_json::Value::Null),
             ast::Constant::Ellipsis => Ok(serde_json::Value::String("...".to_string())),
@@ -93,37 +205,45 @@ pub fn ast_expr_to_structured(expr: &ast::Expr, raw_function_calls: &str) -> Res
             // _ => panic!("Unsupported constant type: {:?}", c.value),
         },
         ast::Expr::UnaryOp(u) => {
+            let operand = ast_expr_to_structured_with_options(&u.operand, raw_function_calls, allow_bareword_enum);
             match u.op {
-                ast::UnaryOp::USub => {
-                    let operand = ast_expr_to_structured(&u.operand, raw_function_calls)?;
-                    let negated = negate_json_value(&operand).expect("Cannot negate a json value");
-                    Ok(negated)
-                }
-                // _ => Err(format!("Unsupported unary operator: {:?}", u.op)),
+                ast::UnaryOp::USub => negate_json_value(&operand?),
+                // identity on numbers; nested negation (e.g. `--3`) composes correctly
+                // since the inner USub is resolved by the recursive call above.
+                ast::UnaryOp::UAdd => match operand? {
+                    value @ serde_json::Value::Number(_) => Ok(value),
+                    other => Err(format!("Unary '+' is only supported on numbers, got: {:?}", other)),
+                },
                 _ => Err(format!("Unsupported unary operator: {:?}", u.op)),
             }
         }
         ast::Expr::List(l) => {
-            let items: Result<Vec<serde_json::Value>, String> =
-                l.elts.iter().map(|e| ast_expr_to_structured(e, raw_function_calls)).collect();
+            let items: Result<Vec<serde_json::Value>, String> = l
+                .elts
+                .iter()
+                .map(|e| ast_expr_to_structured_with_options(e, raw_function_calls, allow_bareword_enum))
+                .collect();
             Ok(serde_json::Value::Array(items?))
         }
         ast::Expr::Tuple(t) => {
-            let items: Result<Vec<serde_json::Value>, String> =
-                t.elts.iter().map(|e| ast_expr_to_structured(e, raw_function_calls)).collect();
+            let items: Result<Vec<serde_json::Value>, String> = t
+                .elts
+                .iter()
+                .map(|e| ast_expr_to_structured_with_options(e, raw_function_calls, allow_bareword_enum))
+                .collect();
             Ok(serde_json::Value::Array(items?))
         }
         ast::Expr::Dict(d) => {
             let mut map = serde_json::Map::new();
             for (key_opt, value) in d.keys.iter().zip(d.values.iter()) {
                 if let Some(key) = key_opt {
-                    let key_val = ast_expr_to_structured(key, raw_function_calls)?;
+                    let key_val = ast_expr_to_structured_with_options(key, raw_function_calls, allow_bareword_enum)?;
                     let key_str = match key_val {
                         serde_json::Value::String(s) => s,
                         // _ => key_val.to_string(),
                         _ => Err(format!("Unsupported dict key type: {:?}", key_val))?,
                     };
-                    let val = ast_expr_to_structured(value, raw_function_calls)?;
+                    let val = ast_expr_to_structured_with_options(value, raw_function_calls, allow_bareword_enum)?;
                     map.insert(key_str, val);
                 }
             }
@@ -137,26 +257,72 @@ pub fn ast_expr_to_structured(expr: &ast::Expr, raw_function_calls: &str) -> Res
                 "None" | "null" => Ok(serde_json::Value::Null),
                 // other => Ok(serde_json::Value::String(other.to_string())),
                 // other => panic!("Unsupported name expression: {}", other),
+                other if allow_bareword_enum => Ok(serde_json::Value::String(other.to_string())),
                 _ => return Err(format!("Failed to parse python expression: unsupported name expression: {}", n.id)),
             }
         }
         ast::Expr::Call(c) => {
-            // // Handle function calls - extract function name and keyword arguments
-            // let func_name = resolve_func_name(&c.func)?;
-            // let mut args_map = serde_json::Map::new();
-
-            // for keyword in &c.keywords {
-            //     if let Some(ref arg_name) = keyword.arg {
-            //         let val = ast_to_structured(&keyword.value)?;
-            //         args_map.insert(arg_name.to_string(), val);
-            //     }
-            // }
-
-            // let mut result = serde_json::Map::new();
-            // result.insert(func_name, Value::Object(args_map));
-            // Ok(Value::Object(result))
-            // panic!("Function call expressions are not supported in parameter values: {:?}, raw_functions: {}", c, raw_function_calls)
-            return Err("Function calls are not allowed in parameter values".to_string());
+            // Models routinely wrap a parameter value in a constructor-like call, e.g.
+            // `add_reminder(time=datetime(2024,7,15))`. `datetime(...)` specifically is
+            // normalized to a "YYYY-MM-DD[ HH:MM:SS]" string so it round-trips the way a
+            // plain string literal would; anything else with only keyword arguments is
+            // passed through as `{func_name: {kwargs...}}` so the information isn't lost.
+            // A call with positional arguments (other than the `datetime` case below) has
+            // no sensible structured representation, so it's rejected instead of panicking.
+            let ast::Expr::Name(name_expr) = &*c.func else {
+                return Err(format!(
+                    "Unsupported function expression type in parameter value: {:?}",
+                    c.func
+                ));
+            };
+            let func_name = name_expr.id.as_str();
+            if func_name == "datetime" && c.keywords.is_empty() {
+                let components: Result<Vec<i64>, String> = c
+                    .args
+                    .iter()
+                    .map(|arg| {
+                        let ast::Expr::Constant(constant) = arg else {
+                            return Err(format!("Unsupported datetime(...) argument: {:?}", arg));
+                        };
+                        let ast::Constant::Int(i) = &constant.value else {
+                            return Err(format!("Unsupported datetime(...) argument: {:?}", constant.value));
+                        };
+                        i.to_string()
+                            .parse::<i64>()
+                            .map_err(|e| format!("Invalid datetime(...) argument: {}", e))
+                    })
+                    .collect();
+                let components = components?;
+                let [year, month, day, rest @ ..] = components.as_slice() else {
+                    return Err("datetime(...) requires at least year, month, and day".to_string());
+                };
+                let get = |idx: usize| rest.get(idx).copied().unwrap_or(0);
+                return if rest.is_empty() {
+                    Ok(serde_json::Value::String(format!("{:04}-{:02}-{:02}", year, month, day)))
+                } else {
+                    Ok(serde_json::Value::String(format!(
+                        "{:04}-{:02}-{:02} {:02}:{:02}:{:02}",
+                        year, month, day, get(0), get(1), get(2)
+                    )))
+                };
+            }
+            if !c.args.is_empty() {
+                return Err(format!(
+                    "Unsupported positional arguments in nested function call {}(...) within parameter value, raw_functions: {}",
+                    func_name, raw_function_calls
+                ));
+            }
+            let mut kwargs = serde_json::Map::new();
+            for keyword in &c.keywords {
+                let Some(arg_name) = &keyword.arg else {
+                    return Err(format!("Unsupported **kwargs expansion in nested function call {}(...)", func_name));
+                };
+                let val = ast_expr_to_structured(&keyword.value, raw_function_calls)?;
+                kwargs.insert(arg_name.to_string(), val);
+            }
+            let mut result = serde_json::Map::new();
+            result.insert(func_name.to_string(), serde_json::Value::Object(kwargs));
+            Ok(serde_json::Value::Object(result))
         }
         // _ => Err(format!("Unsupported AST type: {:?}", expr)),
         // _ => panic!("Unknown AST type: {:?}, raw function calls: {}", expr, raw_function_calls),
@@ -243,3 +409,177 @@ pub fn decode_function_list_with_fc_mode(
         decode_function_list(function_calls)
     }
 }
+
+#[cfg(test)]
+mod unicode_handling_tests {
+    use super::*;
+
+    #[test]
+    fn decode_function_list_round_trips_a_chinese_string_argument() {
+        let calls = decode_function_list("[search_products(merchant_name='海底捞')]").unwrap();
+        assert_eq!(calls.len(), 1);
+        assert_eq!(
+            calls[0].parameters.get("merchant_name").unwrap().as_str().unwrap(),
+            "海底捞"
+        );
+    }
+}
+
+#[cfg(test)]
+mod decode_ast_tests {
+    use super::*;
+
+    #[test]
+    fn decode_ast_accepts_a_parallel_call_and_is_function_call_format_valid_confirms_it() {
+        let decoded = decode_ast("[get_products(keyword='pizza'), search_products(keyword='bibimbap')]").unwrap();
+        assert_eq!(decoded.len(), 2);
+        assert!(is_function_call_format_valid(&decoded));
+    }
+
+    #[test]
+    fn decode_ast_accepts_a_nested_dict_argument() {
+        let decoded = decode_ast("[reserve_flight(details={'cabin': 'Business Class', 'baggage': {'count': 2}})]").unwrap();
+        assert_eq!(decoded.len(), 1);
+        assert!(is_function_call_format_valid(&decoded));
+    }
+
+    #[test]
+    fn decode_ast_accepts_a_negative_number_argument() {
+        let decoded = decode_ast("[recharge_balance(amount=-50)]").unwrap();
+        assert_eq!(decoded.len(), 1);
+        assert!(is_function_call_format_valid(&decoded));
+    }
+
+    #[test]
+    fn is_function_call_format_valid_rejects_a_bare_literal_list() {
+        let decoded = decode_ast("[42]").unwrap();
+        assert!(!is_function_call_format_valid(&decoded));
+    }
+}
+
+#[cfg(test)]
+mod nested_call_argument_tests {
+    use super::*;
+
+    #[test]
+    fn a_nested_datetime_call_is_normalized_to_a_string() {
+        let calls = decode_function_list("[add_reminder(time=datetime(2024,7,15))]").unwrap();
+        assert_eq!(calls.len(), 1);
+        assert_eq!(calls[0].parameters["time"], serde_json::Value::String("2024-07-15".to_string()));
+    }
+
+    #[test]
+    fn a_nested_datetime_call_with_a_time_component_is_normalized_to_a_string() {
+        let calls = decode_function_list("[add_reminder(time=datetime(2024,7,15,9,30,0))]").unwrap();
+        assert_eq!(calls.len(), 1);
+        assert_eq!(
+            calls[0].parameters["time"],
+            serde_json::Value::String("2024-07-15 09:30:00".to_string())
+        );
+    }
+
+    #[test]
+    fn a_nested_call_with_positional_arguments_other_than_datetime_is_rejected() {
+        let result = decode_function_list("[add_reminder(time=make_time(2024,7,15))]");
+        assert!(result.is_err());
+    }
+}
+
+#[cfg(test)]
+mod oversized_literal_tests {
+    use super::*;
+
+    #[test]
+    fn a_thirty_digit_integer_literal_falls_back_to_a_string_instead_of_panicking() {
+        let calls = decode_function_list("[recharge_balance(amount=123456789012345678901234567890)]").unwrap();
+        assert_eq!(calls.len(), 1);
+        assert_eq!(
+            calls[0].parameters["amount"],
+            serde_json::Value::String("123456789012345678901234567890".to_string())
+        );
+    }
+
+    #[test]
+    fn a_float_literal_that_overflows_to_infinity_is_a_clean_error_not_a_panic() {
+        let result = decode_function_list("[recharge_balance(amount=1e400)]");
+        assert!(result.is_err());
+    }
+}
+
+#[cfg(test)]
+mod unary_operator_tests {
+    use super::*;
+
+    #[test]
+    fn unary_plus_on_a_number_is_the_identity() {
+        let calls = decode_function_list("[recharge_balance(amount=+3)]").unwrap();
+        assert_eq!(calls[0].parameters["amount"], serde_json::json!(3));
+    }
+
+    #[test]
+    fn double_negation_composes_back_to_the_original_value() {
+        let calls = decode_function_list("[recharge_balance(amount=--3)]").unwrap();
+        assert_eq!(calls[0].parameters["amount"], serde_json::json!(3));
+    }
+
+    #[test]
+    fn a_boolean_not_returns_a_descriptive_error_instead_of_panicking() {
+        let result = decode_function_list("[recharge_balance(amount=not True)]");
+        assert!(result.is_err());
+    }
+}
+
+#[cfg(test)]
+mod bareword_enum_tests {
+    use super::*;
+
+    #[test]
+    fn a_bareword_enum_identifier_fails_to_parse_by_default() {
+        let result = decode_function_list("[reserve_flight(cabin=Economy)]");
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn a_bareword_enum_identifier_becomes_its_string_literal_in_lenient_mode() {
+        let calls = decode_function_list_with_options("[reserve_flight(cabin=Economy)]", true).unwrap();
+        assert_eq!(calls[0].parameters["cabin"], serde_json::Value::String("Economy".to_string()));
+    }
+}
+
+#[cfg(test)]
+mod attribute_style_callee_tests {
+    use super::*;
+
+    #[test]
+    fn a_class_qualified_call_resolves_to_the_bare_method_name() {
+        let calls = decode_function_list("[Travel.reserve_flight(flight_no='CA1234')]").unwrap();
+        assert_eq!(calls.len(), 1);
+        assert_eq!(calls[0].name, "reserve_flight");
+        assert_eq!(calls[0].parameters["flight_no"], serde_json::Value::String("CA1234".to_string()));
+    }
+}
+
+#[cfg(test)]
+mod decode_function_list_lenient_tests {
+    use super::*;
+
+    #[test]
+    fn a_markdown_fenced_call_list_is_isolated_and_parsed() {
+        let calls = decode_function_list_lenient("Here you go:\n```python\n[get_products(keyword='pizza')]\n```").unwrap();
+        assert_eq!(calls.len(), 1);
+        assert_eq!(calls[0].name, "get_products");
+    }
+
+    #[test]
+    fn a_prose_wrapped_call_list_is_isolated_and_parsed() {
+        let calls = decode_function_list_lenient("Sure, I'll call [get_products(keyword='pizza')] now.").unwrap();
+        assert_eq!(calls.len(), 1);
+        assert_eq!(calls[0].name, "get_products");
+    }
+
+    #[test]
+    fn strict_decode_function_list_still_fails_on_prose_wrapped_input() {
+        let result = decode_function_list("Sure, I'll call [get_products(keyword='pizza')] now.");
+        assert!(result.is_err());
+    }
+}
@@ -1,10 +1,37 @@
-use std::{path::PathBuf, sync::LazyLock};
+use std::{env, path::PathBuf, sync::LazyLock};
+
+/// Resolves a root directory from an environment variable, falling back to `default`
+/// when unset; lets a consumer embedding this crate in a different working tree
+/// relocate datasets/outputs without forking `paths.rs`.
+fn resolve_path(env_var: &str, default: &str) -> PathBuf {
+    env::var(env_var).map(PathBuf::from).unwrap_or_else(|_| PathBuf::from(default))
+}
 
 pub static BASE_DATASET_PATH: LazyLock<PathBuf> =
-    LazyLock::new(|| PathBuf::from("acebench_perturbed"));
+    LazyLock::new(|| resolve_path("ACEBENCH_DATASET_DIR", "acebench_perturbed"));
 
 pub static BASE_OUTPUT_PATH: LazyLock<PathBuf> =
-    LazyLock::new(|| PathBuf::from("acebench_perturbed_result"));
+    LazyLock::new(|| resolve_path("ACEBENCH_OUTPUT_DIR", "acebench_perturbed_result"));
 
 pub static BASE_SCORE_PATH: LazyLock<PathBuf> =
-    LazyLock::new(|| PathBuf::from("acebench_perturbed_score"));
\ No newline at end of file
+    LazyLock::new(|| resolve_path("ACEBENCH_SCORE_DIR", "acebench_perturbed_score"));
+
+#[cfg(test)]
+mod resolve_path_tests {
+    use super::*;
+
+    // BASE_DATASET_PATH/BASE_OUTPUT_PATH/BASE_SCORE_PATH are LazyLock and get forced by
+    // other tests in the same process, so setting their env vars after the fact wouldn't
+    // observably change anything; resolve_path is the testable unit underneath them.
+    #[test]
+    fn resolve_path_uses_the_env_var_when_set_and_the_default_otherwise() {
+        let env_var = "ACEBENCH_RESOLVE_PATH_TEST_VAR";
+        unsafe { std::env::remove_var(env_var) };
+        assert_eq!(resolve_path(env_var, "default_dir"), PathBuf::from("default_dir"));
+
+        unsafe { std::env::set_var(env_var, "/tmp/overridden_dir") };
+        assert_eq!(resolve_path(env_var, "default_dir"), PathBuf::from("/tmp/overridden_dir"));
+
+        unsafe { std::env::remove_var(env_var) };
+    }
+}
\ No newline at end of file
@@ -1,3 +1,5 @@
+use serde_json::Value;
+
 #[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
 pub enum PerturbationType {
     NoPerturbation,
@@ -67,4 +69,294 @@ impl PerturbationType {
         };
         folder_name.to_string()
     }
+
+    /// Inverse of [`to_folder_name`](Self::to_folder_name); `None` for an unrecognized
+    /// folder name. Lets a caller reading an existing results directory tree (e.g.
+    /// re-scoring) recover which perturbation produced a folder without reimplementing
+    /// the string match.
+    pub fn from_folder_name(name: &str) -> Option<PerturbationType> {
+        Some(match name {
+            "no_perturbation" => PerturbationType::NoPerturbation,
+            "action_a" => PerturbationType::ActionA,
+            "action_b" => PerturbationType::ActionB,
+            "action_c" => PerturbationType::ActionC,
+            "action_d" => PerturbationType::ActionD,
+            "action_e" => PerturbationType::ActionE,
+            "action_redundant" => PerturbationType::ActionRedundant,
+            "obs_param_desc" => PerturbationType::ObsParamDesc,
+            "obs_paraphrase" => PerturbationType::ObsParaphrase,
+            "obs_tool_desc" => PerturbationType::ObsToolDesc,
+            "obs_typos" => PerturbationType::ObsTypos,
+            "reward_cd" => PerturbationType::RewardCd,
+            "reward_cd_ab" => PerturbationType::RewardCdAb,
+            "reward_cd_nt" => PerturbationType::RewardCdNt,
+            "reward_td" => PerturbationType::RewardTd,
+            "reward_td_ab" => PerturbationType::RewardTdAb,
+            "reward_td_nt" => PerturbationType::RewardTdNt,
+            "transition" => PerturbationType::Transition,
+            _ => return None,
+        })
+    }
+}
+
+/// Mutates `functions` in place for whatever the given perturbation implies about the
+/// function list shown to the LLM. Every other variant is already baked into its own
+/// per-perturbation dataset file under `BASE_DATASET_PATH`, so only `ActionRedundant`,
+/// `ObsToolDesc`, and `ObsParamDesc` do anything here.
+pub fn perturb_functions(functions: &mut Vec<Value>, perturbation: PerturbationType) {
+    match perturbation {
+        PerturbationType::ActionRedundant => inject_redundant_distractors(functions),
+        PerturbationType::ObsToolDesc => blank_tool_descriptions(functions),
+        PerturbationType::ObsParamDesc => blank_parameter_descriptions(functions),
+        _ => {}
+    }
+}
+
+/// Clones the first three existing tool schemas (or all of them, when there are fewer)
+/// as near-duplicate distractors (similar but not identical name/description) and
+/// appends them; a single-tool list gets one distractor. Implements the `ActionRedundant`
+/// perturbation ("add 2-3 similar but not identical distractor tools").
+fn inject_redundant_distractors(functions: &mut Vec<Value>) {
+    if functions.is_empty() {
+        return;
+    }
+    let distractor_count = functions.len().min(3);
+    let distractors: Vec<Value> = functions
+        .iter()
+        .take(distractor_count)
+        .enumerate()
+        .map(|(index, original)| {
+            let mut distractor = original.clone();
+            if let Some(name) = distractor.get("name").and_then(|v| v.as_str()) {
+                let distractor_name = format!("{}_Similar{}", name, index + 1);
+                distractor["name"] = Value::String(distractor_name);
+            }
+            if let Some(description) = distractor.get("description").and_then(|v| v.as_str()) {
+                let distractor_description = format!("{} (alternative variant)", description);
+                distractor["description"] = Value::String(distractor_description);
+            }
+            distractor
+        })
+        .collect();
+    functions.extend(distractors);
+}
+
+/// Blanks out the `description` field of every tool schema in `functions`, leaving
+/// `name` and `parameters` (so types/required-ness stay intact for evaluation) untouched.
+/// Implements the `ObsToolDesc` perturbation ("perturb tool description").
+fn blank_tool_descriptions(functions: &mut [Value]) {
+    for function in functions.iter_mut() {
+        if let Some(object) = function.as_object_mut()
+            && object.contains_key("description")
+        {
+            object.insert("description".to_string(), Value::String(String::new()));
+        }
+    }
+}
+
+/// Blanks out the `description` field of every parameter under `parameters.properties`
+/// in `functions`, leaving the parameter names and `type`/`enum` untouched so evaluation
+/// still works. Implements the `ObsParamDesc` perturbation ("perturb parameter
+/// description").
+fn blank_parameter_descriptions(functions: &mut [Value]) {
+    for function in functions.iter_mut() {
+        let Some(properties) = function
+            .pointer_mut("/parameters/properties")
+            .and_then(|v| v.as_object_mut())
+        else {
+            continue;
+        };
+        for property in properties.values_mut() {
+            if let Some(object) = property.as_object_mut()
+                && object.contains_key("description")
+            {
+                object.insert("description".to_string(), Value::String(String::new()));
+            }
+        }
+    }
+}
+
+/// Derives a deterministic 64-bit seed from a problem id via FNV-1a, so the same id
+/// always perturbs the same way.
+fn seed_from_id(id: &str) -> u64 {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for byte in id.as_bytes() {
+        hash ^= *byte as u64;
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    hash
+}
+
+/// Tiny deterministic PRNG (xorshift64) seeded from a problem id; only used to decide
+/// where typos land, not a general-purpose RNG.
+struct DeterministicRng(u64);
+
+impl DeterministicRng {
+    fn next_u64(&mut self) -> u64 {
+        let mut x = self.0;
+        x ^= x << 13;
+        x ^= x >> 7;
+        x ^= x << 17;
+        self.0 = x;
+        x
+    }
+    fn next_below(&mut self, bound: u64) -> u64 {
+        self.next_u64() % bound
+    }
+}
+
+/// Returns the text that should be shown to the model in the prompt for `question`:
+/// the typo-perturbed text when `perturbation_type` is `ObsTypos`, otherwise `question`
+/// unchanged. The original `question` is kept separately on `AceProblem` for ground-truth
+/// record-keeping, so only the prompt-facing copy goes through this.
+pub fn perturbed_question(question: &str, id: &str, perturbation_type: PerturbationType) -> String {
+    if perturbation_type == PerturbationType::ObsTypos {
+        apply_typos(question, id)
+    } else {
+        question.to_string()
+    }
+}
+
+/// Applies a deterministic, reproducible set of typos to `question`, seeded by `id` so
+/// the same id always yields the same perturbed text: adjacent letters are occasionally
+/// swapped and a letter is occasionally dropped. Implements the `ObsTypos` perturbation
+/// ("add typos to the query"); used both by the live problem builders (via
+/// `perturbed_question`) and for regenerating/extending the pre-baked `obs_typos` dataset
+/// folder checked into this repo.
+pub fn apply_typos(question: &str, id: &str) -> String {
+    let mut rng = DeterministicRng(seed_from_id(id).max(1));
+    let mut chars: Vec<char> = question.chars().collect();
+    let mut i = 0;
+    while i + 1 < chars.len() {
+        if chars[i].is_alphabetic() && chars[i + 1].is_alphabetic() {
+            if rng.next_below(12) == 0 {
+                chars.swap(i, i + 1);
+                i += 2;
+                continue;
+            }
+            if rng.next_below(30) == 0 {
+                chars.remove(i);
+                continue;
+            }
+        }
+        i += 1;
+    }
+    chars.into_iter().collect()
+}
+
+#[cfg(test)]
+mod perturb_functions_tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn action_redundant_appends_near_duplicate_distractors_and_keeps_originals_intact() {
+        let original = vec![
+            json!({"name": "get_products", "description": "Search for products."}),
+            json!({"name": "reserve_flight", "description": "Reserve a flight."}),
+        ];
+        let mut functions = original.clone();
+
+        perturb_functions(&mut functions, PerturbationType::ActionRedundant);
+
+        assert_eq!(functions.len(), 4, "2 originals + 2 distractors");
+        assert_eq!(&functions[..2], &original[..], "original tools must be untouched");
+        assert_eq!(functions[2]["name"], "get_products_Similar1");
+        assert_eq!(functions[3]["name"], "reserve_flight_Similar2");
+        assert!(functions[2]["description"].as_str().unwrap().contains("alternative variant"));
+    }
+
+    #[test]
+    fn other_perturbation_types_leave_the_function_list_untouched() {
+        let mut functions = vec![json!({"name": "get_products", "description": "Search for products."})];
+
+        perturb_functions(&mut functions, PerturbationType::NoPerturbation);
+
+        assert_eq!(functions.len(), 1);
+    }
+
+    #[test]
+    fn obs_tool_desc_blanks_tool_descriptions_but_leaves_name_and_parameters_intact() {
+        let mut functions = vec![json!({
+            "name": "get_products",
+            "description": "Search for products by keyword.",
+            "parameters": {
+                "type": "object",
+                "properties": {"keyword": {"type": "string", "description": "search keyword"}},
+                "required": ["keyword"],
+            },
+        })];
+
+        perturb_functions(&mut functions, PerturbationType::ObsToolDesc);
+
+        assert_eq!(functions[0]["description"], "");
+        assert_eq!(functions[0]["name"], "get_products");
+        assert_eq!(functions[0]["parameters"]["properties"]["keyword"]["description"], "search keyword");
+        assert_eq!(functions[0]["parameters"]["required"], json!(["keyword"]));
+    }
+
+    #[test]
+    fn obs_param_desc_blanks_parameter_descriptions_but_leaves_types_and_names_intact() {
+        let mut functions = vec![json!({
+            "name": "get_products",
+            "description": "Search for products by keyword.",
+            "parameters": {
+                "type": "object",
+                "properties": {"keyword": {"type": "string", "description": "search keyword"}},
+                "required": ["keyword"],
+            },
+        })];
+
+        perturb_functions(&mut functions, PerturbationType::ObsParamDesc);
+
+        assert_eq!(functions[0]["description"], "Search for products by keyword.");
+        assert_eq!(functions[0]["parameters"]["properties"]["keyword"]["description"], "");
+        assert_eq!(functions[0]["parameters"]["properties"]["keyword"]["type"], "string");
+        assert_eq!(functions[0]["parameters"]["required"], json!(["keyword"]));
+    }
+}
+
+#[cfg(test)]
+mod from_folder_name_tests {
+    use super::*;
+
+    #[test]
+    fn every_perturbation_round_trips_through_to_folder_name_and_back() {
+        for perturbation in PerturbationType::all_perturbations() {
+            let folder_name = perturbation.to_folder_name();
+            assert_eq!(
+                PerturbationType::from_folder_name(&folder_name),
+                Some(perturbation),
+                "round-trip failed for folder name '{}'",
+                folder_name
+            );
+        }
+    }
+
+    #[test]
+    fn an_unrecognized_folder_name_returns_none() {
+        assert_eq!(PerturbationType::from_folder_name("not_a_real_perturbation"), None);
+    }
+}
+
+#[cfg(test)]
+mod perturbed_question_tests {
+    use super::*;
+
+    #[test]
+    fn obs_typos_perturbs_the_question_and_is_reproducible_for_the_same_id() {
+        let question = "Please reserve the cheapest flight from Beijing to Tokyo tomorrow morning.";
+        let first = perturbed_question(question, "problem_42", PerturbationType::ObsTypos);
+        let second = perturbed_question(question, "problem_42", PerturbationType::ObsTypos);
+
+        assert_ne!(first, question, "ObsTypos should actually perturb the text");
+        assert_eq!(first, second, "same id must regenerate identical perturbed text");
+    }
+
+    #[test]
+    fn other_perturbation_types_leave_the_question_untouched() {
+        let question = "Please reserve the cheapest flight from Beijing to Tokyo tomorrow morning.";
+        let result = perturbed_question(question, "problem_42", PerturbationType::NoPerturbation);
+        assert_eq!(result, question);
+    }
 }
@@ -104,6 +104,26 @@ tool: Provides the results of tool calls"#
     )
 }
 
+pub fn system_prompt_for_special_data_fc_en(time: &str) -> String {
+    format!(
+        r#"You are an AI assistant with the role name "assistant". Based on the provided API specifications and conversation history from steps 1 to t, generate the API requests that the assistant should call in step t+1. Below are two specific scenarios:
+1. When the information provided by the user is clear and unambiguous, and the problem can be resolved using the list of candidate functions:
+   - If the API parameter description does not specify the required format for the value, use the user's original text for the parameter value.
+   - When multiple tools in the candidate list can satisfy the user's needs, output all API requests.
+
+2. When the information provided by the user is unclear, incomplete, or incorrect, or the user's question exceeds the capabilities of the provided functions, you need to clearly point out these issues. The following is your strategy:
+   (1) If the user's instructions include the key details required to call the API, but the type or form of the parameter values does not match the API's definitions, ask in-depth questions to clarify and correct the details. The output format should be: ["There is incorrect value (value) for the parameters (key) in the conversation history."]
+   (2) If the user's instructions lack the key details required by the API, ask questions to obtain the necessary information. The output format should be: ["Missing necessary parameters (key1, key2, ...) for the api (ApiName)"], replacing key1, key2 with the names of the missing parameters and ApiName with the actual API name.
+   (3) If the user's request exceeds the current capabilities of your APIs, inform them that you cannot fulfill the request. The output format should be: ["Due to the limitations of the function, I cannot solve this problem."]
+   Note: The above steps have a priority order. You need to first determine whether scenario (1) applies. If it does, output according to the requirements in (1). Pay attention to distinguishing between scenarios (1) and (2).
+
+{time}
+
+Role Descriptions:
+user: User
+assistant: The AI assistant role that makes API requests"#
+    )
+}
 
 /// Multi-step agent prompt (agent decides when to finish)
 /// Used for data_agent_multi_step - no user simulation, agent completes task autonomously
@@ -16,4 +16,8 @@ pub struct PythonTask {
 pub struct PythonResponse {
     pub identifier: String,
     pub response: String,
+    // set when the driver is re-submitting a response for a step that already ran
+    // (e.g. retrying a failed LLM call), so the harness does not double-count the step
+    #[serde(default)]
+    pub is_retry: bool,
 }
@@ -1,3 +1,6 @@
+use std::collections::HashMap;
+
+use chrono::{NaiveDate, NaiveDateTime};
 use indexmap::IndexMap;
 use serde::{Deserialize, Serialize};
 
@@ -47,6 +50,24 @@ pub struct SearchRemindersArgs {
     pub keyword: String,
 }
 
+#[derive(Deserialize, Clone)]
+pub struct UpdateReminderArgs {
+    pub reminder_id: usize,
+    pub new_title: Option<String>,
+    pub new_description: Option<String>,
+    pub new_time: Option<String>,
+}
+
+#[derive(Deserialize, Clone)]
+pub struct MarkReminderNotifiedArgs {
+    pub reminder_id: usize,
+}
+
+#[derive(Deserialize, Clone)]
+pub struct ViewRemindersByDateArgs {
+    pub date: String,
+}
+
 impl Default for ReminderApi {
     fn default() -> Self {
         let reminder_list: IndexMap<String, Reminder> = vec![
@@ -98,6 +119,9 @@ impl ReminderApi {
         if self.reminder_list.len() >= self.max_capacity.unwrap() {
             return ExecutionResult::error("Reminder capacity is full. Unable to add a new reminder.".to_string());
         }
+        if NaiveDateTime::parse_from_str(&time, "%Y-%m-%d %H:%M").is_err() {
+            return ExecutionResult::error(format!("Invalid time format: '{}'. Expected YYYY-MM-DD HH:MM.", time));
+        }
         *self.reminder_id_counter.as_mut().unwrap() += 1;
         let reminder_id = self.reminder_id_counter.unwrap();
         self.reminder_list.insert(
@@ -124,6 +148,45 @@ impl ReminderApi {
         self.reminder_list.swap_remove(&reminder_id_str);
         ExecutionResult::success(format!("Reminder ID {} was successfully deleted.", reminder_id))
     }
+    pub fn update_reminder(
+        &mut self,
+        reminder_id: usize,
+        new_title: Option<String>,
+        new_description: Option<String>,
+        new_time: Option<String>,
+    ) -> ExecutionResult {
+        if !self.base_api.logged_in {
+            return ExecutionResult::error("Device not logged in. Unable to update the specified reminder.".to_string());
+        }
+        if let Some(new_time) = &new_time && NaiveDateTime::parse_from_str(new_time, "%Y-%m-%d %H:%M").is_err() {
+            return ExecutionResult::error(format!("Invalid time format: '{}'. Expected YYYY-MM-DD HH:MM.", new_time));
+        }
+        let reminder_id_str = reminder_id.to_string();
+        let Some(reminder) = self.reminder_list.get_mut(&reminder_id_str) else {
+            return ExecutionResult::error("Reminder ID does not exist.".to_string());
+        };
+        if let Some(new_title) = new_title {
+            reminder.title = new_title;
+        }
+        if let Some(new_description) = new_description {
+            reminder.description = new_description;
+        }
+        if let Some(new_time) = new_time {
+            reminder.time = new_time;
+        }
+        ExecutionResult::success(format!("Reminder ID {} was successfully updated.", reminder_id))
+    }
+    pub fn mark_reminder_notified(&mut self, reminder_id: usize) -> ExecutionResult {
+        if !self.base_api.logged_in {
+            return ExecutionResult::error("Device not logged in. Unable to update the specified reminder.".to_string());
+        }
+        let reminder_id_str = reminder_id.to_string();
+        let Some(reminder) = self.reminder_list.get_mut(&reminder_id_str) else {
+            return ExecutionResult::error("Reminder ID does not exist.".to_string());
+        };
+        reminder.notified = true;
+        ExecutionResult::success(format!("Reminder ID {} was marked as notified.", reminder_id))
+    }
     pub fn view_all_reminders(&self) -> ExecutionResult {
         if self.reminder_list.is_empty() {
             return ExecutionResult::error("No reminders found.".to_string());
@@ -133,6 +196,7 @@ impl ReminderApi {
         ExecutionResult::success(reminders_str)
     }
     // the following function seems to apply only to Chinese version, but it somehow appears in function descriptions in English version as well
+    // brings reminder search to parity with MessageApi::search_messages / FoodPlatform::search_orders
     pub fn search_reminders(&self, keyword: String) -> ExecutionResult {
         if !self.base_api.logged_in {
             return ExecutionResult::error("Device not logged in. Unable to search reminders.".to_string());
@@ -150,13 +214,81 @@ impl ReminderApi {
         ExecutionResult::success(reminders_str)
     }
 
+    pub fn view_reminders_by_date(&self, date: String) -> ExecutionResult {
+        if !self.base_api.logged_in {
+            return ExecutionResult::error("Device not logged in. Unable to view reminders.".to_string());
+        }
+        let Ok(target_date) = NaiveDate::parse_from_str(&date, "%Y-%m-%d") else {
+            return ExecutionResult::error(format!("Invalid date format: '{}'. Expected YYYY-MM-DD.", date));
+        };
+        let matched_reminders: Vec<&Reminder> = self.reminder_list.values()
+            .filter(|reminder| {
+                NaiveDateTime::parse_from_str(&reminder.time, "%Y-%m-%d %H:%M")
+                    .map(|time| time.date() == target_date)
+                    .unwrap_or(false)
+            })
+            .collect();
+
+        if matched_reminders.is_empty() {
+            return ExecutionResult::error("No reminders found on the given date.".to_string());
+        }
+
+        let reminders_str = serde_json::to_string(&matched_reminders).unwrap();
+        ExecutionResult::success(reminders_str)
+    }
+
+    pub fn get_reminder_utilization(&self) -> ExecutionResult {
+        if !self.base_api.logged_in {
+            return ExecutionResult::error("Device not logged in. Unable to check reminder utilization.".to_string());
+        }
+        let used = self.reminder_list.len();
+        let capacity = self.max_capacity.unwrap();
+        let remaining = capacity.saturating_sub(used);
+        ExecutionResult::success(
+            serde_json::json!({
+                "used": used,
+                "capacity": capacity,
+                "remaining": remaining,
+            })
+            .to_string(),
+        )
+    }
+
     pub fn equals_ground_truth(&self, possible_answer: &ReminderApi) -> Result<(), String> {
         self.base_api.equals_ground_truth(&possible_answer.base_api)?;
         if let Some(possible_answer_max_capacity) = &possible_answer.max_capacity && self.max_capacity.as_ref().unwrap() != possible_answer_max_capacity {
             Err(format!("Reminder max capacity does not match. Expected: {}, got {}", possible_answer_max_capacity, self.max_capacity.as_ref().unwrap()))?;
         }
-        if self.reminder_list != possible_answer.reminder_list {
-            Err(format!("Reminder lists do not match. Expected: {:?}, got: {:?}", possible_answer.reminder_list, self.reminder_list))?;
+        // Compare as an unordered multiset of (title, description, time, notified),
+        // ignoring internal map keys and `reminder_id`: those are assigned by insertion
+        // order/counter and can legitimately differ between two lists that describe the
+        // same set of reminders.
+        let mut counts: HashMap<(&str, &str, &str, bool), i32> = HashMap::new();
+        for reminder in self.reminder_list.values() {
+            *counts
+                .entry((&reminder.title, &reminder.description, &reminder.time, reminder.notified))
+                .or_insert(0) += 1;
+        }
+        for reminder in possible_answer.reminder_list.values() {
+            *counts
+                .entry((&reminder.title, &reminder.description, &reminder.time, reminder.notified))
+                .or_insert(0) -= 1;
+        }
+        let extra: Vec<_> = counts
+            .iter()
+            .filter(|(_, count)| **count > 0)
+            .map(|(sig, _)| sig)
+            .collect();
+        let missing: Vec<_> = counts
+            .iter()
+            .filter(|(_, count)| **count < 0)
+            .map(|(sig, _)| sig)
+            .collect();
+        if !extra.is_empty() || !missing.is_empty() {
+            Err(format!(
+                "Reminder lists do not match. Missing from output: {:?}. Unexpected in output: {:?}.",
+                missing, extra
+            ))?;
         }
         if let Some(possible_answer_reminder_id_counter) = &possible_answer.reminder_id_counter && self.reminder_id_counter.as_ref().unwrap() != possible_answer_reminder_id_counter {
             Err(format!("Reminder ID counters do not match. Expected: {}, got: {}", possible_answer_reminder_id_counter, self.reminder_id_counter.as_ref().unwrap()))?;
@@ -164,3 +296,225 @@ impl ReminderApi {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod reminder_utilization_tests {
+    use super::*;
+
+    #[test]
+    fn utilization_reflects_seeded_counts_and_updates_after_add_and_delete() {
+        let mut api = ReminderApi::default();
+        let seeded_used = api.reminder_list.len();
+        let capacity = api.max_capacity.unwrap();
+
+        let result = api.get_reminder_utilization();
+        let value: serde_json::Value = serde_json::from_str(&result.message).unwrap();
+        assert_eq!(value["used"], seeded_used);
+        assert_eq!(value["capacity"], capacity);
+        assert_eq!(value["remaining"], capacity - seeded_used);
+
+        assert!(api.add_reminder("Gym".to_string(), "Leg day".to_string(), "2024-07-18 09:00".to_string()).is_success());
+        let after_add: serde_json::Value = serde_json::from_str(&api.get_reminder_utilization().message).unwrap();
+        assert_eq!(after_add["used"], seeded_used + 1);
+        assert_eq!(after_add["remaining"], capacity - seeded_used - 1);
+
+        assert!(api.delete_reminder(1).is_success());
+        let after_delete: serde_json::Value = serde_json::from_str(&api.get_reminder_utilization().message).unwrap();
+        assert_eq!(after_delete["used"], seeded_used);
+        assert_eq!(after_delete["remaining"], capacity - seeded_used);
+    }
+}
+
+#[cfg(test)]
+mod search_reminders_tests {
+    use super::*;
+
+    #[test]
+    fn matches_title_and_description_case_insensitively() {
+        let api = ReminderApi::default();
+
+        let by_title = api.search_reminders("doctor".to_string());
+        assert!(by_title.is_success(), "{}", by_title.message);
+        assert!(by_title.message.contains("Doctor's Appointment"));
+
+        let by_description = api.search_reminders("project review".to_string());
+        assert!(by_description.is_success(), "{}", by_description.message);
+        assert!(by_description.message.contains("Team Meeting"));
+    }
+
+    #[test]
+    fn returns_error_when_nothing_matches() {
+        let api = ReminderApi::default();
+        let result = api.search_reminders("nonexistent keyword".to_string());
+        assert!(!result.is_success());
+    }
+}
+
+#[cfg(test)]
+mod equals_ground_truth_multiset_tests {
+    use super::*;
+
+    fn reminder_api_with(entries: &[(&str, &str, &str, &str)]) -> ReminderApi {
+        let mut api = ReminderApi::default();
+        api.reminder_list.clear();
+        for (key, title, description, time) in entries {
+            api.reminder_list.insert(
+                key.to_string(),
+                Reminder {
+                    reminder_id: key.parse().unwrap(),
+                    title: title.to_string(),
+                    description: description.to_string(),
+                    time: time.to_string(),
+                    notified: false,
+                },
+            );
+        }
+        api
+    }
+
+    #[test]
+    fn same_reminders_in_a_different_insertion_order_compare_equal() {
+        let api_a = reminder_api_with(&[
+            ("1", "Gym", "Leg day", "2024-07-18 09:00"),
+            ("2", "Dentist", "Checkup", "2024-07-19 10:00"),
+        ]);
+        let api_b = reminder_api_with(&[
+            ("2", "Dentist", "Checkup", "2024-07-19 10:00"),
+            ("1", "Gym", "Leg day", "2024-07-18 09:00"),
+        ]);
+
+        assert!(api_a.equals_ground_truth(&api_b).is_ok());
+    }
+
+    #[test]
+    fn a_genuinely_different_reminder_is_reported_as_missing_and_extra() {
+        let api_a = reminder_api_with(&[("1", "Gym", "Leg day", "2024-07-18 09:00")]);
+        let api_b = reminder_api_with(&[("1", "Dentist", "Checkup", "2024-07-19 10:00")]);
+
+        let result = api_a.equals_ground_truth(&api_b);
+        assert!(result.is_err());
+        let message = result.unwrap_err();
+        assert!(message.contains("Missing from output"));
+        assert!(message.contains("Unexpected in output"));
+    }
+}
+
+#[cfg(test)]
+mod update_reminder_tests {
+    use super::*;
+
+    #[test]
+    fn updating_only_the_time_leaves_title_and_description_unchanged() {
+        let mut api = ReminderApi::default();
+
+        let result = api.update_reminder(1, None, None, Some("2024-07-15 10:00".to_string()));
+        assert!(result.is_success(), "{}", result.message);
+
+        let reminder = api.reminder_list.get("1").unwrap();
+        assert_eq!(reminder.title, "Doctor's Appointment");
+        assert_eq!(reminder.description, "Visit Dr. Smith for a checkup.");
+        assert_eq!(reminder.time, "2024-07-15 10:00");
+    }
+
+    #[test]
+    fn updating_a_missing_reminder_id_is_an_error() {
+        let mut api = ReminderApi::default();
+
+        let result = api.update_reminder(9999, Some("new title".to_string()), None, None);
+        assert!(!result.is_success());
+        assert!(result.message.contains("does not exist"));
+    }
+}
+
+#[cfg(test)]
+mod mark_reminder_notified_tests {
+    use super::*;
+
+    #[test]
+    fn marking_one_reminder_notified_leaves_the_others_flags_false() {
+        let mut api = ReminderApi::default();
+
+        let result = api.mark_reminder_notified(1);
+        assert!(result.is_success(), "{}", result.message);
+
+        assert!(api.reminder_list.get("1").unwrap().notified);
+        for (id, reminder) in api.reminder_list.iter() {
+            if id != "1" {
+                assert!(!reminder.notified, "reminder {} should remain unnotified", id);
+            }
+        }
+    }
+
+    #[test]
+    fn marking_a_missing_reminder_id_is_an_error() {
+        let mut api = ReminderApi::default();
+
+        let result = api.mark_reminder_notified(9999);
+        assert!(!result.is_success());
+        assert!(result.message.contains("does not exist"));
+    }
+}
+
+#[cfg(test)]
+mod view_reminders_by_date_tests {
+    use super::*;
+
+    #[test]
+    fn reminders_on_the_given_day_are_returned() {
+        let api = ReminderApi::default();
+
+        let result = api.view_reminders_by_date("2024-07-15".to_string());
+        assert!(result.is_success(), "{}", result.message);
+        assert!(result.message.contains("Doctor's Appointment"));
+        assert!(!result.message.contains("Team Meeting"));
+    }
+
+    #[test]
+    fn an_unparseable_date_is_an_error() {
+        let api = ReminderApi::default();
+
+        let result = api.view_reminders_by_date("not-a-date".to_string());
+        assert!(!result.is_success());
+        assert!(result.message.contains("Invalid date format"));
+    }
+
+    #[test]
+    fn a_date_with_no_matching_reminders_is_an_error() {
+        let api = ReminderApi::default();
+
+        let result = api.view_reminders_by_date("2099-01-01".to_string());
+        assert!(!result.is_success());
+        assert!(result.message.contains("No reminders found"));
+    }
+}
+
+#[cfg(test)]
+mod time_format_validation_tests {
+    use super::*;
+
+    #[test]
+    fn add_reminder_rejects_a_malformed_time_string() {
+        let mut api = ReminderApi::default();
+
+        let result = api.add_reminder("Lunch".to_string(), "Lunch with Alex".to_string(), "tomorrow 9am".to_string());
+        assert!(!result.is_success());
+        assert!(result.message.contains("Invalid time format"));
+    }
+
+    #[test]
+    fn add_reminder_accepts_the_default_data_s_format() {
+        let mut api = ReminderApi::default();
+
+        let result = api.add_reminder("Lunch".to_string(), "Lunch with Alex".to_string(), "2024-08-01 12:00".to_string());
+        assert!(result.is_success(), "{}", result.message);
+    }
+
+    #[test]
+    fn update_reminder_rejects_a_malformed_time_string() {
+        let mut api = ReminderApi::default();
+
+        let result = api.update_reminder(1, None, None, Some("tomorrow 9am".to_string()));
+        assert!(!result.is_success());
+        assert!(result.message.contains("Invalid time format"));
+    }
+}
@@ -112,12 +112,19 @@ impl Reservation {
 
 /// Travel API state (does NOT inherit from BaseApi)
 /// Python: scenariosen/travel.py
+fn default_current_time() -> String {
+    "2024-07-14 06:00:00".to_string()
+}
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Travel {
     pub users: IndexMap<String, TravelUser>, // key: user_id (e.g., "user1")
     #[serde(default)]
     pub flights: Option<Vec<Flight>>,
     pub reservations: Vec<Reservation>,
+    // the evaluation "now" used by time-sensitive methods (e.g. cancellation fee
+    // calculation) when a call doesn't supply its own `current_time` override
+    #[serde(default = "default_current_time")]
+    pub current_time: String,
 }
 
 impl Default for Travel {
@@ -235,7 +242,7 @@ impl Default for Travel {
                 business_price: 2500,
             },
             Flight {
-                flight_no: "CZ1765".to_string(),
+                flight_no: "CZ1785".to_string(),
                 origin: "Nanjing".to_string(),
                 destination: "Shenzhen".to_string(),
                 depart_time: "2024-07-18 12:30:00".to_string(),
@@ -268,7 +275,7 @@ impl Default for Travel {
                 business_price: 2500,
             },
             Flight {
-                flight_no: "MH2616".to_string(),
+                flight_no: "MH2626".to_string(),
                 origin: "Chengdu".to_string(),
                 destination: "Fuzhou".to_string(),
                 depart_time: "2024-07-16 18:30:00".to_string(),
@@ -330,6 +337,7 @@ impl Default for Travel {
             users,
             flights,
             reservations,
+            current_time: default_current_time(),
         }
     }
 }
@@ -342,12 +350,23 @@ pub struct GetFlightDetailsArgs {
     pub destination: Option<String>,
 }
 
+#[derive(Clone, Deserialize)]
+pub struct GetFlightArgs {
+    pub flight_no: String,
+    pub depart_time: String,
+}
+
 #[derive(Clone, Deserialize)]
 pub struct GetUserDetailsArgs {
     pub user_id: String,
     pub password: String,
 }
 #[derive(Clone, Deserialize)]
+pub struct ListUserReservationsArgs {
+    pub user_id: String,
+    pub password: String,
+}
+#[derive(Clone, Deserialize)]
 pub struct GetReservationDetailsArgs {
     #[serde(default)]
     pub reservation_id: Option<String>,
@@ -371,6 +390,27 @@ pub struct ReserveFlightArgs {
     pub baggage_count: usize,
 }
 
+#[derive(Clone, Deserialize)]
+pub struct CanAffordFlightArgs {
+    pub user_id: String,
+    pub password: String,
+    pub flight_no: String,
+    pub cabin: String,
+    pub baggage_count: usize,
+    pub payment_method: String,
+}
+
+#[derive(Clone, Deserialize)]
+pub struct ReserveRoundTripArgs {
+    pub user_id: String,
+    pub password: String,
+    pub outbound_flight_no: String,
+    pub return_flight_no: String,
+    pub cabin: String,
+    pub payment_method: String,
+    pub baggage_count: usize,
+}
+
 #[derive(Clone, Deserialize)]
 pub struct ModifyFlightArgs {
     pub user_id: String,
@@ -390,6 +430,41 @@ pub struct CancelReservationArgs {
     pub user_id: String,
     pub reservation_id: String,
     pub reason: String,
+    #[serde(default)]
+    pub current_time: Option<String>,
+}
+
+#[derive(Clone, Deserialize)]
+pub struct CancelAllReservationsArgs {
+    pub user_id: String,
+    pub password: String,
+    pub reason: String,
+}
+
+#[derive(Clone, Deserialize)]
+pub struct TransferReservationArgs {
+    pub user_id: String,
+    pub reservation_id: String,
+    pub new_user_id: String,
+}
+
+#[derive(Clone, Deserialize)]
+pub struct GetRouteAvailabilityArgs {
+    pub origin: String,
+    pub destination: String,
+}
+
+#[derive(Clone, Deserialize)]
+pub struct GetReservationSummaryArgs {
+    pub user_id: String,
+    pub password: String,
+}
+
+#[derive(Clone, Deserialize)]
+pub struct GetCheapestFlightArgs {
+    pub origin: String,
+    pub destination: String,
+    pub cabin: String,
 }
 
 impl Travel {
@@ -419,6 +494,31 @@ impl Travel {
         let flights_str = serde_json::to_string(&flights).unwrap();
         ExecutionResult::success(format!("Flight details: {}", flights_str))
     }
+    // gives agents a deterministic handle for reserve/modify calls even when flight_no
+    // is not unique by itself
+    pub fn get_flight(&self, flight_no: String, depart_time: String) -> ExecutionResult {
+        let matching_flights: Vec<&Flight> = self
+            .flights
+            .as_ref()
+            .unwrap()
+            .iter()
+            .filter(|flight| flight.flight_no == flight_no && flight.depart_time == depart_time)
+            .collect();
+        match matching_flights.as_slice() {
+            [] => ExecutionResult::error(format!(
+                "No flight {} departing at {} was found.",
+                flight_no, depart_time
+            )),
+            [flight] => ExecutionResult::success(format!(
+                "Flight details: {}",
+                serde_json::to_string(flight).unwrap()
+            )),
+            _ => ExecutionResult::error(format!(
+                "Multiple flights {} departing at {} were found; this should not happen.",
+                flight_no, depart_time
+            )),
+        }
+    }
     pub fn get_user_details(&self, user_id: String, password: String) -> ExecutionResult {
         if let Some(user) = self.users.get(&user_id)
             && user.password == Some(password.to_string())
@@ -475,6 +575,66 @@ impl Travel {
             detailed_reservations_str
         ))
     }
+    // authenticated listing endpoint, distinct from get_reservation_details, for "show me
+    // all my trips" style requests that don't already have a reservation_id in hand
+    pub fn list_user_reservations(&self, user_id: String, password: String) -> ExecutionResult {
+        if !Self::authenticate_user(&self.users, &user_id, &password) {
+            return ExecutionResult::error(
+                "Authentication failed. Incorrect username or password.".to_string(),
+            );
+        }
+        let flights = self.flights.as_ref().unwrap();
+        let mut user_reservations: Vec<Reservation> = self
+            .reservations
+            .iter()
+            .filter(|res| res.user_id == user_id)
+            .cloned()
+            .map(|mut res| {
+                if let Some(flight) = flights.iter().find(|flight| flight.flight_no == res.flight_no) {
+                    res.flight_info = Some(flight.clone());
+                }
+                res
+            })
+            .collect();
+        user_reservations.sort_by(|a, b| {
+            let a_depart = a.flight_info.as_ref().map(|flight| flight.depart_time.as_str());
+            let b_depart = b.flight_info.as_ref().map(|flight| flight.depart_time.as_str());
+            a_depart.cmp(&b_depart)
+        });
+        let user_reservations_str = serde_json::to_string(&user_reservations).unwrap();
+        ExecutionResult::success(format!("Reservations: {}", user_reservations_str))
+    }
+    pub fn get_reservation_summary(&self, user_id: String, password: String) -> ExecutionResult {
+        if !Self::authenticate_user(&self.users, &user_id, &password) {
+            return ExecutionResult::error(
+                "Authentication failed. Incorrect username or password.".to_string(),
+            );
+        }
+        let user_reservations: Vec<&Reservation> = self
+            .reservations
+            .iter()
+            .filter(|res| res.user_id == user_id)
+            .collect();
+        let flights = self.flights.as_ref().unwrap();
+        let total_fare: u32 = user_reservations
+            .iter()
+            .filter_map(|res| {
+                let flight = flights.iter().find(|f| f.flight_no == res.flight_no)?;
+                match res.cabin.as_str() {
+                    "Economy Class" => Some(flight.economy_price),
+                    "Business Class" => Some(flight.business_price),
+                    _ => None,
+                }
+            })
+            .sum();
+        ExecutionResult::success(
+            serde_json::json!({
+                "reservation_count": user_reservations.len(),
+                "total_fare": total_fare,
+            })
+            .to_string(),
+        )
+    }
     // helper function, not directly invoked
     pub fn authenticate_user(
         travel_users: &IndexMap<String, TravelUser>,
@@ -489,6 +649,9 @@ impl Travel {
         false
     }
     // helper function, not directly invoked
+    // unknown membership levels or cabin classes fall back to the "regular" allowance
+    // instead of panicking, since malformed data should degrade gracefully rather than
+    // take down the whole request
     fn get_baggage_allowance(membership_level: &str, cabin_class: &str) -> usize {
         match (membership_level, cabin_class) {
             ("regular", "Economy Class") => 1,
@@ -497,7 +660,8 @@ impl Travel {
             ("silver", "Business Class") => 3,
             ("gold", "Economy Class") => 3,
             ("gold", "Business Class") => 3,
-            _ => panic!("Unknown membership level or cabin class"),
+            (_, "Business Class") => 2,
+            _ => 1,
         }
     }
     pub fn find_transfer_flights(
@@ -534,11 +698,29 @@ impl Travel {
 
         // Combine first and second leg flights into connecting flights
         // rename it from transfer_flights to connecting_flights, however, the function name remains the same for compatibility
+        const MIN_LAYOVER_MINUTES: i64 = 30;
         let mut connecting_flights = Vec::new();
         for first_leg in &first_leg_flights {
             for second_leg in &second_leg_flights {
-                // Here we should ideally check the timing constraints (arrival time of first < depart time of second)
-                // but for simplicity, we skip that check in this implementation.
+                // the first leg's arrival must precede the second leg's departure by at
+                // least the minimum layover, otherwise the connection is physically impossible
+                let Ok(first_arrival) = chrono::NaiveDateTime::parse_from_str(
+                    &first_leg.arrival_time,
+                    "%Y-%m-%d %H:%M:%S",
+                ) else {
+                    continue;
+                };
+                let Ok(second_departure) = chrono::NaiveDateTime::parse_from_str(
+                    &second_leg.depart_time,
+                    "%Y-%m-%d %H:%M:%S",
+                ) else {
+                    continue;
+                };
+                if second_departure - first_arrival
+                    < chrono::Duration::minutes(MIN_LAYOVER_MINUTES)
+                {
+                    continue;
+                }
                 connecting_flights.push(((*first_leg).clone(), (*second_leg).clone()));
             }
         }
@@ -553,6 +735,8 @@ impl Travel {
         ExecutionResult::success(format!("Connecting flights: {}", connecting_flights_str))
     }
     // helper function, not directly invoked
+    // `baggage_count` is the total number of checked bags on the reservation, not an
+    // incremental add; 0 is a valid count (no checked bags) and incurs no fee
     pub fn calculate_baggage_fee(
         membership_level: &str,
         cabin_class: &str,
@@ -563,76 +747,66 @@ impl Travel {
         additional_baggage * 50 // assuming each additional baggage costs 50
     }
     // helper function, not directly invoked
+    // `amount` is signed: negative to debit (e.g. booking a flight), positive to
+    // credit (e.g. a refund). Only a debit can fail, and only when it would drive
+    // the balance negative; a credit always succeeds.
     pub fn update_balance(travel_user: &mut TravelUser, payment_method: &str, amount: f64) -> bool {
         let amount = NotNan::new(amount).unwrap();
-        match payment_method {
-            "cash" => {
-                if travel_user.cash_balance < amount {
-                    return false;
-                }
-                travel_user.cash_balance =
-                    NotNan::new(travel_user.cash_balance.into_inner() + amount.into_inner())
-                        .unwrap();
-            }
-            "bank" => {
-                if travel_user.bank_balance < amount {
-                    return false;
-                }
-                travel_user.bank_balance =
-                    NotNan::new(travel_user.bank_balance.into_inner() + amount.into_inner())
-                        .unwrap();
-            }
+        let balance = match payment_method {
+            "cash" => &mut travel_user.cash_balance,
+            "bank" => &mut travel_user.bank_balance,
             _ => panic!("Unknown payment method"),
+        };
+        let new_balance = NotNan::new(balance.into_inner() + amount.into_inner()).unwrap();
+        if new_balance < NotNan::new(0.0).unwrap() {
+            return false;
         }
+        *balance = new_balance;
         true
     }
-    pub fn reserve_flight_helper(
+    // core single-leg booking logic, shared by `reserve_flight_helper` and
+    // `reserve_round_trip_helper`; assumes the caller has already authenticated
+    // the user and validated the payment method
+    fn reserve_one_flight(
         flights: &mut Vec<Flight>,
         travel_users: &mut IndexMap<String, TravelUser>,
         reservations: &mut Vec<Reservation>,
-        user_id: String,
-        password: String,
-        flight_no: String,
-        cabin: String,
-        payment_method: String,
+        user_id: &str,
+        flight_no: &str,
+        cabin: &str,
+        payment_method: &str,
         baggage_count: usize,
-    ) -> ExecutionResult {
-        if !Self::authenticate_user(travel_users, &user_id, &password) {
-            return ExecutionResult::error(
-                "Authentication failed. Incorrect username or password.".to_string(),
-            );
-        };
+    ) -> Result<(String, f64), String> {
         let Some(flight) = flights.iter_mut().find(|f| f.flight_no == flight_no) else {
-            return ExecutionResult::error(format!("Flight {} not found.", flight_no));
+            return Err(format!("Flight {} not found.", flight_no));
         };
         if flight.status != "available" || flight.seats_available == 0 {
-            return ExecutionResult::error(format!(
+            return Err(format!(
                 "Flight {} is not available for booking or has no seats available.",
                 flight_no
             ));
         }
-        let price = match cabin.as_str() {
+        let price = match cabin {
             "Economy Class" => flight.economy_price,
             "Business Class" => flight.business_price,
-            // _ => {
-            //     panic!("Unknown cabin class");
-            // }
-            _ => return ExecutionResult::error(
-                "Unknown cabin class. Please specify either 'Economy Class' or 'Business Class'."
-                    .to_string(),
-            ),
+            _ => {
+                return Err(
+                    "Unknown cabin class. Please specify either 'Economy Class' or 'Business Class'."
+                        .to_string(),
+                );
+            }
         };
         let mut total_cost: f64 = price as f64;
-        let user = travel_users.get_mut(&user_id).unwrap();
-        let baggage_fee =
-            Self::calculate_baggage_fee(&user.membership_level, &cabin, baggage_count);
+        let user = travel_users.get_mut(user_id).unwrap();
+        let baggage_fee = Self::calculate_baggage_fee(&user.membership_level, cabin, baggage_count);
         total_cost += baggage_fee as f64;
-        if !Self::update_balance(user, &payment_method, -total_cost) {
-            return ExecutionResult::error(format!(
+        if !Self::update_balance(user, payment_method, -total_cost) {
+            return Err(format!(
                 "Your {} balance is insufficient. Please consider using another payment method.",
                 payment_method
             ));
         }
+        let flight = flights.iter_mut().find(|f| f.flight_no == flight_no).unwrap();
         flight.seats_available -= 1;
         let reservation_id = format!("res_{}", reservations.len() + 1);
         let reservation = Reservation {
@@ -647,9 +821,135 @@ impl Travel {
             destination: Some(flight.destination.clone()),
         };
         reservations.push(reservation);
+        Ok((reservation_id, total_cost))
+    }
+    // undoes a just-created reservation: restores the seat and refunds the full
+    // amount, regardless of how much time has passed; used to roll back a
+    // round-trip booking when its second leg fails
+    fn undo_reservation(
+        flights: &mut Vec<Flight>,
+        travel_users: &mut IndexMap<String, TravelUser>,
+        reservations: &mut Vec<Reservation>,
+        reservation_id: &str,
+        total_cost: f64,
+    ) {
+        let Some(index) = reservations
+            .iter()
+            .position(|r| r.reservation_id == reservation_id)
+        else {
+            return;
+        };
+        let reservation = reservations.remove(index);
+        if let Some(flight) = flights.iter_mut().find(|f| f.flight_no == reservation.flight_no) {
+            flight.seats_available += 1;
+        }
+        if let Some(user) = travel_users.get_mut(&reservation.user_id) {
+            Self::update_balance(user, &reservation.payment_method, total_cost);
+        }
+    }
+    pub fn reserve_flight_helper(
+        flights: &mut Vec<Flight>,
+        travel_users: &mut IndexMap<String, TravelUser>,
+        reservations: &mut Vec<Reservation>,
+        user_id: String,
+        password: String,
+        flight_no: String,
+        cabin: String,
+        payment_method: String,
+        baggage_count: usize,
+    ) -> ExecutionResult {
+        if !Self::authenticate_user(travel_users, &user_id, &password) {
+            return ExecutionResult::error(
+                "Authentication failed. Incorrect username or password.".to_string(),
+            );
+        };
+        if !["cash", "bank"].contains(&payment_method.as_str()) {
+            return ExecutionResult::error(format!(
+                "Unsupported payment method: {}",
+                payment_method
+            ));
+        }
+        match Self::reserve_one_flight(
+            flights,
+            travel_users,
+            reservations,
+            &user_id,
+            &flight_no,
+            &cabin,
+            &payment_method,
+            baggage_count,
+        ) {
+            Ok((reservation_id, total_cost)) => ExecutionResult::success(format!(
+                "Booking successful. Reservation ID: {}. Total cost: {} yuan (including baggage fees).",
+                reservation_id, total_cost
+            )),
+            Err(e) => ExecutionResult::error(e),
+        }
+    }
+    pub fn reserve_round_trip_helper(
+        flights: &mut Vec<Flight>,
+        travel_users: &mut IndexMap<String, TravelUser>,
+        reservations: &mut Vec<Reservation>,
+        user_id: String,
+        password: String,
+        outbound_flight_no: String,
+        return_flight_no: String,
+        cabin: String,
+        payment_method: String,
+        baggage_count: usize,
+    ) -> ExecutionResult {
+        if !Self::authenticate_user(travel_users, &user_id, &password) {
+            return ExecutionResult::error(
+                "Authentication failed. Incorrect username or password.".to_string(),
+            );
+        };
+        if !["cash", "bank"].contains(&payment_method.as_str()) {
+            return ExecutionResult::error(format!(
+                "Unsupported payment method: {}",
+                payment_method
+            ));
+        }
+        let (outbound_reservation_id, outbound_cost) = match Self::reserve_one_flight(
+            flights,
+            travel_users,
+            reservations,
+            &user_id,
+            &outbound_flight_no,
+            &cabin,
+            &payment_method,
+            baggage_count,
+        ) {
+            Ok(booked) => booked,
+            Err(e) => return ExecutionResult::error(format!("Outbound leg failed: {}", e)),
+        };
+        let (return_reservation_id, return_cost) = match Self::reserve_one_flight(
+            flights,
+            travel_users,
+            reservations,
+            &user_id,
+            &return_flight_no,
+            &cabin,
+            &payment_method,
+            baggage_count,
+        ) {
+            Ok(booked) => booked,
+            Err(e) => {
+                Self::undo_reservation(
+                    flights,
+                    travel_users,
+                    reservations,
+                    &outbound_reservation_id,
+                    outbound_cost,
+                );
+                return ExecutionResult::error(format!(
+                    "Return leg failed, outbound booking rolled back: {}",
+                    e
+                ));
+            }
+        };
         ExecutionResult::success(format!(
-            "Booking successful. Reservation ID: {}. Total cost: {} yuan (including baggage fees).",
-            reservation_id, total_cost
+            "Round-trip booking successful. Outbound reservation ID: {} (cost: {} yuan). Return reservation ID: {} (cost: {} yuan).",
+            outbound_reservation_id, outbound_cost, return_reservation_id, return_cost
         ))
     }
     pub fn reserve_flight(
@@ -681,20 +981,116 @@ impl Travel {
         self.reservations = reservations;
         result
     }
+    pub fn reserve_round_trip(
+        &mut self,
+        user_id: String,
+        password: String,
+        outbound_flight_no: String,
+        return_flight_no: String,
+        cabin: String,
+        payment_method: String,
+        baggage_count: usize,
+    ) -> ExecutionResult {
+        let mut travel_users = std::mem::take(&mut self.users);
+        let mut flights = std::mem::take(&mut self.flights).unwrap();
+        let mut reservations = std::mem::take(&mut self.reservations);
+        let result = Self::reserve_round_trip_helper(
+            &mut flights,
+            &mut travel_users,
+            &mut reservations,
+            user_id,
+            password,
+            outbound_flight_no,
+            return_flight_no,
+            cabin,
+            payment_method,
+            baggage_count,
+        );
+        // put back
+        self.users = travel_users;
+        self.flights = Some(flights);
+        self.reservations = reservations;
+        result
+    }
+    // non-mutating affordability check: mirrors the cost computation in
+    // `reserve_one_flight` (fare + baggage fee) without booking anything, so an
+    // agent can check before committing to a reservation
+    pub fn can_afford_flight(
+        &self,
+        user_id: String,
+        password: String,
+        flight_no: String,
+        cabin: String,
+        baggage_count: usize,
+        payment_method: String,
+    ) -> ExecutionResult {
+        if !Self::authenticate_user(&self.users, &user_id, &password) {
+            return ExecutionResult::error(
+                "Authentication failed. Incorrect username or password.".to_string(),
+            );
+        };
+        if !["cash", "bank"].contains(&payment_method.as_str()) {
+            return ExecutionResult::error(format!(
+                "Unsupported payment method: {}",
+                payment_method
+            ));
+        }
+        let flights = self.flights.as_ref().unwrap();
+        let Some(flight) = flights.iter().find(|f| f.flight_no == flight_no) else {
+            return ExecutionResult::error(format!("Flight {} not found.", flight_no));
+        };
+        let price = match cabin.as_str() {
+            "Economy Class" => flight.economy_price,
+            "Business Class" => flight.business_price,
+            _ => {
+                return ExecutionResult::error(
+                    "Unknown cabin class. Please specify either 'Economy Class' or 'Business Class'."
+                        .to_string(),
+                );
+            }
+        };
+        let user = self.users.get(&user_id).unwrap();
+        let baggage_fee = Self::calculate_baggage_fee(&user.membership_level, &cabin, baggage_count);
+        let total_cost = price as f64 + baggage_fee as f64;
+        let balance = match payment_method.as_str() {
+            "cash" => user.cash_balance.into_inner(),
+            "bank" => user.bank_balance.into_inner(),
+            _ => unreachable!(),
+        };
+        if balance >= total_cost {
+            ExecutionResult::success(format!(
+                "Yes, you can afford this flight. Total cost: {} yuan (including baggage fees). Your {} balance: {} yuan.",
+                total_cost, payment_method, balance
+            ))
+        } else {
+            ExecutionResult::success(format!(
+                "No, you cannot afford this flight. Total cost: {} yuan (including baggage fees). Your {} balance: {} yuan. Shortfall: {} yuan.",
+                total_cost, payment_method, balance, total_cost - balance
+            ))
+        }
+    }
     // helper function, not directly invoked
-    fn calculate_price_difference(flight: &Flight, old_cabin: &str, new_cabin: &str) -> f64 {
+    fn calculate_price_difference(
+        flight: &Flight,
+        old_cabin: &str,
+        new_cabin: &str,
+    ) -> Result<f64, String> {
         let old_price = match old_cabin {
             "Economy Class" => flight.economy_price,
             "Business Class" => flight.business_price,
-            // _ => return ExecutionResult::error("Unknown cabin class. Please specify either 'Economy Class' or 'Business Class'.".to_string()),
-            _ => panic!("Unknown cabin class"),
+            other => return Err(format!("Unknown cabin class on reservation: {}", other)),
         };
         let new_price = match new_cabin {
             "Economy Class" => flight.economy_price,
             "Business Class" => flight.business_price,
-            _ => panic!("Unknown cabin class"),
+            _ => {
+                return Err(
+                    "Unknown cabin class. Please specify either 'Economy Class' or 'Business Class'."
+                        .to_string(),
+                );
+            }
         };
-        (new_price as f64) - (old_price as f64)
+        Ok((new_price as f64) - (old_price as f64))
     }
 
     pub fn modify_flight_helper(
@@ -715,9 +1111,11 @@ impl Travel {
             return ExecutionResult::error("Reservation not found for the given user.".to_string());
         };
 
+        // cloned so the lookup doesn't keep flights borrowed while we mutate seat counts below
         let Some(current_flight) = flights
             .iter()
             .find(|f| f.flight_no == reservation.flight_no)
+            .cloned()
         else {
             return ExecutionResult::error("Current flight information not found.".to_string());
         };
@@ -726,6 +1124,21 @@ impl Travel {
             return ExecutionResult::error("User information not found.".to_string());
         };
 
+        // explicitly distinguish "nothing was requested" from "something was requested
+        // but it turned out to be a no-op / incurred no fee"; `add_baggage: Some(0)` is a
+        // deliberate no-op request (e.g. confirming "no extra bags"), not the absence of one
+        let nothing_to_change = new_flight_no
+            .as_deref()
+            .is_none_or(|f| f == reservation.flight_no)
+            && new_cabin.as_deref().is_none_or(|c| c == reservation.cabin)
+            && add_baggage.is_none()
+            && payment_method == reservation.payment_method;
+        if nothing_to_change {
+            return ExecutionResult::success(
+                "No changes were requested; the reservation is unchanged.".to_string(),
+            );
+        }
+
         let mut result_messages: Vec<String> = Vec::new();
         if let Some(new_flight_no) = new_flight_no
             && new_flight_no != reservation.flight_no
@@ -738,9 +1151,20 @@ impl Travel {
             if new_flight.origin == current_flight.origin
                 && new_flight.destination == current_flight.destination
             {
-                // this is the logic in the original python code, which only changes the reservation record but not flight seat availability
-                // this might be logically wrong, but we keep it for compatibility
+                if new_flight.seats_available == 0 {
+                    return ExecutionResult::error(
+                        "Flight change failed: The new flight is sold out.".to_string(),
+                    );
+                }
+                let old_flight_no = reservation.flight_no.clone();
                 reservation.flight_no = new_flight_no.to_string();
+                // keep seat availability consistent with the reassigned reservation
+                if let Some(f) = flights.iter_mut().find(|f| f.flight_no == new_flight_no) {
+                    f.seats_available -= 1;
+                }
+                if let Some(f) = flights.iter_mut().find(|f| f.flight_no == old_flight_no) {
+                    f.seats_available += 1;
+                }
                 result_messages.push("Flight number has been changed.".to_string());
             } else {
                 return ExecutionResult::error(
@@ -754,11 +1178,14 @@ impl Travel {
             if !["Economy Class", "Business Class"].contains(&new_cabin.as_str()) {
                 result_messages.push("Cabin change failed: Invalid cabin class. Please specify either 'Economy Class' or 'Business Class'.".to_string());
             } else {
-                let price_difference = Self::calculate_price_difference(
-                    current_flight,
+                let price_difference = match Self::calculate_price_difference(
+                    &current_flight,
                     &reservation.cabin,
                     &new_cabin,
-                );
+                ) {
+                    Ok(price_difference) => price_difference,
+                    Err(e) => return ExecutionResult::error(e),
+                };
                 let paid_or_refunded = match price_difference >= 0.0 {
                     true => "paid",
                     false => "refunded",
@@ -777,34 +1204,40 @@ impl Travel {
                 }
             }
         }
-        if let Some(add_baggage) = add_baggage
-            && add_baggage > 0
-        {
-            let total_baggage = reservation.baggage as usize + add_baggage;
-            let new_baggage_cost = Self::calculate_baggage_fee(
-                &user.membership_level,
-                &reservation.cabin,
-                total_baggage,
-            );
-            let old_baggage_cost = Self::calculate_baggage_fee(
-                &user.membership_level,
-                &reservation.cabin,
-                reservation.baggage as usize,
-            );
-            let baggage_cost: f64 = new_baggage_cost as f64 - old_baggage_cost as f64;
-            if Self::update_balance(user, &payment_method, -(baggage_cost as f64)) {
-                if baggage_cost > 0.0 {
-                    result_messages.push(format!(
-                        "Baggage has been added. Additional fee to be paid: {}.",
-                        baggage_cost
-                    ));
+        if let Some(add_baggage) = add_baggage {
+            if add_baggage == 0 {
+                // an explicit request to add 0 bags is a deliberate no-op, not an error
+                result_messages
+                    .push("Baggage count unchanged: adding 0 bags is a no-op.".to_string());
+            } else {
+                let total_baggage = reservation.baggage as usize + add_baggage;
+                let new_baggage_cost = Self::calculate_baggage_fee(
+                    &user.membership_level,
+                    &reservation.cabin,
+                    total_baggage,
+                );
+                let old_baggage_cost = Self::calculate_baggage_fee(
+                    &user.membership_level,
+                    &reservation.cabin,
+                    reservation.baggage as usize,
+                );
+                let baggage_cost: f64 = new_baggage_cost as f64 - old_baggage_cost as f64;
+                if Self::update_balance(user, &payment_method, -(baggage_cost as f64)) {
+                    if baggage_cost > 0.0 {
+                        result_messages.push(format!(
+                            "Baggage has been added. Additional fee to be paid: {}.",
+                            baggage_cost
+                        ));
+                    } else {
+                        result_messages
+                            .push("Baggage has been added. No additional fee.".to_string());
+                    }
+                    reservation.baggage = total_baggage as u32;
                 } else {
-                    result_messages.push("Baggage has been added. No additional fee.".to_string());
+                    result_messages.push(
+                        "Insufficient balance to pay the additional baggage fees.".to_string(),
+                    );
                 }
-                reservation.baggage = total_baggage as u32;
-            } else {
-                result_messages
-                    .push("Insufficient balance to pay the additional baggage fees.".to_string());
             }
         }
         if result_messages.is_empty() {
@@ -844,43 +1277,63 @@ impl Travel {
         result
     }
 
-    pub fn cancel_reservation_helper(
+    // helper function, not directly invoked
+    // returns (refund_amount, cancellation_fee) under the airline's cancellation policy
+    fn calculate_cancellation_refund(
+        flight_price: f64,
+        reason: &str,
+        time_until_departure: chrono::Duration,
+    ) -> (f64, f64) {
+        if reason == "The airline has canceled the flight."
+            || time_until_departure > chrono::Duration::hours(24)
+        {
+            (flight_price, 0.0)
+        } else {
+            // Assume a cancellation fee of 10% of the ticket price
+            let cancel_fee = flight_price * 0.1;
+            (flight_price - cancel_fee, cancel_fee)
+        }
+    }
+    // Cancels a single reservation: validates it belongs to the user and hasn't already
+    // departed, refunds according to the cancellation policy, frees the seat, and removes
+    // the reservation record. Returns (refund_amount, cancellation_fee) on success, shared
+    // by `cancel_reservation_helper` and `cancel_all_reservations_helper`.
+    fn cancel_one_reservation(
         flights: &mut Vec<Flight>,
         travel_users: &mut IndexMap<String, TravelUser>,
         reservations: &mut Vec<Reservation>,
-        user_id: String,
-        reservation_id: String,
-        reason: String,
-    ) -> ExecutionResult {
-        // Set the default current time to July 14, 2024, 6:00 AM
-        let current_time =
-            chrono::NaiveDateTime::parse_from_str("2024-07-14 06:00:00", "%Y-%m-%d %H:%M:%S")
-                .unwrap();
+        user_id: &str,
+        reservation_id: &str,
+        reason: &str,
+        current_time_override: Option<&str>,
+        default_current_time: &str,
+    ) -> Result<(f64, f64), String> {
+        // Per-call override takes precedence; otherwise falls back to the
+        // Travel-level `current_time` (the evaluation "now" for this scenario)
+        let current_time = current_time_override.unwrap_or(default_current_time);
+        let current_time = chrono::NaiveDateTime::parse_from_str(current_time, "%Y-%m-%d %H:%M:%S")
+            .map_err(|e| format!("Invalid current_time format: {}", e))?;
 
-        let Some(user) = travel_users.get_mut(&user_id) else {
-            return ExecutionResult::error("Invalid user ID.".to_string());
+        let Some(user) = travel_users.get_mut(user_id) else {
+            return Err("Invalid user ID.".to_string());
         };
         let Some(reservation) = reservations
             .iter()
             .find(|r| r.reservation_id == reservation_id && r.user_id == user_id)
         else {
-            return ExecutionResult::error(
-                "Invalid reservation ID or it does not belong to the user.".to_string(),
-            );
+            return Err("Invalid reservation ID or it does not belong to the user.".to_string());
         };
         let Some(flight) = flights
             .iter()
             .find(|f| f.flight_no == reservation.flight_no)
         else {
-            return ExecutionResult::error("Invalid flight information.".to_string());
+            return Err("Invalid flight information.".to_string());
         };
         let depart_time =
             chrono::NaiveDateTime::parse_from_str(&flight.depart_time, "%Y-%m-%d %H:%M:%S")
                 .unwrap();
         if current_time > depart_time {
-            return ExecutionResult::error(
-                "The flight segment has been used and cannot be canceled.".to_string(),
-            );
+            return Err("The flight segment has been used and cannot be canceled.".to_string());
         }
         let time_until_departure = depart_time - current_time;
 
@@ -888,41 +1341,17 @@ impl Travel {
         let flight_price = match reservation.cabin.as_str() {
             "Economy Class" => flight.economy_price as f64,
             "Business Class" => flight.business_price as f64,
-            _ => panic!("Unknown cabin class"),
+            other => return Err(format!("Unknown cabin class on reservation: {}", other)),
         };
-        // Need to store flight_no before the borrow of user ends
+        // Need to store flight_no/payment_method before the borrow of reservation ends
         let reservation_flight_no = reservation.flight_no.clone();
-        // Cancellation policy and refund calculation
-        let execution_result = if reason == "The airline has canceled the flight." {
-            // Airline cancels the flight, full refund
-            let refund_amount = flight_price;
-            assert!(refund_amount >= 0.0);
-            // the original process_refund function in python adds the amount to user's cash balance
-            Self::update_balance(user, "cash", refund_amount);
-            ExecutionResult::success(format!(
-                "The flight has been canceled. Your reservation will be canceled free of charge, and {} yuan has been refunded.",
-                refund_amount
-            ))
-        } else if time_until_departure > chrono::Duration::hours(24) {
-            // More than 24 hours before departure, free cancellation
-            let refund_amount = flight_price;
-            assert!(refund_amount >= 0.0);
-            Self::update_balance(user, "cash", refund_amount);
-            ExecutionResult::success(format!(
-                "More than 24 hours before departure. Free cancellation successful, {} yuan has been refunded.",
-                refund_amount
-            ))
-        } else {
-            // If not eligible for free cancellation, set a cancellation fee as needed
-            let cancel_fee = flight_price * 0.1; // Assume a cancellation fee of 10% of the ticket price
-            let refund_amount = flight_price - cancel_fee;
-            assert!(refund_amount >= 0.0);
-            Self::update_balance(user, "cash", refund_amount);
-            ExecutionResult::success(format!(
-                "Less than 24 hours before departure. A cancellation fee of {} yuan has been deducted, and {} yuan has been refunded.",
-                cancel_fee, refund_amount
-            ))
-        };
+        let payment_method = reservation.payment_method.clone();
+        let (refund_amount, cancel_fee) =
+            Self::calculate_cancellation_refund(flight_price, reason, time_until_departure);
+        assert!(refund_amount >= 0.0);
+        // refund to the account the reservation was originally paid from, so a bank
+        // payment is restored to bank_balance rather than corrupting cash_balance
+        Self::update_balance(user, &payment_method, refund_amount);
         // the following does not appear in the original python code, which might be a bug
         // commonly, we need to remove the reservation record and increase the available seats after cancellation
         // Increase the available seats on the flight
@@ -934,7 +1363,106 @@ impl Travel {
         }
         // Remove the reservation
         reservations.retain(|r| r.reservation_id != reservation_id);
-        execution_result
+        Ok((refund_amount, cancel_fee))
+    }
+    pub fn cancel_reservation_helper(
+        flights: &mut Vec<Flight>,
+        travel_users: &mut IndexMap<String, TravelUser>,
+        reservations: &mut Vec<Reservation>,
+        user_id: String,
+        reservation_id: String,
+        reason: String,
+        current_time: Option<String>,
+        default_current_time: String,
+    ) -> ExecutionResult {
+        match Self::cancel_one_reservation(
+            flights,
+            travel_users,
+            reservations,
+            &user_id,
+            &reservation_id,
+            &reason,
+            current_time.as_deref(),
+            &default_current_time,
+        ) {
+            Ok((refund_amount, cancel_fee)) => {
+                if reason == "The airline has canceled the flight." {
+                    ExecutionResult::success(format!(
+                        "The flight has been canceled. Your reservation will be canceled free of charge, and {} yuan has been refunded.",
+                        refund_amount
+                    ))
+                } else if cancel_fee == 0.0 {
+                    ExecutionResult::success(format!(
+                        "More than 24 hours before departure. Free cancellation successful, {} yuan has been refunded.",
+                        refund_amount
+                    ))
+                } else {
+                    ExecutionResult::success(format!(
+                        "Less than 24 hours before departure. A cancellation fee of {} yuan has been deducted, and {} yuan has been refunded.",
+                        cancel_fee, refund_amount
+                    ))
+                }
+            }
+            Err(e) => ExecutionResult::error(e),
+        }
+    }
+    pub fn cancel_all_reservations_helper(
+        flights: &mut Vec<Flight>,
+        travel_users: &mut IndexMap<String, TravelUser>,
+        reservations: &mut Vec<Reservation>,
+        user_id: String,
+        password: String,
+        reason: String,
+        default_current_time: String,
+    ) -> ExecutionResult {
+        if !Self::authenticate_user(travel_users, &user_id, &password) {
+            return ExecutionResult::error(
+                "Authentication failed. Incorrect username or password.".to_string(),
+            );
+        }
+        let reservation_ids: Vec<String> = reservations
+            .iter()
+            .filter(|r| r.user_id == user_id)
+            .map(|r| r.reservation_id.clone())
+            .collect();
+        if reservation_ids.is_empty() {
+            return ExecutionResult::error(format!(
+                "User {} has no reservations to cancel.",
+                user_id
+            ));
+        }
+        let mut canceled_count = 0;
+        let mut total_refunded = 0.0;
+        let mut total_fees = 0.0;
+        for reservation_id in &reservation_ids {
+            if let Ok((refund_amount, cancel_fee)) = Self::cancel_one_reservation(
+                flights,
+                travel_users,
+                reservations,
+                &user_id,
+                reservation_id,
+                &reason,
+                None,
+                &default_current_time,
+            ) {
+                canceled_count += 1;
+                total_refunded += refund_amount;
+                total_fees += cancel_fee;
+            }
+        }
+        if canceled_count == 0 {
+            return ExecutionResult::error(
+                "None of the user's reservations could be canceled.".to_string(),
+            );
+        }
+        ExecutionResult::success(
+            serde_json::json!({
+                "canceled_count": canceled_count,
+                "total_refunded": total_refunded,
+                "total_fees": total_fees,
+            })
+            .to_string(),
+        )
     }
 
     pub fn cancel_reservation(
@@ -942,10 +1470,12 @@ impl Travel {
         user_id: String,
         reservation_id: String,
         reason: String,
+        current_time: Option<String>,
     ) -> ExecutionResult {
         let mut travel_users = std::mem::take(&mut self.users);
         let mut flights = std::mem::take(&mut self.flights).unwrap();
         let mut reservations = std::mem::take(&mut self.reservations);
+        let default_current_time = self.current_time.clone();
         let result = Self::cancel_reservation_helper(
             &mut flights,
             &mut travel_users,
@@ -953,6 +1483,33 @@ impl Travel {
             user_id,
             reservation_id,
             reason,
+            current_time,
+            default_current_time,
+        );
+        // put back
+        self.users = travel_users;
+        self.flights = Some(flights);
+        self.reservations = reservations;
+        result
+    }
+    pub fn cancel_all_reservations(
+        &mut self,
+        user_id: String,
+        password: String,
+        reason: String,
+    ) -> ExecutionResult {
+        let mut travel_users = std::mem::take(&mut self.users);
+        let mut flights = std::mem::take(&mut self.flights).unwrap();
+        let mut reservations = std::mem::take(&mut self.reservations);
+        let default_current_time = self.current_time.clone();
+        let result = Self::cancel_all_reservations_helper(
+            &mut flights,
+            &mut travel_users,
+            &mut reservations,
+            user_id,
+            password,
+            reason,
+            default_current_time,
         );
         // put back
         self.users = travel_users;
@@ -961,12 +1518,148 @@ impl Travel {
         result
     }
 
+    // Reassigns a reservation to another user. The reservation's payment_method stays
+    // as-is and no balance is moved between the two users at transfer time. Later
+    // modifications and cancellations look the user up by the reservation's new owner,
+    // so price differences, baggage fees and refunds go to the new owner's balance.
+    pub fn transfer_reservation(
+        &mut self,
+        user_id: String,
+        reservation_id: String,
+        new_user_id: String,
+    ) -> ExecutionResult {
+        if !self.users.contains_key(&new_user_id) {
+            return ExecutionResult::error("Invalid target user ID.".to_string());
+        }
+        if user_id == new_user_id {
+            return ExecutionResult::error(
+                "The reservation already belongs to the target user.".to_string(),
+            );
+        }
+        let Some(reservation) = self
+            .reservations
+            .iter_mut()
+            .find(|r| r.reservation_id == reservation_id && r.user_id == user_id)
+        else {
+            return ExecutionResult::error(
+                "Invalid reservation ID or it does not belong to the user.".to_string(),
+            );
+        };
+        reservation.user_id = new_user_id.clone();
+        ExecutionResult::success(format!(
+            "Reservation {} has been transferred to {}.",
+            reservation_id, new_user_id
+        ))
+    }
+
+    pub fn get_cheapest_flight(
+        &self,
+        origin: String,
+        destination: String,
+        cabin: String,
+    ) -> ExecutionResult {
+        let price = |flight: &Flight| match cabin.as_str() {
+            "Economy Class" => Some(flight.economy_price),
+            "Business Class" => Some(flight.business_price),
+            _ => None,
+        };
+        let cheapest = self
+            .flights
+            .as_ref()
+            .unwrap()
+            .iter()
+            .filter(|flight| {
+                flight.origin == origin
+                    && flight.destination == destination
+                    && flight.status == "available"
+            })
+            .filter_map(|flight| price(flight).map(|p| (p, flight)))
+            .min_by_key(|(p, _)| *p);
+        let Some((_, flight)) = cheapest else {
+            return ExecutionResult::error(
+                "There are no available flights on this route for the requested cabin."
+                    .to_string(),
+            );
+        };
+        ExecutionResult::success(format!(
+            "Cheapest flight: {}",
+            serde_json::to_string(flight).unwrap()
+        ))
+    }
+
+    pub fn get_route_availability(&self, origin: String, destination: String) -> ExecutionResult {
+        let matching_flights: Vec<&Flight> = self
+            .flights
+            .as_ref()
+            .unwrap()
+            .iter()
+            .filter(|flight| {
+                flight.origin == origin
+                    && flight.destination == destination
+                    && flight.status == "available"
+            })
+            .collect();
+        if matching_flights.is_empty() {
+            return ExecutionResult::error(
+                "There are no available flights on this route.".to_string(),
+            );
+        }
+        let total_seats_available: u32 = matching_flights
+            .iter()
+            .map(|flight| flight.seats_available)
+            .sum();
+        let per_flight: Vec<serde_json::Value> = matching_flights
+            .iter()
+            .map(|flight| {
+                serde_json::json!({
+                    "flight_no": flight.flight_no,
+                    "seats_available": flight.seats_available,
+                })
+            })
+            .collect();
+        ExecutionResult::success(
+            serde_json::json!({
+                "total_seats_available": total_seats_available,
+                "flights": per_flight,
+            })
+            .to_string(),
+        )
+    }
+
+    /// Rejects a `Travel` whose `flights` contains duplicate `flight_no` entries. Lookups
+    /// such as `reserve_flight_helper`/`modify_flight_helper` resolve a flight by
+    /// `find(|f| f.flight_no == ...)`, which silently picks the first match, so a duplicate
+    /// flight number would make booking non-deterministic instead of failing loudly.
+    pub fn validate(&self) -> Result<(), String> {
+        let Some(flights) = &self.flights else {
+            return Ok(());
+        };
+        let mut seen = std::collections::HashSet::new();
+        for flight in flights {
+            if !seen.insert(&flight.flight_no) {
+                return Err(format!(
+                    "Duplicate flight_no in Travel flights: {}",
+                    flight.flight_no
+                ));
+            }
+        }
+        Ok(())
+    }
     pub fn equals_ground_truth(&self, ground_truth: &Travel) -> Result<(), String> {
-        if self.users != ground_truth.users {
-            return Err(format!(
-                "Users do not match. Expected: {:?}, got: {:?}",
-                ground_truth.users, self.users
-            ));
+        for (user_id, ground_truth_user) in ground_truth.users.iter() {
+            let Some(self_user) = self.users.get(user_id) else {
+                return Err(format!("User does not exist in output. Expected user ID: {}", user_id));
+            };
+            // password is not part of the scenario outcome, so it is deliberately excluded here
+            if self_user.cash_balance != ground_truth_user.cash_balance
+                || self_user.bank_balance != ground_truth_user.bank_balance
+                || self_user.membership_level != ground_truth_user.membership_level
+            {
+                return Err(format!(
+                    "User {} does not match. Expected: {:?}, got: {:?}",
+                    user_id, ground_truth_user, self_user
+                ));
+            }
         }
         // if self.flights != ground_truth.flights {
         //     return Err(format!(
@@ -974,11 +1667,19 @@ impl Travel {
         //         ground_truth.flights, self.flights
         //     ));
         // }
-        if let Some(ground_truth_flights) = &ground_truth.flights && self.flights.as_ref().unwrap() != ground_truth_flights {
-            return Err(format!(
-                "Flights do not match. Expected: {:?}, got: {:?}",
-                ground_truth_flights, self.flights.as_ref().unwrap()
-            ));
+        if let Some(ground_truth_flights) = &ground_truth.flights {
+            let Some(self_flights) = &self.flights else {
+                return Err(format!(
+                    "Flights do not match. Expected: {:?}, got: None",
+                    ground_truth_flights
+                ));
+            };
+            if self_flights != ground_truth_flights {
+                return Err(format!(
+                    "Flights do not match. Expected: {:?}, got: {:?}",
+                    ground_truth_flights, self_flights
+                ));
+            }
         }
         // if self.reservations != ground_truth.reservations {
         //     return Err(format!("Reservations do not match. Expected: {:?}, got: {:?}", ground_truth.reservations, self.reservations));
@@ -1015,4 +1716,790 @@ impl Travel {
         }
         Ok(())
     }
+    /// Like [`Self::equals_ground_truth`], but collects every discrepancy instead of
+    /// stopping at the first one: a balance mismatch on one user and a missing
+    /// reservation elsewhere are both reported, rather than whichever is checked
+    /// first hiding the other. Used by [`crate::world_state::WorldState::diff`].
+    pub fn diff(&self, ground_truth: &Travel) -> Vec<String> {
+        let mut discrepancies = Vec::new();
+        for (user_id, ground_truth_user) in ground_truth.users.iter() {
+            match self.users.get(user_id) {
+                None => discrepancies.push(format!(
+                    "User does not exist in output. Expected user ID: {}",
+                    user_id
+                )),
+                Some(self_user) => {
+                    // password is not part of the scenario outcome, so it is deliberately excluded here
+                    if self_user.cash_balance != ground_truth_user.cash_balance
+                        || self_user.bank_balance != ground_truth_user.bank_balance
+                        || self_user.membership_level != ground_truth_user.membership_level
+                    {
+                        discrepancies.push(format!(
+                            "User {} does not match. Expected: {:?}, got: {:?}",
+                            user_id, ground_truth_user, self_user
+                        ));
+                    }
+                }
+            }
+        }
+        if let Some(ground_truth_flights) = &ground_truth.flights {
+            match &self.flights {
+                None => discrepancies.push(format!(
+                    "Flights do not match. Expected: {:?}, got: None",
+                    ground_truth_flights
+                )),
+                Some(self_flights) if self_flights != ground_truth_flights => {
+                    discrepancies.push(format!(
+                        "Flights do not match. Expected: {:?}, got: {:?}",
+                        ground_truth_flights, self_flights
+                    ));
+                }
+                Some(_) => {}
+            }
+        }
+        let self_reservation_map: IndexMap<String, Reservation> = self
+            .reservations
+            .iter()
+            .map(|r| (r.reservation_id.clone(), r.clone()))
+            .collect();
+        let ground_truth_reservation_map: IndexMap<String, Reservation> = ground_truth
+            .reservations
+            .iter()
+            .map(|r| (r.reservation_id.clone(), r.clone()))
+            .collect();
+        if self_reservation_map.len() != ground_truth_reservation_map.len() {
+            discrepancies.push(format!(
+                "Number of reservations do not match. Expected: {}, got: {}",
+                ground_truth_reservation_map.len(),
+                self_reservation_map.len()
+            ));
+        }
+        for (res_id, ground_truth_res) in ground_truth_reservation_map.iter() {
+            match self_reservation_map.get(res_id) {
+                Some(self_res) => {
+                    if let Err(e) = self_res.equals_ground_truth(ground_truth_res) {
+                        discrepancies.push(e);
+                    }
+                }
+                None => discrepancies.push(format!(
+                    "Reservation does not exist in output. Expected reservation ID: {}",
+                    res_id
+                )),
+            }
+        }
+        discrepancies
+    }
+}
+
+#[cfg(test)]
+mod transfer_reservation_tests {
+    use super::*;
+
+    #[test]
+    fn transfers_reservation_ownership_to_new_user() {
+        let mut travel = Travel::default();
+        let result = travel.transfer_reservation(
+            "user1".to_string(),
+            "res_1".to_string(),
+            "user2".to_string(),
+        );
+        assert!(result.is_success(), "{}", result.message);
+
+        let reservation = travel
+            .reservations
+            .iter()
+            .find(|r| r.reservation_id == "res_1")
+            .expect("res_1 should still exist");
+        assert_eq!(reservation.user_id, "user2");
+
+        let details = travel.get_reservation_details(None, Some("user2".to_string()));
+        assert!(details.is_success());
+        assert!(details.message.contains("res_1"));
+    }
+}
+
+#[cfg(test)]
+mod membership_level_tests {
+    use super::*;
+
+    #[test]
+    fn reserve_flight_with_unknown_membership_level_does_not_panic() {
+        let mut travel = Travel::default();
+        travel.users.get_mut("user1").unwrap().membership_level = "platinum".to_string();
+        let result = travel.reserve_flight(
+            "user1".to_string(),
+            travel.users["user1"].password.clone().unwrap(),
+            "CA1234".to_string(),
+            "Economy Class".to_string(),
+            "cash".to_string(),
+            0,
+        );
+        assert!(result.is_success(), "{}", result.message);
+    }
+}
+
+#[cfg(test)]
+mod modify_flight_noop_tests {
+    use super::*;
+
+    #[test]
+    fn all_none_arguments_report_no_changes_requested() {
+        let mut travel = Travel::default();
+        let result = travel.modify_flight(
+            "user1".to_string(),
+            "res_1".to_string(),
+            None,
+            None,
+            None,
+            None,
+        );
+        assert!(result.is_success(), "{}", result.message);
+        assert_eq!(result.message, "No changes were requested; the reservation is unchanged.");
+    }
+}
+
+#[cfg(test)]
+mod route_availability_tests {
+    use super::*;
+
+    #[test]
+    fn aggregates_seats_available_across_a_route() {
+        let travel = Travel::default();
+        let result = travel.get_route_availability("Beijing".to_string(), "Shanghai".to_string());
+        assert!(result.is_success(), "{}", result.message);
+        let value: serde_json::Value = serde_json::from_str(&result.message).unwrap();
+        assert_eq!(value["total_seats_available"], 5);
+        assert_eq!(value["flights"].as_array().unwrap().len(), 1);
+        assert_eq!(value["flights"][0]["flight_no"], "CA1234");
+    }
+}
+
+#[cfg(test)]
+mod reserve_flight_payment_method_tests {
+    use super::*;
+
+    #[test]
+    fn invalid_payment_method_is_rejected_without_mutating_state() {
+        let mut travel = Travel::default();
+        let seats_before = travel
+            .flights
+            .as_ref()
+            .unwrap()
+            .iter()
+            .find(|f| f.flight_no == "CA1234")
+            .unwrap()
+            .seats_available;
+        let cash_before = travel.users.get("user1").unwrap().cash_balance;
+        let bank_before = travel.users.get("user1").unwrap().bank_balance;
+
+        let result = travel.reserve_flight(
+            "user1".to_string(),
+            "password123".to_string(),
+            "CA1234".to_string(),
+            "Economy Class".to_string(),
+            "credit".to_string(),
+            0,
+        );
+
+        assert!(!result.is_success());
+        assert_eq!(result.message, "Unsupported payment method: credit");
+
+        let seats_after = travel
+            .flights
+            .as_ref()
+            .unwrap()
+            .iter()
+            .find(|f| f.flight_no == "CA1234")
+            .unwrap()
+            .seats_available;
+        assert_eq!(seats_after, seats_before);
+        assert_eq!(travel.users.get("user1").unwrap().cash_balance, cash_before);
+        assert_eq!(travel.users.get("user1").unwrap().bank_balance, bank_before);
+    }
+}
+
+#[cfg(test)]
+mod travel_equals_ground_truth_tests {
+    use super::*;
+
+    #[test]
+    fn identical_travel_states_match() {
+        let travel = Travel::default();
+        let ground_truth = Travel::default();
+        assert!(travel.equals_ground_truth(&ground_truth).is_ok());
+    }
+
+    #[test]
+    fn mismatched_cash_balance_reports_the_offending_user() {
+        let mut travel = Travel::default();
+        let ground_truth = Travel::default();
+        travel.users.get_mut("user1").unwrap().cash_balance = NotNan::new(0.0).unwrap();
+
+        let err = travel.equals_ground_truth(&ground_truth).unwrap_err();
+        assert!(err.contains("User user1 does not match"), "unexpected error: {}", err);
+    }
+}
+
+#[cfg(test)]
+mod reservation_summary_tests {
+    use super::*;
+
+    #[test]
+    fn summarizes_count_and_total_fare_for_user1() {
+        let travel = Travel::default();
+        let result = travel.get_reservation_summary(
+            "user1".to_string(),
+            "password123".to_string(),
+        );
+        assert!(result.is_success(), "{}", result.message);
+        let parsed: serde_json::Value = serde_json::from_str(&result.message).unwrap();
+        assert_eq!(parsed["reservation_count"], 2);
+        assert_eq!(parsed["total_fare"], 4200);
+    }
+
+    #[test]
+    fn returns_zeros_for_a_user_with_no_reservations() {
+        let travel = Travel::default();
+        let result = travel.get_reservation_summary(
+            "user3".to_string(),
+            "password789".to_string(),
+        );
+        assert!(result.is_success(), "{}", result.message);
+        let parsed: serde_json::Value = serde_json::from_str(&result.message).unwrap();
+        assert_eq!(parsed["reservation_count"], 0);
+        assert_eq!(parsed["total_fare"], 0);
+    }
+}
+
+#[cfg(test)]
+mod find_transfer_flights_timing_tests {
+    use super::*;
+
+    #[test]
+    fn impossible_connections_are_filtered_out_by_minimum_layover() {
+        let travel = Travel::default();
+        let result = travel.find_transfer_flights(
+            "Beijing".to_string(),
+            "Nanjing".to_string(),
+            "Shenzhen".to_string(),
+        );
+        assert!(result.is_success(), "{}", result.message);
+        // MU3561 (Beijing->Nanjing, arrives 10:00) connects to CZ1785 (Nanjing->Shenzhen,
+        // departs 12:30) with a valid layover, but MU3561/CZ1765, MU1566/CZ1785, and
+        // MU1566/CZ1765 are all physically impossible and must be filtered out
+        assert!(result.message.contains("MU3561"));
+        assert!(result.message.contains("CZ1785"));
+        assert!(!result.message.contains("CZ1765"));
+        assert!(!result.message.contains("MU1566"));
+    }
+}
+
+#[cfg(test)]
+mod cancel_all_reservations_tests {
+    use super::*;
+
+    #[test]
+    fn cancels_both_of_user1s_reservations_and_leaves_none_remaining() {
+        let mut travel = Travel::default();
+        let result = travel.cancel_all_reservations(
+            "user1".to_string(),
+            "password123".to_string(),
+            "Change of plans.".to_string(),
+        );
+        assert!(result.is_success(), "{}", result.message);
+        let parsed: serde_json::Value = serde_json::from_str(&result.message).unwrap();
+        assert_eq!(parsed["canceled_count"], 2);
+        assert_eq!(parsed["total_refunded"], 4200.0);
+
+        assert!(!travel
+            .reservations
+            .iter()
+            .any(|r| r.user_id == "user1"));
+    }
+}
+
+
+#[cfg(test)]
+mod cancel_reservation_payment_method_tests {
+    use super::*;
+
+    #[test]
+    fn canceling_a_bank_paid_reservation_refunds_the_bank_balance() {
+        let mut travel = Travel::default();
+        let bank_before = travel.users.get("user1").unwrap().bank_balance;
+        let cash_before = travel.users.get("user1").unwrap().cash_balance;
+        assert_eq!(
+            travel.reservations.iter().find(|r| r.reservation_id == "res_1").unwrap().payment_method,
+            "bank"
+        );
+
+        let result = travel.cancel_reservation(
+            "user1".to_string(),
+            "res_1".to_string(),
+            "Change of plans.".to_string(),
+            None,
+        );
+        assert!(result.is_success(), "{}", result.message);
+
+        assert_eq!(
+            travel.users.get("user1").unwrap().bank_balance,
+            NotNan::new(bank_before.into_inner() + 1200.0).unwrap()
+        );
+        assert_eq!(travel.users.get("user1").unwrap().cash_balance, cash_before);
+    }
+}
+
+#[cfg(test)]
+mod validate_duplicate_flight_no_tests {
+    use super::*;
+
+    #[test]
+    fn default_data_has_no_duplicate_flight_numbers() {
+        let travel = Travel::default();
+        assert!(travel.validate().is_ok());
+    }
+
+    #[test]
+    fn rejects_a_synthetic_duplicate_flight_no() {
+        let mut travel = Travel::default();
+        let mut duplicate = travel.flights.as_ref().unwrap()[0].clone();
+        duplicate.depart_time = "2099-01-01 00:00:00".to_string();
+        travel.flights.as_mut().unwrap().push(duplicate);
+
+        let err = travel.validate().unwrap_err();
+        assert!(err.contains("Duplicate flight_no"));
+        assert!(err.contains(&travel.flights.as_ref().unwrap()[0].flight_no));
+    }
+}
+
+#[cfg(test)]
+mod get_flight_lookup_tests {
+    use super::*;
+
+    #[test]
+    fn finds_the_unique_flight_by_number_and_depart_time() {
+        let travel = Travel::default();
+        let result = travel.get_flight(
+            "CZ1765".to_string(),
+            "2024-07-17 20:30:00".to_string(),
+        );
+        assert!(result.is_success(), "{}", result.message);
+        assert!(result.message.contains("CZ1765"));
+    }
+
+    #[test]
+    fn reports_an_error_when_no_flight_matches() {
+        let travel = Travel::default();
+        let result = travel.get_flight(
+            "CZ1765".to_string(),
+            "2099-01-01 00:00:00".to_string(),
+        );
+        assert!(!result.is_success());
+    }
+
+    #[test]
+    fn reports_an_error_when_multiple_flights_match() {
+        let mut travel = Travel::default();
+        let duplicate = travel.flights.as_ref().unwrap()[0].clone();
+        let flight_no = duplicate.flight_no.clone();
+        let depart_time = duplicate.depart_time.clone();
+        travel.flights.as_mut().unwrap().push(duplicate);
+
+        let result = travel.get_flight(flight_no, depart_time);
+        assert!(!result.is_success());
+        assert!(result.message.contains("Multiple flights"));
+    }
+}
+
+#[cfg(test)]
+mod modify_flight_seat_accounting_tests {
+    use super::*;
+
+    #[test]
+    fn changing_flight_no_moves_a_seat_from_the_old_flight_to_the_new_one() {
+        let mut travel = Travel::default();
+        let old_seats_before = travel
+            .flights
+            .as_ref()
+            .unwrap()
+            .iter()
+            .find(|f| f.flight_no == "MU5678")
+            .unwrap()
+            .seats_available;
+        let new_seats_before = travel
+            .flights
+            .as_ref()
+            .unwrap()
+            .iter()
+            .find(|f| f.flight_no == "CZ4321")
+            .unwrap()
+            .seats_available;
+
+        let result = travel.modify_flight(
+            "user1".to_string(),
+            "res_2".to_string(),
+            Some("CZ4321".to_string()),
+            None,
+            None,
+            None,
+        );
+        assert!(result.is_success(), "{}", result.message);
+
+        let old_seats_after = travel
+            .flights
+            .as_ref()
+            .unwrap()
+            .iter()
+            .find(|f| f.flight_no == "MU5678")
+            .unwrap()
+            .seats_available;
+        let new_seats_after = travel
+            .flights
+            .as_ref()
+            .unwrap()
+            .iter()
+            .find(|f| f.flight_no == "CZ4321")
+            .unwrap()
+            .seats_available;
+        assert_eq!(old_seats_after, old_seats_before + 1);
+        assert_eq!(new_seats_after, new_seats_before - 1);
+    }
+
+    #[test]
+    fn rejects_changing_to_a_sold_out_flight() {
+        let mut travel = Travel::default();
+        travel
+            .flights
+            .as_mut()
+            .unwrap()
+            .iter_mut()
+            .find(|f| f.flight_no == "CZ4321")
+            .unwrap()
+            .seats_available = 0;
+
+        let result = travel.modify_flight(
+            "user1".to_string(),
+            "res_2".to_string(),
+            Some("CZ4321".to_string()),
+            None,
+            None,
+            None,
+        );
+        assert!(!result.is_success());
+        assert!(result.message.contains("sold out"));
+    }
+}
+
+#[cfg(test)]
+mod get_cheapest_flight_tests {
+    use super::*;
+
+    #[test]
+    fn picks_the_lowest_priced_flight_for_the_requested_cabin() {
+        let travel = Travel::default();
+        // Shanghai->Beijing has three available economy options: MU5678 (1900),
+        // CZ4321 (2500), CZ4352 (1600); the cheapest is CZ4352
+        let result = travel.get_cheapest_flight(
+            "Shanghai".to_string(),
+            "Beijing".to_string(),
+            "Economy Class".to_string(),
+        );
+        assert!(result.is_success(), "{}", result.message);
+        assert!(result.message.contains("CZ4352"));
+    }
+
+    #[test]
+    fn errors_when_no_flight_serves_the_requested_cabin_on_the_route() {
+        let travel = Travel::default();
+        let result = travel.get_cheapest_flight(
+            "Nowhere".to_string(),
+            "Nowhere Else".to_string(),
+            "Economy Class".to_string(),
+        );
+        assert!(!result.is_success());
+    }
+}
+
+#[cfg(test)]
+mod cancel_reservation_current_time_override_tests {
+    use super::*;
+
+    #[test]
+    fn the_same_reservation_can_be_free_or_fee_based_depending_on_the_supplied_now() {
+        let mut travel_far_out = Travel::default();
+        let free_result = travel_far_out.cancel_reservation(
+            "user1".to_string(),
+            "res_1".to_string(),
+            "Change of plans.".to_string(),
+            Some("2024-07-13 00:00:00".to_string()),
+        );
+        assert!(free_result.is_success(), "{}", free_result.message);
+        assert!(free_result.message.contains("Free cancellation"));
+
+        let mut travel_close_in = Travel::default();
+        let fee_result = travel_close_in.cancel_reservation(
+            "user1".to_string(),
+            "res_1".to_string(),
+            "Change of plans.".to_string(),
+            Some("2024-07-15 00:00:00".to_string()),
+        );
+        assert!(fee_result.is_success(), "{}", fee_result.message);
+        assert!(fee_result.message.contains("cancellation fee"));
+    }
+}
+
+#[cfg(test)]
+mod cabin_class_validation_tests {
+    use super::*;
+
+    #[test]
+    fn rejects_a_lowercase_cabin_string_with_a_friendly_error_instead_of_panicking() {
+        let mut travel = Travel::default();
+        let result = travel.reserve_flight(
+            "user1".to_string(),
+            "password123".to_string(),
+            "CA1234".to_string(),
+            "economy".to_string(),
+            "cash".to_string(),
+            0,
+        );
+        assert!(!result.is_success());
+        assert!(result.message.contains("Unknown cabin class"));
+    }
+}
+
+#[cfg(test)]
+mod reserve_round_trip_rollback_tests {
+    use super::*;
+
+    #[test]
+    fn a_sold_out_return_leg_rolls_back_the_outbound_booking() {
+        let mut travel = Travel::default();
+        travel
+            .flights
+            .as_mut()
+            .unwrap()
+            .iter_mut()
+            .find(|f| f.flight_no == "MU5678")
+            .unwrap()
+            .seats_available = 0;
+
+        let bank_before = travel.users.get("user1").unwrap().bank_balance;
+        let reservations_before = travel.reservations.len();
+        let outbound_seats_before = travel
+            .flights
+            .as_ref()
+            .unwrap()
+            .iter()
+            .find(|f| f.flight_no == "CA1234")
+            .unwrap()
+            .seats_available;
+
+        let result = travel.reserve_round_trip(
+            "user1".to_string(),
+            "password123".to_string(),
+            "CA1234".to_string(),
+            "MU5678".to_string(),
+            "Economy Class".to_string(),
+            "bank".to_string(),
+            0,
+        );
+        assert!(!result.is_success());
+
+        assert_eq!(travel.reservations.len(), reservations_before);
+        assert_eq!(travel.users.get("user1").unwrap().bank_balance, bank_before);
+        let outbound_seats_after = travel
+            .flights
+            .as_ref()
+            .unwrap()
+            .iter()
+            .find(|f| f.flight_no == "CA1234")
+            .unwrap()
+            .seats_available;
+        assert_eq!(outbound_seats_after, outbound_seats_before);
+    }
+
+    #[test]
+    fn successfully_books_both_legs_when_both_have_seats() {
+        let mut travel = Travel::default();
+        let result = travel.reserve_round_trip(
+            "user1".to_string(),
+            "password123".to_string(),
+            "CA1234".to_string(),
+            "MU5678".to_string(),
+            "Economy Class".to_string(),
+            "bank".to_string(),
+            0,
+        );
+        assert!(result.is_success(), "{}", result.message);
+        assert_eq!(
+            travel
+                .reservations
+                .iter()
+                .filter(|r| r.user_id == "user1")
+                .count(),
+            4 // the two seeded reservations plus the new outbound/return pair
+        );
+    }
+}
+
+
+#[cfg(test)]
+mod configurable_current_time_tests {
+    use super::*;
+
+    #[test]
+    fn deserializes_a_custom_current_time_and_uses_it_as_the_default_cancellation_now() {
+        let mut travel = Travel::default();
+        // moving "now" to within 24 hours of CA1234's 2024-07-15 08:00:00 departure
+        // should switch the default (non-overridden) cancellation from free to fee-based
+        travel.current_time = "2024-07-15 00:00:00".to_string();
+
+        let result = travel.cancel_reservation(
+            "user1".to_string(),
+            "res_1".to_string(),
+            "Change of plans.".to_string(),
+            None,
+        );
+        assert!(result.is_success(), "{}", result.message);
+        assert!(result.message.contains("cancellation fee"));
+    }
+
+    #[test]
+    fn current_time_round_trips_through_serde() {
+        let mut travel = Travel::default();
+        travel.current_time = "2024-07-01 12:00:00".to_string();
+        let serialized = serde_json::to_string(&travel).unwrap();
+        let deserialized: Travel = serde_json::from_str(&serialized).unwrap();
+        assert_eq!(deserialized.current_time, "2024-07-01 12:00:00");
+    }
+}
+
+#[cfg(test)]
+mod baggage_count_validation_tests {
+    use super::*;
+
+    #[test]
+    fn zero_baggage_on_reservation_is_valid_and_incurs_no_fee() {
+        let mut travel = Travel::default();
+        let cash_before = travel.users.get("user1").unwrap().cash_balance;
+
+        let result = travel.reserve_flight(
+            "user1".to_string(),
+            "password123".to_string(),
+            "CA1234".to_string(),
+            "Economy Class".to_string(),
+            "cash".to_string(),
+            0,
+        );
+        assert!(result.is_success(), "{}", result.message);
+
+        // only the flight fare (1200) should be deducted; zero baggage adds no fee
+        assert_eq!(
+            travel.users.get("user1").unwrap().cash_balance,
+            NotNan::new(cash_before.into_inner() - 1200.0).unwrap()
+        );
+    }
+
+    #[test]
+    fn zero_add_baggage_on_modify_is_an_explicit_no_op() {
+        let mut travel = Travel::default();
+        let baggage_before = travel
+            .reservations
+            .iter()
+            .find(|r| r.reservation_id == "res_1")
+            .unwrap()
+            .baggage;
+
+        let result = travel.modify_flight(
+            "user1".to_string(),
+            "res_1".to_string(),
+            None,
+            None,
+            Some(0),
+            None,
+        );
+        assert!(result.is_success(), "{}", result.message);
+        assert!(result.message.contains("no-op"));
+
+        let baggage_after = travel
+            .reservations
+            .iter()
+            .find(|r| r.reservation_id == "res_1")
+            .unwrap()
+            .baggage;
+        assert_eq!(baggage_after, baggage_before);
+    }
+}
+
+#[cfg(test)]
+mod can_afford_flight_tests {
+    use super::*;
+
+    #[test]
+    fn a_user_can_afford_economy_but_not_business_on_the_same_flight() {
+        let travel = Travel::default();
+
+        let economy_result = travel.can_afford_flight(
+            "user1".to_string(),
+            "password123".to_string(),
+            "CA1234".to_string(),
+            "Economy Class".to_string(),
+            0,
+            "cash".to_string(),
+        );
+        assert!(economy_result.is_success());
+        assert!(economy_result.message.contains("Yes, you can afford"));
+
+        let business_result = travel.can_afford_flight(
+            "user1".to_string(),
+            "password123".to_string(),
+            "CA1234".to_string(),
+            "Business Class".to_string(),
+            0,
+            "cash".to_string(),
+        );
+        assert!(business_result.is_success());
+        assert!(business_result.message.contains("No, you cannot afford"));
+        assert!(business_result.message.contains("Shortfall"));
+    }
+}
+
+#[cfg(test)]
+mod list_user_reservations_tests {
+    use super::*;
+
+    #[test]
+    fn wrong_password_is_rejected() {
+        let travel = Travel::default();
+        let result = travel.list_user_reservations("user1".to_string(), "wrong_password".to_string());
+        assert!(!result.is_success());
+        assert!(result.message.contains("Authentication failed"));
+    }
+
+    #[test]
+    fn reservations_are_enriched_with_flight_info_and_sorted_by_departure_time() {
+        let travel = Travel::default();
+        let result = travel.list_user_reservations("user1".to_string(), "password123".to_string());
+        assert!(result.is_success(), "{}", result.message);
+
+        let reservations: Vec<Reservation> = {
+            let prefix = "Reservations: ";
+            let json_str = result.message.strip_prefix(prefix).expect("unexpected message format");
+            serde_json::from_str(json_str).expect("failed to parse reservations")
+        };
+
+        assert_eq!(reservations.len(), 2, "user1 has two reservations in the default data");
+        for reservation in &reservations {
+            assert_eq!(reservation.user_id, "user1");
+            assert!(reservation.flight_info.is_some(), "flight_info should be injected for every reservation");
+        }
+        let depart_times: Vec<&str> = reservations
+            .iter()
+            .map(|reservation| reservation.flight_info.as_ref().unwrap().depart_time.as_str())
+            .collect();
+        let mut sorted_depart_times = depart_times.clone();
+        sorted_depart_times.sort();
+        assert_eq!(depart_times, sorted_depart_times, "reservations should be sorted by departure time");
+    }
 }
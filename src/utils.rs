@@ -1,4 +1,8 @@
-use std::{fs::File, io::{BufRead, BufReader}, path::Path};
+use std::{fs::File, io::{BufRead, BufReader, Write}, path::Path, sync::Arc};
+
+use atomic_refcell::AtomicRefCell;
+use indexmap::IndexMap;
+use serde::Serialize;
 
 pub fn load_json_lines(file_path: impl AsRef<Path>) -> Result<Vec<serde_json::Value>, String> {
     let file = File::open(&file_path).map_err(|e| {
@@ -21,6 +25,42 @@ pub fn load_json_lines(file_path: impl AsRef<Path>) -> Result<Vec<serde_json::Va
     Ok(results)
 }
 
+/// `(1-based line number, parse error message)` for a line skipped by
+/// [`load_json_lines_lenient`].
+pub type LenientLoadWarning = (usize, String);
+
+/// Like [`load_json_lines`], but a single corrupt record (e.g. from an interrupted
+/// write) doesn't poison the whole load: blank lines are skipped, and a line that
+/// fails to parse is reported as a [`LenientLoadWarning`] instead of aborting. Used
+/// by the generator's resume path, where a partially written output file is expected.
+pub fn load_json_lines_lenient(
+    file_path: impl AsRef<Path>,
+) -> Result<(Vec<serde_json::Value>, Vec<LenientLoadWarning>), String> {
+    let file = File::open(&file_path).map_err(|e| {
+        format!(
+            "Unable to open file {}: {}",
+            file_path.as_ref().display(),
+            e
+        )
+    })?;
+    let reader = BufReader::new(file);
+
+    let mut results = Vec::new();
+    let mut warnings = Vec::new();
+
+    for (line_number, line) in reader.lines().enumerate() {
+        let line = line.map_err(|e| format!("Unable to read line: {}", e))?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        match serde_json::from_str(&line) {
+            Ok(line_json) => results.push(line_json),
+            Err(e) => warnings.push((line_number + 1, format!("Unable to parse JSON: {}", e))),
+        }
+    }
+    Ok((results, warnings))
+}
+
 pub fn write_json_lines_to_file(
     file_path: impl AsRef<Path>,
     results: &Vec<serde_json::Value>,
@@ -41,4 +81,247 @@ pub fn write_json_lines_to_file(
         .map_err(|e| format!("Unable to flush file: {}", e))?;
 
     Ok(())
-}
\ No newline at end of file
+}
+
+/// Sorts result entries in place by their `id` field, using the same ordering
+/// `AceGenerator::sort_all_files_after_generation` applies before evaluation:
+/// multi-turn datasets ("123_456") sort by (major, minor), everything else sorts
+/// by the trailing number in the id.
+pub fn sort_entries_by_id(entries: &mut Vec<serde_json::Value>, is_multi_turn: bool) {
+    entries.sort_by(|a, b| {
+        let id_a = a.get("id").and_then(|v| v.as_str()).unwrap_or("");
+        let id_b = b.get("id").and_then(|v| v.as_str()).unwrap_or("");
+
+        if is_multi_turn {
+            // For multi_turn datasets, IDs are like "123_456"
+            // Compare by first number (major), then second number (minor)
+            let parse_multi_turn_id = |id: &str| -> (i64, i64) {
+                let parts: Vec<&str> = id.split('_').collect();
+                let major = parts.first().and_then(|s| s.parse().ok()).unwrap_or(0);
+                let minor = parts.get(1).and_then(|s| s.parse().ok()).unwrap_or(0);
+                (major, minor)
+            };
+            let (major_a, minor_a) = parse_multi_turn_id(id_a);
+            let (major_b, minor_b) = parse_multi_turn_id(id_b);
+            (major_a, minor_a).cmp(&(major_b, minor_b))
+        } else {
+            // For other datasets, extract trailing number from ID
+            let extract_trailing_number = |id: &str| -> i64 {
+                id.chars()
+                    .rev()
+                    .take_while(|c| c.is_ascii_digit())
+                    .collect::<String>()
+                    .chars()
+                    .rev()
+                    .collect::<String>()
+                    .parse()
+                    .unwrap_or(0)
+            };
+            let num_a = extract_trailing_number(id_a);
+            let num_b = extract_trailing_number(id_b);
+            num_a.cmp(&num_b)
+        }
+    });
+}
+
+/// Merges multiple sharded result files into one: entries are concatenated in
+/// the order `paths` is given, deduplicated by `id` (keeping the last occurrence,
+/// i.e. the latest shard wins), sorted with [`sort_entries_by_id`], and written
+/// to `output_path`.
+pub fn merge_result_files(
+    paths: &[impl AsRef<Path>],
+    output_path: impl AsRef<Path>,
+    is_multi_turn: bool,
+) -> Result<(), String> {
+    let mut merged: IndexMap<String, serde_json::Value> = IndexMap::new();
+    for path in paths {
+        for entry in load_json_lines(path)? {
+            let id = entry
+                .get("id")
+                .and_then(|v| v.as_str())
+                .ok_or_else(|| format!("Entry in {} is missing an 'id' field", path.as_ref().display()))?
+                .to_string();
+            merged.insert(id, entry);
+        }
+    }
+    let mut entries: Vec<serde_json::Value> = merged.into_values().collect();
+    sort_entries_by_id(&mut entries, is_multi_turn);
+    write_json_lines_to_file(output_path, &entries)
+}
+/// Wraps a shared output file so concurrent writers can't interleave a record: each
+/// record is serialized to a `String` (with its trailing newline) before the lock is
+/// taken, then written with a single `write_all` call, guaranteeing the whole line
+/// reaches the file as one write rather than as separate calls for the body and the
+/// newline.
+pub struct JsonLinesWriter {
+    file: Arc<AtomicRefCell<File>>,
+}
+
+impl JsonLinesWriter {
+    pub fn new(file: Arc<AtomicRefCell<File>>) -> Self {
+        JsonLinesWriter { file }
+    }
+
+    pub fn write_line(&self, record: &impl Serialize) -> Result<(), String> {
+        let mut line = serde_json::to_string(record)
+            .map_err(|e| format!("failed to serialize record: {}", e))?;
+        line.push('\n');
+        // `borrow_mut` panics outright on a conflicting borrow; under real concurrency
+        // (multiple threads sharing this output file) that would crash a writer instead
+        // of just making it wait its turn, so spin on `try_borrow_mut` until the lock is free.
+        let mut file_ref = loop {
+            match self.file.try_borrow_mut() {
+                Ok(file_ref) => break file_ref,
+                Err(_) => continue,
+            }
+        };
+        file_ref
+            .write_all(line.as_bytes())
+            .map_err(|e| format!("failed to write record: {}", e))
+    }
+}
+
+#[cfg(test)]
+mod merge_result_files_tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    static COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+    fn scratch_path(suffix: &str) -> std::path::PathBuf {
+        let n = COUNTER.fetch_add(1, Ordering::Relaxed);
+        std::env::temp_dir().join(format!(
+            "merge_result_files_test_{}_{}_{}",
+            std::process::id(),
+            n,
+            suffix
+        ))
+    }
+
+    #[test]
+    fn merges_overlapping_shards_deduplicated_and_sorted() {
+        let shard_a = scratch_path("shard_a.jsonl");
+        let shard_b = scratch_path("shard_b.jsonl");
+        let output = scratch_path("output.jsonl");
+
+        write_json_lines_to_file(
+            &shard_a,
+            &vec![
+                serde_json::json!({"id": "3", "result": "from_a_3"}),
+                serde_json::json!({"id": "1", "result": "from_a_1"}),
+            ],
+        )
+        .unwrap();
+        write_json_lines_to_file(
+            &shard_b,
+            &vec![
+                // id "1" overlaps with shard_a; shard_b is passed second, so its
+                // value should win
+                serde_json::json!({"id": "1", "result": "from_b_1"}),
+                serde_json::json!({"id": "2", "result": "from_b_2"}),
+            ],
+        )
+        .unwrap();
+
+        merge_result_files(&[&shard_a, &shard_b], &output, false).unwrap();
+
+        let merged = load_json_lines(&output).unwrap();
+        let ids: Vec<&str> = merged.iter().map(|e| e["id"].as_str().unwrap()).collect();
+        assert_eq!(ids, vec!["1", "2", "3"]);
+        assert_eq!(merged[0]["result"], "from_b_1");
+
+        let _ = std::fs::remove_file(&shard_a);
+        let _ = std::fs::remove_file(&shard_b);
+        let _ = std::fs::remove_file(&output);
+    }
+}
+
+#[cfg(test)]
+mod load_json_lines_lenient_tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    static COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+    fn scratch_path(suffix: &str) -> std::path::PathBuf {
+        let n = COUNTER.fetch_add(1, Ordering::Relaxed);
+        std::env::temp_dir().join(format!(
+            "load_json_lines_lenient_test_{}_{}_{}",
+            std::process::id(),
+            n,
+            suffix
+        ))
+    }
+
+    #[test]
+    fn a_bad_line_among_several_good_ones_is_reported_but_does_not_abort_the_load() {
+        let path = scratch_path("mixed.jsonl");
+        std::fs::write(
+            &path,
+            "{\"id\": \"1\"}\n\nnot valid json\n{\"id\": \"2\"}\n{\"id\": \"3\"}\n",
+        )
+        .unwrap();
+
+        let (entries, warnings) = load_json_lines_lenient(&path).unwrap();
+
+        let ids: Vec<&str> = entries.iter().map(|e| e["id"].as_str().unwrap()).collect();
+        assert_eq!(ids, vec!["1", "2", "3"]);
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].0, 3);
+
+        let _ = std::fs::remove_file(&path);
+    }
+}
+
+#[cfg(test)]
+mod json_lines_writer_tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    static COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+    fn scratch_path(suffix: &str) -> std::path::PathBuf {
+        let n = COUNTER.fetch_add(1, Ordering::Relaxed);
+        std::env::temp_dir().join(format!(
+            "json_lines_writer_test_{}_{}_{}",
+            std::process::id(),
+            n,
+            suffix
+        ))
+    }
+
+    #[test]
+    fn concurrent_writers_never_interleave_a_line() {
+        let path = scratch_path("concurrent.jsonl");
+        let file = File::create(&path).unwrap();
+        let shared_file = Arc::new(AtomicRefCell::new(file));
+
+        const NUM_THREADS: usize = 8;
+        const LINES_PER_THREAD: usize = 50;
+
+        let handles: Vec<_> = (0..NUM_THREADS)
+            .map(|thread_index| {
+                let writer = JsonLinesWriter::new(shared_file.clone());
+                std::thread::spawn(move || {
+                    for i in 0..LINES_PER_THREAD {
+                        writer
+                            .write_line(&serde_json::json!({"thread": thread_index, "i": i}))
+                            .unwrap();
+                    }
+                })
+            })
+            .collect();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+
+        let entries = load_json_lines(&path).unwrap();
+        assert_eq!(entries.len(), NUM_THREADS * LINES_PER_THREAD);
+        for entry in &entries {
+            assert!(entry["thread"].is_number());
+            assert!(entry["i"].is_number());
+        }
+
+        let _ = std::fs::remove_file(&path);
+    }
+}
@@ -1,3 +1,6 @@
+use std::collections::HashMap;
+use std::sync::LazyLock;
+
 use indexmap::IndexMap;
 use ordered_float::NotNan;
 use serde::{Deserialize, Serialize};
@@ -16,9 +19,16 @@ use crate::{
 // These mirror the Python classes in ACEBench/model_inference/multi_turn/scenariosen/
 // ============================================================================
 
+/// Suffixes that mark a function call as a "bait" function the agent should never
+/// call; matches the perturbation datasets' current convention of appending one of
+/// these to an otherwise-valid function name.
+fn default_bait_function_suffixes() -> Vec<String> {
+    vec!["_1".to_string(), "_Budget".to_string(), "_Fast".to_string()]
+}
+
 /// Unified world state for multi-turn/multi-step scenarios
 /// Contains the state of all involved API instances
-#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct WorldState {
     #[serde(rename = "BaseApi", default, skip_serializing_if = "Option::is_none")]
     pub base_api: Option<BaseApi>,
@@ -44,6 +54,25 @@ pub struct WorldState {
     pub travel: Option<Travel>,
     #[serde(default)]
     pub called_a_bait_function: bool,
+    /// Function-name suffixes that mark a bait function (see
+    /// `default_bait_function_suffixes`); configurable per scenario so a dataset can
+    /// extend or narrow the set without a code change.
+    #[serde(default = "default_bait_function_suffixes")]
+    pub bait_function_suffixes: Vec<String>,
+}
+
+impl Default for WorldState {
+    fn default() -> Self {
+        WorldState {
+            base_api: None,
+            message_api: None,
+            reminder_api: None,
+            food_platform: None,
+            travel: None,
+            called_a_bait_function: false,
+            bait_function_suffixes: default_bait_function_suffixes(),
+        }
+    }
 }
 
 impl WorldState {
@@ -111,19 +140,75 @@ impl WorldState {
             }
         }
     }
+
+    /// Single source of truth for the wifi flag: updates `base_api` and every
+    /// populated sub-API's nested `base_api` in one place, so the root and the
+    /// sub-API copies (propagated once, at setup time, by
+    /// `populate_with_involved_classes`) can never drift apart after a toggle.
+    pub fn set_wifi(&mut self, wifi: bool) {
+        if let Some(base_api) = &mut self.base_api {
+            base_api.wifi = wifi;
+        }
+        if let Some(food_platform) = &mut self.food_platform {
+            food_platform.base_api.wifi = wifi;
+        }
+        if let Some(message_api) = &mut self.message_api {
+            message_api.base_api.wifi = wifi;
+        }
+        if let Some(reminder_api) = &mut self.reminder_api {
+            reminder_api.base_api.wifi = wifi;
+        }
+    }
+
+    /// Single source of truth for the logged_in flag: see [`WorldState::set_wifi`].
+    pub fn set_logged_in(&mut self, logged_in: bool) {
+        if let Some(base_api) = &mut self.base_api {
+            base_api.logged_in = logged_in;
+        }
+        if let Some(food_platform) = &mut self.food_platform {
+            food_platform.base_api.logged_in = logged_in;
+        }
+        if let Some(message_api) = &mut self.message_api {
+            message_api.base_api.logged_in = logged_in;
+        }
+        if let Some(reminder_api) = &mut self.reminder_api {
+            reminder_api.base_api.logged_in = logged_in;
+        }
+    }
+
     pub fn execute_function_calls(
         &mut self,
         function_calls: &Vec<FunctionCallHygienic>,
     ) -> Vec<ExecutionResult> {
+        self.execute_function_calls_with_limit(function_calls, None)
+    }
+
+    /// Same as `execute_function_calls`, but rejects the whole turn without executing
+    /// anything if it contains more than `max_calls_per_turn` calls. `None` means unlimited.
+    pub fn execute_function_calls_with_limit(
+        &mut self,
+        function_calls: &Vec<FunctionCallHygienic>,
+        max_calls_per_turn: Option<usize>,
+    ) -> Vec<ExecutionResult> {
+        if let Some(max_calls) = max_calls_per_turn
+            && function_calls.len() > max_calls
+        {
+            return vec![ExecutionResult::error(format!(
+                "This turn contains {} function calls, which exceeds the maximum of {} allowed per turn. Please batch fewer calls.",
+                function_calls.len(),
+                max_calls
+            ))];
+        }
         // let function_call_names: Vec<&str> =
         //     function_calls.iter().map(|fc| fc.name.as_str()).collect();
         // println!("function calls to execute: {:?}", function_call_names);
         let mut execution_results: Vec<ExecutionResult> = Vec::new();
         for function_call in function_calls.iter() {
             let parameters = serde_json::to_value(function_call.parameters.clone()).unwrap();
-            if function_call.name.ends_with("_1")
-                || function_call.name.ends_with("_Budget")
-                || function_call.name.ends_with("_Fast")
+            if self
+                .bait_function_suffixes
+                .iter()
+                .any(|suffix| function_call.name.ends_with(suffix.as_str()))
             {
                 self.called_a_bait_function = true;
                 execution_results.push(ExecutionResult::error(format!(
@@ -134,413 +219,31 @@ impl WorldState {
             }
             match function_call.name.as_str() {
                 "turn_on_wifi" | "T_O_W" => {
-                    if let Some(base_api) = &mut self.base_api {
-                        // only need to push once, assuming base_api always exists if other APIs exist
-                        execution_results.push(base_api.turn_on_wifi());
-                    }
-                    if let Some(food_platform) = &mut self.food_platform {
-                        food_platform.base_api.turn_on_wifi();
-                    }
-                    if let Some(message_api) = &mut self.message_api {
-                        message_api.base_api.turn_on_wifi();
-                    }
-                    if let Some(reminder_api) = &mut self.reminder_api {
-                        reminder_api.base_api.turn_on_wifi();
+                    // only need to push once, assuming base_api always exists if other APIs exist
+                    if self.base_api.is_some() {
+                        execution_results
+                            .push(ExecutionResult::success("Wi-Fi has been turned on".to_string()));
                     }
+                    self.set_wifi(true);
                 }
                 "login_device" | "L_D" => {
-                    if let Some(base_api) = &mut self.base_api {
-                        // only need to push once, assuming base_api always exists if other APIs exist
-                        execution_results.push(base_api.login_device());
-                    }
-                    if let Some(food_platform) = &mut self.food_platform {
-                        food_platform.base_api.login_device();
-                    }
-                    if let Some(message_api) = &mut self.message_api {
-                        message_api.base_api.login_device();
-                    }
-                    if let Some(reminder_api) = &mut self.reminder_api {
-                        reminder_api.base_api.login_device();
-                    }
-                }
-                // travel function calls
-                "get_flight_details" | "G_F_D" => {
-                    if let Some(travel) = &mut self.travel {
-                        let execution_result = match serde_json::from_value::<
-                            travel::GetFlightDetailsArgs,
-                        >(parameters.clone())
-                        {
-                            Ok(a) => travel.get_flight_details(a.origin, a.destination),
-                            Err(e) => ExecutionResult::error(format!(
-                                "Failed to parse parameters for get_flight_details: {}",
-                                e
-                            )),
-                        };
-                        execution_results.push(execution_result);
-                    }
-                }
-                "get_user_details" | "G_U_D" => {
-                    if let Some(travel) = &mut self.travel {
-                        let execution_result = match serde_json::from_value::<
-                            travel::GetUserDetailsArgs,
-                        >(parameters.clone())
-                        {
-                            Ok(a) => travel.get_user_details(a.user_id, a.password),
-                            Err(e) => ExecutionResult::error(format!(
-                                "Failed to parse parameters for get_user_details: {}",
-                                e
-                            )),
-                        };
-                        execution_results.push(execution_result);
-                    }
-                }
-                "get_reservation_details" | "G_R_D" => {
-                    if let Some(travel) = &mut self.travel {
-                        let execution_result = match serde_json::from_value::<
-                            travel::GetReservationDetailsArgs,
-                        >(parameters.clone())
-                        {
-                            Ok(a) => travel.get_reservation_details(a.reservation_id, a.user_id),
-                            Err(e) => ExecutionResult::error(format!(
-                                "Failed to parse parameters for get_reservation_details: {}",
-                                e
-                            )),
-                        };
-                        execution_results.push(execution_result);
-                    }
-                }
-                "find_transfer_flights" | "F_T_F" => {
-                    if let Some(travel) = &mut self.travel {
-                        let execution_result = match serde_json::from_value::<
-                            travel::FindTransferFlightsArgs,
-                        >(parameters.clone())
-                        {
-                            Ok(a) => travel.find_transfer_flights(
-                                a.origin_city,
-                                a.transfer_city,
-                                a.destination_city,
-                            ),
-                            Err(e) => ExecutionResult::error(format!(
-                                "Failed to parse parameters for find_transfer_flights: {}",
-                                e
-                            )),
-                        };
-                        execution_results.push(execution_result);
-                    }
-                }
-                "reserve_flight" | "R_F" => {
-                    if let Some(travel) = &mut self.travel {
-                        let execution_result = match serde_json::from_value::<
-                            travel::ReserveFlightArgs,
-                        >(parameters.clone())
-                        {
-                            Ok(a) => travel.reserve_flight(
-                                a.user_id,
-                                a.password,
-                                a.flight_no,
-                                a.cabin,
-                                a.payment_method,
-                                a.baggage_count,
-                            ),
-                            Err(e) => ExecutionResult::error(format!(
-                                "Failed to parse parameters for reserve_flight: {}",
-                                e
-                            )),
-                        };
-                        execution_results.push(execution_result);
-                    }
-                }
-                "modify_flight" | "M_F" => {
-                    if let Some(travel) = &mut self.travel {
-                        let execution_result = match serde_json::from_value::<
-                            travel::ModifyFlightArgs,
-                        >(parameters.clone())
-                        {
-                            Ok(a) => travel.modify_flight(
-                                a.user_id,
-                                a.reservation_id,
-                                a.new_flight_no,
-                                a.new_cabin,
-                                a.add_baggage,
-                                a.new_payment_method,
-                            ),
-                            Err(e) => ExecutionResult::error(format!(
-                                "Failed to parse parameters for modify_flight: {}",
-                                e
-                            )),
-                        };
-                        execution_results.push(execution_result);
-                    }
-                }
-                "cancel_reservation" | "C_R" => {
-                    if let Some(travel) = &mut self.travel {
-                        let execution_result = match serde_json::from_value::<
-                            travel::CancelReservationArgs,
-                        >(parameters.clone())
-                        {
-                            Ok(a) => {
-                                travel.cancel_reservation(a.user_id, a.reservation_id, a.reason)
-                            }
-                            Err(e) => ExecutionResult::error(format!(
-                                "Failed to parse parameters for cancel_reservation: {}",
-                                e
-                            )),
-                        };
-                        execution_results.push(execution_result);
-                    }
-                }
-                // food services function calls
-                "login_food_platform" | "L_F_P" => {
-                    if let Some(food_platform) = &mut self.food_platform {
-                        let execution_result = match serde_json::from_value::<
-                            food_services::LoginFoodPlatformArgs,
-                        >(parameters.clone())
-                        {
-                            Ok(a) => food_platform.login_food_platform(a.username, a.password),
-                            Err(e) => ExecutionResult::error(format!(
-                                "Failed to parse parameters for login_food_platform: {}",
-                                e
-                            )),
-                        };
-                        execution_results.push(execution_result);
-                    }
-                }
-                "view_logged_in_users" | "V_L_I_U" => {
-                    if let Some(food_platform) = &mut self.food_platform {
-                        execution_results.push(food_platform.view_logged_in_users());
-                    }
-                }
-                "check_balance" | "C_B" => {
-                    if let Some(food_platform) = &mut self.food_platform {
-                        let execution_result = match serde_json::from_value::<
-                            food_services::CheckBalanceArgs,
-                        >(parameters.clone())
-                        {
-                            Ok(a) => food_platform.check_balance(a.user_name),
-                            Err(e) => ExecutionResult::error(format!(
-                                "Failed to parse parameters for check_balance: {}",
-                                e
-                            )),
-                        };
-                        execution_results.push(execution_result);
-                    }
-                }
-                "add_food_delivery_order" | "A_F_D_O" => {
-                    if let Some(food_platform) = &mut self.food_platform {
-                        let execution_result = match serde_json::from_value::<
-                            food_services::AddFoodDeliveryOrderArgs,
-                        >(parameters.clone())
-                        {
-                            Ok(a) => food_platform.add_food_delivery_order(
-                                a.username,
-                                a.merchant_name,
-                                a.items,
-                            ),
-                            Err(e) => ExecutionResult::error(format!(
-                                "Failed to parse parameters for add_food_delivery_order: {}",
-                                e
-                            )),
-                        };
-                        execution_results.push(execution_result);
-                    }
-                }
-                "get_products" | "G_P" => {
-                    if let Some(food_platform) = &mut self.food_platform {
-                        let execution_result = match serde_json::from_value::<
-                            food_services::GetProductArgs,
-                        >(parameters.clone())
-                        {
-                            Ok(a) => food_platform.get_products(a.merchant_name),
-                            Err(e) => ExecutionResult::error(format!(
-                                "Failed to parse parameters for get_products: {}",
-                                e
-                            )),
-                        };
-                        execution_results.push(execution_result);
-                    }
-                }
-                "view_orders" | "V_O" => {
-                    if let Some(food_platform) = &mut self.food_platform {
-                        let execution_result = match serde_json::from_value::<
-                            food_services::ViewOrdersArgs,
-                        >(parameters.clone())
-                        {
-                            Ok(a) => food_platform.view_orders(a.user_name),
-                            Err(e) => ExecutionResult::error(format!(
-                                "Failed to parse parameters for view_orders: {}",
-                                e
-                            )),
-                        };
-                        execution_results.push(execution_result);
-                    }
-                }
-                "search_orders" | "S_O" => {
-                    if let Some(food_platform) = &mut self.food_platform {
-                        let execution_result = match serde_json::from_value::<
-                            food_services::SearchOrdersArgs,
-                        >(parameters.clone())
-                        {
-                            Ok(a) => food_platform.search_orders(a.keyword),
-                            Err(e) => ExecutionResult::error(format!(
-                                "Failed to parse parameters for search_orders: {}",
-                                e
-                            )),
-                        };
-                        execution_results.push(execution_result);
-                    }
-                }
-                // message function calls
-                "send_message" | "S_M" => {
-                    if let Some(message_api) = &mut self.message_api {
-                        let execution_result = match serde_json::from_value::<
-                            message::SendMessageArgs,
-                        >(parameters.clone())
-                        {
-                            Ok(a) => {
-                                message_api.send_message(a.sender_name, a.receiver_name, a.message)
-                            }
-                            Err(e) => ExecutionResult::error(format!(
-                                "Failed to parse parameters for send_message: {}",
-                                e
-                            )),
-                        };
-                        execution_results.push(execution_result);
-                    }
-                }
-                "delete_message" | "D_M" => {
-                    if let Some(message_api) = &mut self.message_api {
-                        let execution_result = match serde_json::from_value::<
-                            message::DeleteMessageArgs,
-                        >(parameters.clone())
-                        {
-                            Ok(a) => message_api.delete_message(a.message_id),
-                            Err(e) => ExecutionResult::error(format!(
-                                "Failed to parse parameters for delete_message: {}",
-                                e
-                            )),
-                        };
-                        execution_results.push(execution_result);
-                    }
-                }
-                "view_messages_between_users" | "V_M_B_U" => {
-                    if let Some(message_api) = &mut self.message_api {
-                        let execution_result = match serde_json::from_value::<
-                            message::ViewMessagesBetweenUsersArgs,
-                        >(parameters.clone())
-                        {
-                            Ok(a) => message_api
-                                .view_messages_between_users(a.sender_name, a.receiver_name),
-                            Err(e) => ExecutionResult::error(format!(
-                                "Failed to parse parameters for view_messages_between_users: {}",
-                                e
-                            )),
-                        };
-                        execution_results.push(execution_result);
-                    }
-                }
-                "search_messages" | "S_M2" => {
-                    if let Some(message_api) = &mut self.message_api {
-                        let execution_result = match serde_json::from_value::<
-                            message::SearchMessagesArgs,
-                        >(parameters.clone())
-                        {
-                            Ok(a) => message_api.search_messages(a.user_name, a.keyword),
-                            Err(e) => ExecutionResult::error(format!(
-                                "Failed to parse parameters for search_messages: {}",
-                                e
-                            )),
-                        };
-                        execution_results.push(execution_result);
-                    }
-                }
-                "get_all_message_times_with_ids" | "G_A_M_T_W_I" => {
-                    if let Some(message_api) = &mut self.message_api {
-                        execution_results.push(message_api.get_all_message_times_with_ids());
-                    }
-                }
-                "get_latest_message_id" | "G_L_M_I" => {
-                    if let Some(message_api) = &mut self.message_api {
-                        execution_results.push(message_api.get_latest_message_id());
-                    }
-                }
-                "get_earliest_message_id" | "G_E_M_I" => {
-                    if let Some(message_api) = &mut self.message_api {
-                        execution_results.push(message_api.get_earliest_message_id());
-                    }
-                }
-                // reminder functions
-                "view_reminder_by_title" | "V_R_B_T" => {
-                    if let Some(reminder_api) = &mut self.reminder_api {
-                        let execution_result = match serde_json::from_value::<
-                            reminder::ViewReminderByTitleArgs,
-                        >(parameters.clone())
-                        {
-                            Ok(a) => reminder_api.view_reminder_by_title(a.title),
-                            Err(e) => ExecutionResult::error(format!(
-                                "Failed to parse parameters for view_reminder_by_title: {}",
-                                e
-                            )),
-                        };
-                        execution_results.push(execution_result);
-                    }
-                }
-                "add_reminder" | "A_R" => {
-                    if let Some(reminder_api) = &mut self.reminder_api {
-                        let execution_result = match serde_json::from_value::<
-                            reminder::AddReminderArgs,
-                        >(parameters.clone())
-                        {
-                            Ok(a) => reminder_api.add_reminder(a.title, a.description, a.time),
-                            Err(e) => ExecutionResult::error(format!(
-                                "Failed to parse parameters for add_reminder: {}",
-                                e
-                            )),
-                        };
-                        execution_results.push(execution_result);
-                    }
-                }
-                "delete_reminder" | "D_R" => {
-                    if let Some(reminder_api) = &mut self.reminder_api {
-                        let execution_result = match serde_json::from_value::<
-                            reminder::DeleteReminderArgs,
-                        >(parameters.clone())
-                        {
-                            Ok(a) => reminder_api.delete_reminder(a.reminder_id),
-                            Err(e) => ExecutionResult::error(format!(
-                                "Failed to parse parameters for delete_reminder: {}",
-                                e
-                            )),
-                        };
-                        execution_results.push(execution_result);
+                    // only need to push once, assuming base_api always exists if other APIs exist
+                    if self.base_api.is_some() {
+                        execution_results
+                            .push(ExecutionResult::success("Device has been logged in".to_string()));
                     }
+                    self.set_logged_in(true);
                 }
-                "view_all_reminders" | "V_A_R" => {
-                    if let Some(reminder_api) = &mut self.reminder_api {
-                        execution_results.push(reminder_api.view_all_reminders());
+                name => {
+                    if let Some(handler) = DISPATCH_TABLE.get(name) {
+                        execution_results.push(handler(self, &parameters));
+                    } else {
+                        execution_results.push(ExecutionResult::error(format!(
+                            "Sorry, the tool {} is currently not available.",
+                            function_call.name
+                        )));
                     }
                 }
-                "search_reminders" | "S_R" => {
-                    if let Some(reminder_api) = &mut self.reminder_api {
-                        let execution_result = match serde_json::from_value::<
-                            reminder::SearchRemindersArgs,
-                        >(parameters.clone())
-                        {
-                            Ok(a) => reminder_api.search_reminders(a.keyword),
-                            Err(e) => ExecutionResult::error(format!(
-                                "Failed to parse parameters for search_reminders: {}",
-                                e
-                            )),
-                        };
-                        execution_results.push(execution_result);
-                    }
-                }
-                // _ => panic!("Unknown function call: {}", function_call.name),
-                _ => {
-                    execution_results.push(ExecutionResult::error(format!(
-                        "Sorry, the tool {} is currently not available.",
-                        function_call.name
-                    )));
-                }
             }
         }
         execution_results
@@ -589,4 +292,1246 @@ impl WorldState {
         }
         Ok(())
     }
+    /// Like [`Self::equals_ground_truth`], but collects every discrepancy instead of
+    /// stopping at the first one: one entry per sub-API that the ground truth expects
+    /// but the output lacks (a sub-API present only in the output is not reported),
+    /// plus every mismatch within a sub-API that exposes a collecting `diff` of its own
+    /// (currently `Travel`; the others only have `equals_ground_truth` and so still
+    /// report just their own first mismatch), so a failing agent run's error report can
+    /// show everything that went wrong in one pass instead of one mismatch at a time.
+    pub fn diff(&self, ground_truth: &WorldState) -> Vec<String> {
+        let mut discrepancies = Vec::new();
+        if self.called_a_bait_function {
+            discrepancies.push("Called a bait function, which is not allowed".to_string());
+        }
+        match (&self.base_api, &ground_truth.base_api) {
+            (None, Some(_)) => discrepancies
+                .push("BaseApi does not appear in the output but is expected by the ground truth".to_string()),
+            (Some(base), Some(ground_truth_base)) => {
+                if let Err(e) = base.equals_ground_truth(ground_truth_base) {
+                    discrepancies.push(format!("BaseApi: {}", e));
+                }
+            }
+            _ => {}
+        }
+        match (&self.message_api, &ground_truth.message_api) {
+            (None, Some(_)) => discrepancies
+                .push("MessageApi does not appear in the output but is expected by the ground truth".to_string()),
+            (Some(message_api), Some(ground_truth_message_api)) => {
+                if let Err(e) = message_api.equals_ground_truth(ground_truth_message_api) {
+                    discrepancies.push(format!("MessageApi: {}", e));
+                }
+            }
+            _ => {}
+        }
+        match (&self.reminder_api, &ground_truth.reminder_api) {
+            (None, Some(_)) => discrepancies
+                .push("ReminderApi does not appear in the output but is expected by the ground truth".to_string()),
+            (Some(reminder_api), Some(ground_truth_reminder_api)) => {
+                if let Err(e) = reminder_api.equals_ground_truth(ground_truth_reminder_api) {
+                    discrepancies.push(format!("ReminderApi: {}", e));
+                }
+            }
+            _ => {}
+        }
+        match (&self.food_platform, &ground_truth.food_platform) {
+            (None, Some(_)) => discrepancies
+                .push("FoodPlatform does not appear in the output but is expected by the ground truth".to_string()),
+            (Some(food_platform), Some(ground_truth_food_platform)) => {
+                if let Err(e) = food_platform.equals_ground_truth(ground_truth_food_platform) {
+                    discrepancies.push(format!("FoodPlatform: {}", e));
+                }
+            }
+            _ => {}
+        }
+        match (&self.travel, &ground_truth.travel) {
+            (None, Some(_)) => discrepancies
+                .push("Travel does not appear in the output but is expected by the ground truth".to_string()),
+            (Some(travel), Some(ground_truth_travel)) => {
+                discrepancies.extend(
+                    travel
+                        .diff(ground_truth_travel)
+                        .into_iter()
+                        .map(|e| format!("Travel: {}", e)),
+                );
+            }
+            _ => {}
+        }
+        discrepancies
+    }
+    /// Canonical JSON form with all object keys sorted recursively, so two worlds that are
+    /// logically equal but built via different insertion orders (IndexMaps preserve
+    /// insertion order by default) serialize identically. Opt-in: normal `Serialize` output
+    /// keeps insertion order for display; use this only when comparing serialized states or
+    /// emitting output meant to be diffed against a ground truth.
+    pub fn to_canonical_json(&self) -> serde_json::Value {
+        canonicalize_json(&serde_json::to_value(self).expect("WorldState must serialize to JSON"))
+    }
+}
+
+fn canonicalize_json(value: &serde_json::Value) -> serde_json::Value {
+    match value {
+        serde_json::Value::Object(map) => {
+            let sorted: std::collections::BTreeMap<String, serde_json::Value> = map
+                .iter()
+                .map(|(k, v)| (k.clone(), canonicalize_json(v)))
+                .collect();
+            serde_json::Value::Object(sorted.into_iter().collect())
+        }
+        serde_json::Value::Array(items) => {
+            serde_json::Value::Array(items.iter().map(canonicalize_json).collect())
+        }
+        other => other.clone(),
+    }
+}
+
+
+// Dispatch table for execute_function_calls: maps each function's full name and
+// abbreviation to the same handler, so both spellings route through one code path.
+type DispatchFn = fn(&mut WorldState, &serde_json::Value) -> ExecutionResult;
+
+static DISPATCH_TABLE: LazyLock<HashMap<&'static str, DispatchFn>> = LazyLock::new(|| {
+    let mut table: HashMap<&'static str, DispatchFn> = HashMap::new();
+    table.insert("get_flight_details", dispatch_get_flight_details as DispatchFn);
+    table.insert("G_F_D", dispatch_get_flight_details as DispatchFn);
+    table.insert("get_flight", dispatch_get_flight as DispatchFn);
+    table.insert("G_F", dispatch_get_flight as DispatchFn);
+    table.insert("get_user_details", dispatch_get_user_details as DispatchFn);
+    table.insert("G_U_D", dispatch_get_user_details as DispatchFn);
+    table.insert("get_reservation_details", dispatch_get_reservation_details as DispatchFn);
+    table.insert("G_R_D", dispatch_get_reservation_details as DispatchFn);
+    table.insert("list_user_reservations", dispatch_list_user_reservations as DispatchFn);
+    table.insert("L_U_R", dispatch_list_user_reservations as DispatchFn);
+    table.insert("find_transfer_flights", dispatch_find_transfer_flights as DispatchFn);
+    table.insert("F_T_F", dispatch_find_transfer_flights as DispatchFn);
+    table.insert("reserve_flight", dispatch_reserve_flight as DispatchFn);
+    table.insert("R_F", dispatch_reserve_flight as DispatchFn);
+    table.insert("reserve_round_trip", dispatch_reserve_round_trip as DispatchFn);
+    table.insert("R_R_T", dispatch_reserve_round_trip as DispatchFn);
+    table.insert("can_afford_flight", dispatch_can_afford_flight as DispatchFn);
+    table.insert("C_A_F", dispatch_can_afford_flight as DispatchFn);
+    table.insert("modify_flight", dispatch_modify_flight as DispatchFn);
+    table.insert("M_F", dispatch_modify_flight as DispatchFn);
+    table.insert("cancel_reservation", dispatch_cancel_reservation as DispatchFn);
+    table.insert("C_R", dispatch_cancel_reservation as DispatchFn);
+    table.insert("transfer_reservation", dispatch_transfer_reservation as DispatchFn);
+    table.insert("T_R", dispatch_transfer_reservation as DispatchFn);
+    table.insert("get_route_availability", dispatch_get_route_availability as DispatchFn);
+    table.insert("G_R_A", dispatch_get_route_availability as DispatchFn);
+    table.insert("get_cheapest_flight", dispatch_get_cheapest_flight as DispatchFn);
+    table.insert("G_C_F", dispatch_get_cheapest_flight as DispatchFn);
+    table.insert("get_reservation_summary", dispatch_get_reservation_summary as DispatchFn);
+    table.insert("G_R_S", dispatch_get_reservation_summary as DispatchFn);
+    table.insert("login_food_platform", dispatch_login_food_platform as DispatchFn);
+    table.insert("L_F_P", dispatch_login_food_platform as DispatchFn);
+    table.insert("logout_food_platform", dispatch_logout_food_platform as DispatchFn);
+    table.insert("L_O_F_P", dispatch_logout_food_platform as DispatchFn);
+    table.insert("view_logged_in_users", dispatch_view_logged_in_users as DispatchFn);
+    table.insert("V_L_I_U", dispatch_view_logged_in_users as DispatchFn);
+    table.insert("check_balance", dispatch_check_balance as DispatchFn);
+    table.insert("C_B", dispatch_check_balance as DispatchFn);
+    table.insert("recharge_balance", dispatch_recharge_balance as DispatchFn);
+    table.insert("R_B", dispatch_recharge_balance as DispatchFn);
+    table.insert("add_food_delivery_order", dispatch_add_food_delivery_order as DispatchFn);
+    table.insert("A_F_D_O", dispatch_add_food_delivery_order as DispatchFn);
+    table.insert("cancel_food_order", dispatch_cancel_food_order as DispatchFn);
+    table.insert("C_F_O", dispatch_cancel_food_order as DispatchFn);
+    table.insert("get_products", dispatch_get_products as DispatchFn);
+    table.insert("G_P", dispatch_get_products as DispatchFn);
+    table.insert("search_products", dispatch_search_products as DispatchFn);
+    table.insert("S_P", dispatch_search_products as DispatchFn);
+    table.insert("search_merchants", dispatch_search_merchants as DispatchFn);
+    table.insert("S_MER", dispatch_search_merchants as DispatchFn);
+    table.insert("view_all_merchants", dispatch_view_all_merchants as DispatchFn);
+    table.insert("V_A_M", dispatch_view_all_merchants as DispatchFn);
+    table.insert("view_orders", dispatch_view_orders as DispatchFn);
+    table.insert("V_O", dispatch_view_orders as DispatchFn);
+    table.insert("search_orders", dispatch_search_orders as DispatchFn);
+    table.insert("S_O", dispatch_search_orders as DispatchFn);
+    table.insert("delete_message", dispatch_delete_message as DispatchFn);
+    table.insert("D_M", dispatch_delete_message as DispatchFn);
+    table.insert("get_message_by_id", dispatch_get_message_by_id as DispatchFn);
+    table.insert("G_M_B_I", dispatch_get_message_by_id as DispatchFn);
+    table.insert("mark_message_read", dispatch_mark_message_read as DispatchFn);
+    table.insert("M_M_R", dispatch_mark_message_read as DispatchFn);
+    table.insert("view_unread_messages", dispatch_view_unread_messages as DispatchFn);
+    table.insert("V_U_M", dispatch_view_unread_messages as DispatchFn);
+    table.insert("reply_to_message", dispatch_reply_to_message as DispatchFn);
+    table.insert("R_T_M", dispatch_reply_to_message as DispatchFn);
+    table.insert("view_messages_between_users", dispatch_view_messages_between_users as DispatchFn);
+    table.insert("V_M_B_U", dispatch_view_messages_between_users as DispatchFn);
+    table.insert("search_messages", dispatch_search_messages as DispatchFn);
+    table.insert("S_M2", dispatch_search_messages as DispatchFn);
+    table.insert("get_all_message_times_with_ids", dispatch_get_all_message_times_with_ids as DispatchFn);
+    table.insert("G_A_M_T_W_I", dispatch_get_all_message_times_with_ids as DispatchFn);
+    table.insert("get_latest_message_id", dispatch_get_latest_message_id as DispatchFn);
+    table.insert("G_L_M_I", dispatch_get_latest_message_id as DispatchFn);
+    table.insert("get_earliest_message_id", dispatch_get_earliest_message_id as DispatchFn);
+    table.insert("G_E_M_I", dispatch_get_earliest_message_id as DispatchFn);
+    table.insert("get_inbox_utilization", dispatch_get_inbox_utilization as DispatchFn);
+    table.insert("G_I_U", dispatch_get_inbox_utilization as DispatchFn);
+    table.insert("get_inbox_status", dispatch_get_inbox_utilization as DispatchFn);
+    table.insert("G_I_S", dispatch_get_inbox_utilization as DispatchFn);
+    table.insert("view_reminder_by_title", dispatch_view_reminder_by_title as DispatchFn);
+    table.insert("V_R_B_T", dispatch_view_reminder_by_title as DispatchFn);
+    table.insert("add_reminder", dispatch_add_reminder as DispatchFn);
+    table.insert("A_R", dispatch_add_reminder as DispatchFn);
+    table.insert("delete_reminder", dispatch_delete_reminder as DispatchFn);
+    table.insert("D_R", dispatch_delete_reminder as DispatchFn);
+    table.insert("update_reminder", dispatch_update_reminder as DispatchFn);
+    table.insert("U_R", dispatch_update_reminder as DispatchFn);
+    table.insert("mark_reminder_notified", dispatch_mark_reminder_notified as DispatchFn);
+    table.insert("M_R_N", dispatch_mark_reminder_notified as DispatchFn);
+    table.insert("view_reminders_by_date", dispatch_view_reminders_by_date as DispatchFn);
+    table.insert("V_R_B_D", dispatch_view_reminders_by_date as DispatchFn);
+    table.insert("view_all_reminders", dispatch_view_all_reminders as DispatchFn);
+    table.insert("V_A_R", dispatch_view_all_reminders as DispatchFn);
+    table.insert("search_reminders", dispatch_search_reminders as DispatchFn);
+    table.insert("S_R", dispatch_search_reminders as DispatchFn);
+    table.insert("get_reminder_utilization", dispatch_get_reminder_utilization as DispatchFn);
+    table.insert("G_R_U", dispatch_get_reminder_utilization as DispatchFn);
+    table.insert("cancel_all_reservations", dispatch_cancel_all_reservations as DispatchFn);
+    table.insert("C_A_R", dispatch_cancel_all_reservations as DispatchFn);
+    table.insert("send_message", dispatch_send_message as DispatchFn);
+    table.insert("S_M", dispatch_send_message as DispatchFn);
+    table
+});fn dispatch_get_flight_details(state: &mut WorldState, parameters: &serde_json::Value) -> ExecutionResult {
+    let Some(travel) = &mut state.travel else {
+        return ExecutionResult::error(
+            "The Travel API is not available in this scenario".to_string(),
+        );
+    };
+    match serde_json::from_value::<travel::GetFlightDetailsArgs>(parameters.clone()) {
+        Ok(a) => travel.get_flight_details(a.origin, a.destination),
+        Err(e) => ExecutionResult::error(format!(
+            "Failed to parse parameters for get_flight_details: {}",
+            e
+        )),
+    }
+}
+
+fn dispatch_get_flight(state: &mut WorldState, parameters: &serde_json::Value) -> ExecutionResult {
+    let Some(travel) = &mut state.travel else {
+        return ExecutionResult::error(
+            "The Travel API is not available in this scenario".to_string(),
+        );
+    };
+    match serde_json::from_value::<travel::GetFlightArgs>(parameters.clone()) {
+        Ok(a) => travel.get_flight(a.flight_no, a.depart_time),
+        Err(e) => ExecutionResult::error(format!(
+            "Failed to parse parameters for get_flight: {}",
+            e
+        )),
+    }
+}
+
+fn dispatch_get_user_details(state: &mut WorldState, parameters: &serde_json::Value) -> ExecutionResult {
+    let Some(travel) = &mut state.travel else {
+        return ExecutionResult::error(
+            "The Travel API is not available in this scenario".to_string(),
+        );
+    };
+    match serde_json::from_value::<travel::GetUserDetailsArgs>(parameters.clone()) {
+        Ok(a) => travel.get_user_details(a.user_id, a.password),
+        Err(e) => ExecutionResult::error(format!(
+            "Failed to parse parameters for get_user_details: {}",
+            e
+        )),
+    }
+}
+
+fn dispatch_get_reservation_details(state: &mut WorldState, parameters: &serde_json::Value) -> ExecutionResult {
+    let Some(travel) = &mut state.travel else {
+        return ExecutionResult::error(
+            "The Travel API is not available in this scenario".to_string(),
+        );
+    };
+    match serde_json::from_value::<travel::GetReservationDetailsArgs>(parameters.clone()) {
+        Ok(a) => travel.get_reservation_details(a.reservation_id, a.user_id),
+        Err(e) => ExecutionResult::error(format!(
+            "Failed to parse parameters for get_reservation_details: {}",
+            e
+        )),
+    }
+}
+
+fn dispatch_list_user_reservations(state: &mut WorldState, parameters: &serde_json::Value) -> ExecutionResult {
+    let Some(travel) = &mut state.travel else {
+        return ExecutionResult::error(
+            "The Travel API is not available in this scenario".to_string(),
+        );
+    };
+    match serde_json::from_value::<travel::ListUserReservationsArgs>(parameters.clone()) {
+        Ok(a) => travel.list_user_reservations(a.user_id, a.password),
+        Err(e) => ExecutionResult::error(format!(
+            "Failed to parse parameters for list_user_reservations: {}",
+            e
+        )),
+    }
+}
+
+fn dispatch_find_transfer_flights(state: &mut WorldState, parameters: &serde_json::Value) -> ExecutionResult {
+    let Some(travel) = &mut state.travel else {
+        return ExecutionResult::error(
+            "The Travel API is not available in this scenario".to_string(),
+        );
+    };
+    match serde_json::from_value::<travel::FindTransferFlightsArgs>(parameters.clone()) {
+        Ok(a) => travel.find_transfer_flights(
+                                a.origin_city,
+                                a.transfer_city,
+                                a.destination_city,
+                            ),
+        Err(e) => ExecutionResult::error(format!(
+            "Failed to parse parameters for find_transfer_flights: {}",
+            e
+        )),
+    }
+}
+
+fn dispatch_reserve_flight(state: &mut WorldState, parameters: &serde_json::Value) -> ExecutionResult {
+    let Some(travel) = &mut state.travel else {
+        return ExecutionResult::error(
+            "The Travel API is not available in this scenario".to_string(),
+        );
+    };
+    match serde_json::from_value::<travel::ReserveFlightArgs>(parameters.clone()) {
+        Ok(a) => travel.reserve_flight(
+                                a.user_id,
+                                a.password,
+                                a.flight_no,
+                                a.cabin,
+                                a.payment_method,
+                                a.baggage_count,
+                            ),
+        Err(e) => ExecutionResult::error(format!(
+            "Failed to parse parameters for reserve_flight: {}",
+            e
+        )),
+    }
+}
+
+fn dispatch_reserve_round_trip(state: &mut WorldState, parameters: &serde_json::Value) -> ExecutionResult {
+    let Some(travel) = &mut state.travel else {
+        return ExecutionResult::error(
+            "The Travel API is not available in this scenario".to_string(),
+        );
+    };
+    match serde_json::from_value::<travel::ReserveRoundTripArgs>(parameters.clone()) {
+        Ok(a) => travel.reserve_round_trip(
+                                a.user_id,
+                                a.password,
+                                a.outbound_flight_no,
+                                a.return_flight_no,
+                                a.cabin,
+                                a.payment_method,
+                                a.baggage_count,
+                            ),
+        Err(e) => ExecutionResult::error(format!(
+            "Failed to parse parameters for reserve_round_trip: {}",
+            e
+        )),
+    }
+}
+
+fn dispatch_can_afford_flight(state: &mut WorldState, parameters: &serde_json::Value) -> ExecutionResult {
+    let Some(travel) = &mut state.travel else {
+        return ExecutionResult::error(
+            "The Travel API is not available in this scenario".to_string(),
+        );
+    };
+    match serde_json::from_value::<travel::CanAffordFlightArgs>(parameters.clone()) {
+        Ok(a) => travel.can_afford_flight(
+                                a.user_id,
+                                a.password,
+                                a.flight_no,
+                                a.cabin,
+                                a.baggage_count,
+                                a.payment_method,
+                            ),
+        Err(e) => ExecutionResult::error(format!(
+            "Failed to parse parameters for can_afford_flight: {}",
+            e
+        )),
+    }
+}
+
+fn dispatch_modify_flight(state: &mut WorldState, parameters: &serde_json::Value) -> ExecutionResult {
+    let Some(travel) = &mut state.travel else {
+        return ExecutionResult::error(
+            "The Travel API is not available in this scenario".to_string(),
+        );
+    };
+    match serde_json::from_value::<travel::ModifyFlightArgs>(parameters.clone()) {
+        Ok(a) => travel.modify_flight(
+                                a.user_id,
+                                a.reservation_id,
+                                a.new_flight_no,
+                                a.new_cabin,
+                                a.add_baggage,
+                                a.new_payment_method,
+                            ),
+        Err(e) => ExecutionResult::error(format!(
+            "Failed to parse parameters for modify_flight: {}",
+            e
+        )),
+    }
+}
+
+fn dispatch_cancel_reservation(state: &mut WorldState, parameters: &serde_json::Value) -> ExecutionResult {
+    let Some(travel) = &mut state.travel else {
+        return ExecutionResult::error(
+            "The Travel API is not available in this scenario".to_string(),
+        );
+    };
+    match serde_json::from_value::<travel::CancelReservationArgs>(parameters.clone()) {
+        Ok(a) => travel.cancel_reservation(
+                                a.user_id,
+                                a.reservation_id,
+                                a.reason,
+                                a.current_time,
+                            ),
+        Err(e) => ExecutionResult::error(format!(
+            "Failed to parse parameters for cancel_reservation: {}",
+            e
+        )),
+    }
+}
+
+fn dispatch_transfer_reservation(state: &mut WorldState, parameters: &serde_json::Value) -> ExecutionResult {
+    let Some(travel) = &mut state.travel else {
+        return ExecutionResult::error(
+            "The Travel API is not available in this scenario".to_string(),
+        );
+    };
+    match serde_json::from_value::<travel::TransferReservationArgs>(parameters.clone()) {
+        Ok(a) => travel.transfer_reservation(
+                                a.user_id,
+                                a.reservation_id,
+                                a.new_user_id,
+                            ),
+        Err(e) => ExecutionResult::error(format!(
+            "Failed to parse parameters for transfer_reservation: {}",
+            e
+        )),
+    }
+}
+
+fn dispatch_get_route_availability(state: &mut WorldState, parameters: &serde_json::Value) -> ExecutionResult {
+    let Some(travel) = &mut state.travel else {
+        return ExecutionResult::error(
+            "The Travel API is not available in this scenario".to_string(),
+        );
+    };
+    match serde_json::from_value::<travel::GetRouteAvailabilityArgs>(parameters.clone()) {
+        Ok(a) => travel.get_route_availability(a.origin, a.destination),
+        Err(e) => ExecutionResult::error(format!(
+            "Failed to parse parameters for get_route_availability: {}",
+            e
+        )),
+    }
+}
+
+fn dispatch_get_cheapest_flight(state: &mut WorldState, parameters: &serde_json::Value) -> ExecutionResult {
+    let Some(travel) = &mut state.travel else {
+        return ExecutionResult::error(
+            "The Travel API is not available in this scenario".to_string(),
+        );
+    };
+    match serde_json::from_value::<travel::GetCheapestFlightArgs>(parameters.clone()) {
+        Ok(a) => travel.get_cheapest_flight(a.origin, a.destination, a.cabin),
+        Err(e) => ExecutionResult::error(format!(
+            "Failed to parse parameters for get_cheapest_flight: {}",
+            e
+        )),
+    }
+}
+
+fn dispatch_get_reservation_summary(state: &mut WorldState, parameters: &serde_json::Value) -> ExecutionResult {
+    let Some(travel) = &mut state.travel else {
+        return ExecutionResult::error(
+            "The Travel API is not available in this scenario".to_string(),
+        );
+    };
+    match serde_json::from_value::<travel::GetReservationSummaryArgs>(parameters.clone()) {
+        Ok(a) => travel.get_reservation_summary(a.user_id, a.password),
+        Err(e) => ExecutionResult::error(format!(
+            "Failed to parse parameters for get_reservation_summary: {}",
+            e
+        )),
+    }
+}
+
+fn dispatch_login_food_platform(state: &mut WorldState, parameters: &serde_json::Value) -> ExecutionResult {
+    let Some(food_platform) = &mut state.food_platform else {
+        return ExecutionResult::error(
+            "The FoodPlatform API is not available in this scenario".to_string(),
+        );
+    };
+    match serde_json::from_value::<food_services::LoginFoodPlatformArgs>(parameters.clone()) {
+        Ok(a) => food_platform.login_food_platform(a.username, a.password),
+        Err(e) => ExecutionResult::error(format!(
+            "Failed to parse parameters for login_food_platform: {}",
+            e
+        )),
+    }
+}
+
+fn dispatch_logout_food_platform(state: &mut WorldState, parameters: &serde_json::Value) -> ExecutionResult {
+    let Some(food_platform) = &mut state.food_platform else {
+        return ExecutionResult::error(
+            "The FoodPlatform API is not available in this scenario".to_string(),
+        );
+    };
+    match serde_json::from_value::<food_services::LogoutFoodPlatformArgs>(parameters.clone()) {
+        Ok(a) => food_platform.logout_food_platform(a.username),
+        Err(e) => ExecutionResult::error(format!(
+            "Failed to parse parameters for logout_food_platform: {}",
+            e
+        )),
+    }
+}
+
+fn dispatch_view_logged_in_users(state: &mut WorldState, _parameters: &serde_json::Value) -> ExecutionResult {
+    let Some(food_platform) = &mut state.food_platform else {
+        return ExecutionResult::error(
+            "The FoodPlatform API is not available in this scenario".to_string(),
+        );
+    };
+    food_platform.view_logged_in_users()
+}
+
+fn dispatch_recharge_balance(state: &mut WorldState, parameters: &serde_json::Value) -> ExecutionResult {
+    let Some(food_platform) = &mut state.food_platform else {
+        return ExecutionResult::error(
+            "The FoodPlatform API is not available in this scenario".to_string(),
+        );
+    };
+    match serde_json::from_value::<food_services::RechargeBalanceArgs>(parameters.clone()) {
+        Ok(a) => food_platform.recharge_balance(a.username, a.amount),
+        Err(e) => ExecutionResult::error(format!(
+            "Failed to parse parameters for recharge_balance: {}",
+            e
+        )),
+    }
+}
+
+fn dispatch_check_balance(state: &mut WorldState, parameters: &serde_json::Value) -> ExecutionResult {
+    let Some(food_platform) = &mut state.food_platform else {
+        return ExecutionResult::error(
+            "The FoodPlatform API is not available in this scenario".to_string(),
+        );
+    };
+    match serde_json::from_value::<food_services::CheckBalanceArgs>(parameters.clone()) {
+        Ok(a) => food_platform.check_balance(a.user_name),
+        Err(e) => ExecutionResult::error(format!(
+            "Failed to parse parameters for check_balance: {}",
+            e
+        )),
+    }
+}
+
+fn dispatch_add_food_delivery_order(state: &mut WorldState, parameters: &serde_json::Value) -> ExecutionResult {
+    let Some(food_platform) = &mut state.food_platform else {
+        return ExecutionResult::error(
+            "The FoodPlatform API is not available in this scenario".to_string(),
+        );
+    };
+    match serde_json::from_value::<food_services::AddFoodDeliveryOrderArgs>(parameters.clone()) {
+        Ok(a) => food_platform.add_food_delivery_order(
+                                a.username,
+                                a.merchant_name,
+                                a.items,
+                            ),
+        Err(e) => ExecutionResult::error(format!(
+            "Failed to parse parameters for add_food_delivery_order: {}",
+            e
+        )),
+    }
+}
+
+fn dispatch_cancel_food_order(state: &mut WorldState, parameters: &serde_json::Value) -> ExecutionResult {
+    let Some(food_platform) = &mut state.food_platform else {
+        return ExecutionResult::error(
+            "The FoodPlatform API is not available in this scenario".to_string(),
+        );
+    };
+    match serde_json::from_value::<food_services::CancelFoodOrderArgs>(parameters.clone()) {
+        Ok(a) => food_platform.cancel_food_order(a.username, a.order_index),
+        Err(e) => ExecutionResult::error(format!(
+            "Failed to parse parameters for cancel_food_order: {}",
+            e
+        )),
+    }
+}
+
+fn dispatch_get_products(state: &mut WorldState, parameters: &serde_json::Value) -> ExecutionResult {
+    let Some(food_platform) = &mut state.food_platform else {
+        return ExecutionResult::error(
+            "The FoodPlatform API is not available in this scenario".to_string(),
+        );
+    };
+    match serde_json::from_value::<food_services::GetProductArgs>(parameters.clone()) {
+        Ok(a) => food_platform.get_products(a.merchant_name),
+        Err(e) => ExecutionResult::error(format!(
+            "Failed to parse parameters for get_products: {}",
+            e
+        )),
+    }
+}
+
+fn dispatch_view_all_merchants(state: &mut WorldState, _parameters: &serde_json::Value) -> ExecutionResult {
+    let Some(food_platform) = &mut state.food_platform else {
+        return ExecutionResult::error(
+            "The FoodPlatform API is not available in this scenario".to_string(),
+        );
+    };
+    food_platform.view_all_merchants()
+}
+
+fn dispatch_search_merchants(state: &mut WorldState, parameters: &serde_json::Value) -> ExecutionResult {
+    let Some(food_platform) = &mut state.food_platform else {
+        return ExecutionResult::error(
+            "The FoodPlatform API is not available in this scenario".to_string(),
+        );
+    };
+    match serde_json::from_value::<food_services::SearchMerchantsArgs>(parameters.clone()) {
+        Ok(a) => food_platform.search_merchants(a.keyword),
+        Err(e) => ExecutionResult::error(format!(
+            "Failed to parse parameters for search_merchants: {}",
+            e
+        )),
+    }
+}
+
+fn dispatch_search_products(state: &mut WorldState, parameters: &serde_json::Value) -> ExecutionResult {
+    let Some(food_platform) = &mut state.food_platform else {
+        return ExecutionResult::error(
+            "The FoodPlatform API is not available in this scenario".to_string(),
+        );
+    };
+    match serde_json::from_value::<food_services::SearchProductsArgs>(parameters.clone()) {
+        Ok(a) => food_platform.search_products(a.keyword),
+        Err(e) => ExecutionResult::error(format!(
+            "Failed to parse parameters for search_products: {}",
+            e
+        )),
+    }
+}
+
+fn dispatch_view_orders(state: &mut WorldState, parameters: &serde_json::Value) -> ExecutionResult {
+    let Some(food_platform) = &mut state.food_platform else {
+        return ExecutionResult::error(
+            "The FoodPlatform API is not available in this scenario".to_string(),
+        );
+    };
+    match serde_json::from_value::<food_services::ViewOrdersArgs>(parameters.clone()) {
+        Ok(a) => food_platform.view_orders(a.user_name),
+        Err(e) => ExecutionResult::error(format!(
+            "Failed to parse parameters for view_orders: {}",
+            e
+        )),
+    }
+}
+
+fn dispatch_search_orders(state: &mut WorldState, parameters: &serde_json::Value) -> ExecutionResult {
+    let Some(food_platform) = &mut state.food_platform else {
+        return ExecutionResult::error(
+            "The FoodPlatform API is not available in this scenario".to_string(),
+        );
+    };
+    match serde_json::from_value::<food_services::SearchOrdersArgs>(parameters.clone()) {
+        Ok(a) => food_platform.search_orders(a.keyword),
+        Err(e) => ExecutionResult::error(format!(
+            "Failed to parse parameters for search_orders: {}",
+            e
+        )),
+    }
+}
+
+fn dispatch_delete_message(state: &mut WorldState, parameters: &serde_json::Value) -> ExecutionResult {
+    let Some(message_api) = &mut state.message_api else {
+        return ExecutionResult::error(
+            "The MessageApi API is not available in this scenario".to_string(),
+        );
+    };
+    match serde_json::from_value::<message::DeleteMessageArgs>(parameters.clone()) {
+        Ok(a) => message_api.delete_message(a.message_id),
+        Err(e) => ExecutionResult::error(format!(
+            "Failed to parse parameters for delete_message: {}",
+            e
+        )),
+    }
+}
+
+fn dispatch_get_message_by_id(state: &mut WorldState, parameters: &serde_json::Value) -> ExecutionResult {
+    let Some(message_api) = &mut state.message_api else {
+        return ExecutionResult::error(
+            "The MessageApi API is not available in this scenario".to_string(),
+        );
+    };
+    match serde_json::from_value::<message::GetMessageByIdArgs>(parameters.clone()) {
+        Ok(a) => message_api.get_message_by_id(a.message_id),
+        Err(e) => ExecutionResult::error(format!(
+            "Failed to parse parameters for get_message_by_id: {}",
+            e
+        )),
+    }
+}
+
+fn dispatch_mark_message_read(state: &mut WorldState, parameters: &serde_json::Value) -> ExecutionResult {
+    let Some(message_api) = &mut state.message_api else {
+        return ExecutionResult::error(
+            "The MessageApi API is not available in this scenario".to_string(),
+        );
+    };
+    match serde_json::from_value::<message::MarkMessageReadArgs>(parameters.clone()) {
+        Ok(a) => message_api.mark_message_read(a.message_id),
+        Err(e) => ExecutionResult::error(format!(
+            "Failed to parse parameters for mark_message_read: {}",
+            e
+        )),
+    }
+}
+
+fn dispatch_reply_to_message(state: &mut WorldState, parameters: &serde_json::Value) -> ExecutionResult {
+    let Some(message_api) = &mut state.message_api else {
+        return ExecutionResult::error(
+            "The MessageApi API is not available in this scenario".to_string(),
+        );
+    };
+    match serde_json::from_value::<message::ReplyToMessageArgs>(parameters.clone()) {
+        Ok(a) => message_api.reply_to_message(a.message_id, a.reply_text),
+        Err(e) => ExecutionResult::error(format!(
+            "Failed to parse parameters for reply_to_message: {}",
+            e
+        )),
+    }
+}
+
+fn dispatch_view_unread_messages(state: &mut WorldState, parameters: &serde_json::Value) -> ExecutionResult {
+    let Some(message_api) = &mut state.message_api else {
+        return ExecutionResult::error(
+            "The MessageApi API is not available in this scenario".to_string(),
+        );
+    };
+    match serde_json::from_value::<message::ViewUnreadMessagesArgs>(parameters.clone()) {
+        Ok(a) => message_api.view_unread_messages(a.user_name),
+        Err(e) => ExecutionResult::error(format!(
+            "Failed to parse parameters for view_unread_messages: {}",
+            e
+        )),
+    }
+}
+
+fn dispatch_view_messages_between_users(state: &mut WorldState, parameters: &serde_json::Value) -> ExecutionResult {
+    let Some(message_api) = &mut state.message_api else {
+        return ExecutionResult::error(
+            "The MessageApi API is not available in this scenario".to_string(),
+        );
+    };
+    match serde_json::from_value::<message::ViewMessagesBetweenUsersArgs>(parameters.clone()) {
+        Ok(a) => message_api
+                                .view_messages_between_users(a.sender_name, a.receiver_name),
+        Err(e) => ExecutionResult::error(format!(
+            "Failed to parse parameters for view_messages_between_users: {}",
+            e
+        )),
+    }
+}
+
+fn dispatch_search_messages(state: &mut WorldState, parameters: &serde_json::Value) -> ExecutionResult {
+    let Some(message_api) = &mut state.message_api else {
+        return ExecutionResult::error(
+            "The MessageApi API is not available in this scenario".to_string(),
+        );
+    };
+    match serde_json::from_value::<message::SearchMessagesArgs>(parameters.clone()) {
+        Ok(a) => message_api.search_messages(a.user_name, a.keyword),
+        Err(e) => ExecutionResult::error(format!(
+            "Failed to parse parameters for search_messages: {}",
+            e
+        )),
+    }
+}
+
+fn dispatch_get_all_message_times_with_ids(state: &mut WorldState, _parameters: &serde_json::Value) -> ExecutionResult {
+    let Some(message_api) = &mut state.message_api else {
+        return ExecutionResult::error(
+            "The MessageApi API is not available in this scenario".to_string(),
+        );
+    };
+    message_api.get_all_message_times_with_ids()
+}
+
+fn dispatch_get_latest_message_id(state: &mut WorldState, _parameters: &serde_json::Value) -> ExecutionResult {
+    let Some(message_api) = &mut state.message_api else {
+        return ExecutionResult::error(
+            "The MessageApi API is not available in this scenario".to_string(),
+        );
+    };
+    message_api.get_latest_message_id()
+}
+
+fn dispatch_get_earliest_message_id(state: &mut WorldState, _parameters: &serde_json::Value) -> ExecutionResult {
+    let Some(message_api) = &mut state.message_api else {
+        return ExecutionResult::error(
+            "The MessageApi API is not available in this scenario".to_string(),
+        );
+    };
+    message_api.get_earliest_message_id()
+}
+
+fn dispatch_get_inbox_utilization(state: &mut WorldState, _parameters: &serde_json::Value) -> ExecutionResult {
+    let Some(message_api) = &mut state.message_api else {
+        return ExecutionResult::error(
+            "The MessageApi API is not available in this scenario".to_string(),
+        );
+    };
+    message_api.get_inbox_utilization()
+}
+
+fn dispatch_view_reminder_by_title(state: &mut WorldState, parameters: &serde_json::Value) -> ExecutionResult {
+    let Some(reminder_api) = &mut state.reminder_api else {
+        return ExecutionResult::error(
+            "The ReminderApi API is not available in this scenario".to_string(),
+        );
+    };
+    match serde_json::from_value::<reminder::ViewReminderByTitleArgs>(parameters.clone()) {
+        Ok(a) => reminder_api.view_reminder_by_title(a.title),
+        Err(e) => ExecutionResult::error(format!(
+            "Failed to parse parameters for view_reminder_by_title: {}",
+            e
+        )),
+    }
+}
+
+fn dispatch_add_reminder(state: &mut WorldState, parameters: &serde_json::Value) -> ExecutionResult {
+    let Some(reminder_api) = &mut state.reminder_api else {
+        return ExecutionResult::error(
+            "The ReminderApi API is not available in this scenario".to_string(),
+        );
+    };
+    match serde_json::from_value::<reminder::AddReminderArgs>(parameters.clone()) {
+        Ok(a) => reminder_api.add_reminder(a.title, a.description, a.time),
+        Err(e) => ExecutionResult::error(format!(
+            "Failed to parse parameters for add_reminder: {}",
+            e
+        )),
+    }
+}
+
+fn dispatch_delete_reminder(state: &mut WorldState, parameters: &serde_json::Value) -> ExecutionResult {
+    let Some(reminder_api) = &mut state.reminder_api else {
+        return ExecutionResult::error(
+            "The ReminderApi API is not available in this scenario".to_string(),
+        );
+    };
+    match serde_json::from_value::<reminder::DeleteReminderArgs>(parameters.clone()) {
+        Ok(a) => reminder_api.delete_reminder(a.reminder_id),
+        Err(e) => ExecutionResult::error(format!(
+            "Failed to parse parameters for delete_reminder: {}",
+            e
+        )),
+    }
+}
+
+fn dispatch_update_reminder(state: &mut WorldState, parameters: &serde_json::Value) -> ExecutionResult {
+    let Some(reminder_api) = &mut state.reminder_api else {
+        return ExecutionResult::error(
+            "The ReminderApi API is not available in this scenario".to_string(),
+        );
+    };
+    match serde_json::from_value::<reminder::UpdateReminderArgs>(parameters.clone()) {
+        Ok(a) => reminder_api.update_reminder(a.reminder_id, a.new_title, a.new_description, a.new_time),
+        Err(e) => ExecutionResult::error(format!(
+            "Failed to parse parameters for update_reminder: {}",
+            e
+        )),
+    }
+}
+
+fn dispatch_mark_reminder_notified(state: &mut WorldState, parameters: &serde_json::Value) -> ExecutionResult {
+    let Some(reminder_api) = &mut state.reminder_api else {
+        return ExecutionResult::error(
+            "The ReminderApi API is not available in this scenario".to_string(),
+        );
+    };
+    match serde_json::from_value::<reminder::MarkReminderNotifiedArgs>(parameters.clone()) {
+        Ok(a) => reminder_api.mark_reminder_notified(a.reminder_id),
+        Err(e) => ExecutionResult::error(format!(
+            "Failed to parse parameters for mark_reminder_notified: {}",
+            e
+        )),
+    }
+}
+
+fn dispatch_view_reminders_by_date(state: &mut WorldState, parameters: &serde_json::Value) -> ExecutionResult {
+    let Some(reminder_api) = &mut state.reminder_api else {
+        return ExecutionResult::error(
+            "The ReminderApi API is not available in this scenario".to_string(),
+        );
+    };
+    match serde_json::from_value::<reminder::ViewRemindersByDateArgs>(parameters.clone()) {
+        Ok(a) => reminder_api.view_reminders_by_date(a.date),
+        Err(e) => ExecutionResult::error(format!(
+            "Failed to parse parameters for view_reminders_by_date: {}",
+            e
+        )),
+    }
+}
+
+fn dispatch_view_all_reminders(state: &mut WorldState, _parameters: &serde_json::Value) -> ExecutionResult {
+    let Some(reminder_api) = &mut state.reminder_api else {
+        return ExecutionResult::error(
+            "The ReminderApi API is not available in this scenario".to_string(),
+        );
+    };
+    reminder_api.view_all_reminders()
+}
+
+fn dispatch_search_reminders(state: &mut WorldState, parameters: &serde_json::Value) -> ExecutionResult {
+    let Some(reminder_api) = &mut state.reminder_api else {
+        return ExecutionResult::error(
+            "The ReminderApi API is not available in this scenario".to_string(),
+        );
+    };
+    match serde_json::from_value::<reminder::SearchRemindersArgs>(parameters.clone()) {
+        Ok(a) => reminder_api.search_reminders(a.keyword),
+        Err(e) => ExecutionResult::error(format!(
+            "Failed to parse parameters for search_reminders: {}",
+            e
+        )),
+    }
+}
+
+fn dispatch_get_reminder_utilization(state: &mut WorldState, _parameters: &serde_json::Value) -> ExecutionResult {
+    let Some(reminder_api) = &mut state.reminder_api else {
+        return ExecutionResult::error(
+            "The ReminderApi API is not available in this scenario".to_string(),
+        );
+    };
+    reminder_api.get_reminder_utilization()
+}
+
+fn dispatch_cancel_all_reservations(state: &mut WorldState, parameters: &serde_json::Value) -> ExecutionResult {
+    let Some(travel) = &mut state.travel else {
+        return ExecutionResult::error(
+            "The Travel API is not available in this scenario".to_string(),
+        );
+    };
+    match serde_json::from_value::<travel::CancelAllReservationsArgs>(parameters.clone()) {
+        Ok(a) => travel.cancel_all_reservations(a.user_id, a.password, a.reason),
+        Err(e) => ExecutionResult::error(format!(
+            "Failed to parse parameters for cancel_all_reservations: {}",
+            e
+        )),
+    }
+}
+
+fn dispatch_send_message(state: &mut WorldState, parameters: &serde_json::Value) -> ExecutionResult {
+    let Some(message_api) = &mut state.message_api else {
+        return ExecutionResult::error(
+            "The MessageApi API is not available in this scenario".to_string(),
+        );
+    };
+    match serde_json::from_value::<message::SendMessageArgs>(parameters.clone()) {
+        Ok(a) => message_api.send_message(a.sender_name, a.receiver_name, a.message),
+        Err(e) => ExecutionResult::error(format!(
+            "Failed to parse parameters for send_message: {}",
+            e
+        )),
+    }
+}
+
+#[cfg(test)]
+mod call_cap_tests {
+    use super::*;
+
+    #[test]
+    fn turn_exceeding_cap_is_rejected_without_executing_any_call() {
+        let mut world_state = WorldState::default();
+        world_state.populate_with_involved_classes(&vec!["BaseApi".to_string()]);
+
+        let calls: Vec<FunctionCallHygienic> = (0..3)
+            .map(|_| FunctionCallHygienic {
+                name: "turn_on_wifi".to_string(),
+                parameters: IndexMap::new(),
+            })
+            .collect();
+
+        let results = world_state.execute_function_calls_with_limit(&calls, Some(2));
+
+        assert_eq!(results.len(), 1);
+        assert!(!results[0].is_success());
+        assert!(results[0].message.contains("exceeds the maximum of 2"));
+        assert!(!world_state.base_api.as_ref().unwrap().wifi, "the turn should have been rejected before any call executed");
+    }
+}
+
+#[cfg(test)]
+mod canonical_json_ordering_tests {
+    use super::*;
+    use crate::reminder::Reminder;
+
+    #[test]
+    fn insertion_order_differing_but_logically_equal_worlds_canonicalize_identically() {
+        let mut reminder_api_a = ReminderApi::default();
+        reminder_api_a.reminder_list.clear();
+        reminder_api_a.reminder_list.insert(
+            "1".to_string(),
+            Reminder {
+                reminder_id: 1,
+                title: "A".to_string(),
+                description: "first".to_string(),
+                time: "2024-07-15 09:30".to_string(),
+                notified: false,
+            },
+        );
+        reminder_api_a.reminder_list.insert(
+            "2".to_string(),
+            Reminder {
+                reminder_id: 2,
+                title: "B".to_string(),
+                description: "second".to_string(),
+                time: "2024-07-16 09:30".to_string(),
+                notified: false,
+            },
+        );
+
+        let mut reminder_api_b = ReminderApi::default();
+        reminder_api_b.reminder_list.clear();
+        // same two reminders, inserted in the opposite order
+        reminder_api_b.reminder_list.insert(
+            "2".to_string(),
+            Reminder {
+                reminder_id: 2,
+                title: "B".to_string(),
+                description: "second".to_string(),
+                time: "2024-07-16 09:30".to_string(),
+                notified: false,
+            },
+        );
+        reminder_api_b.reminder_list.insert(
+            "1".to_string(),
+            Reminder {
+                reminder_id: 1,
+                title: "A".to_string(),
+                description: "first".to_string(),
+                time: "2024-07-15 09:30".to_string(),
+                notified: false,
+            },
+        );
+
+        let mut world_a = WorldState::default();
+        world_a.reminder_api = Some(reminder_api_a);
+        let mut world_b = WorldState::default();
+        world_b.reminder_api = Some(reminder_api_b);
+
+        // raw serialized text differs because IndexMap preserves insertion order...
+        assert_ne!(
+            serde_json::to_string(&world_a).unwrap(),
+            serde_json::to_string(&world_b).unwrap()
+        );
+        // ...but the canonical form sorts keys, so the two logically-equal worlds match
+        assert_eq!(
+            serde_json::to_string(&world_a.to_canonical_json()).unwrap(),
+            serde_json::to_string(&world_b.to_canonical_json()).unwrap()
+        );
+    }
+}
+
+#[cfg(test)]
+mod wifi_and_login_propagation_tests {
+    use super::*;
+
+    #[test]
+    fn toggling_wifi_and_login_propagates_to_every_populated_sub_api() {
+        let mut world_state = WorldState::default();
+        world_state.populate_with_involved_classes(&vec![
+            "BaseApi".to_string(),
+            "MessageApi".to_string(),
+            "FoodPlatform".to_string(),
+        ]);
+
+        world_state.set_wifi(true);
+        world_state.set_logged_in(false);
+
+        assert!(world_state.base_api.as_ref().unwrap().wifi);
+        assert!(!world_state.base_api.as_ref().unwrap().logged_in);
+        assert!(world_state.message_api.as_ref().unwrap().base_api.wifi);
+        assert!(!world_state.message_api.as_ref().unwrap().base_api.logged_in);
+        assert!(world_state.food_platform.as_ref().unwrap().base_api.wifi);
+        assert!(!world_state.food_platform.as_ref().unwrap().base_api.logged_in);
+    }
+
+    #[test]
+    fn turn_on_wifi_function_call_flips_wifi_on_every_populated_sub_api() {
+        let mut world_state = WorldState::default();
+        world_state.populate_with_involved_classes(&vec![
+            "BaseApi".to_string(),
+            "ReminderApi".to_string(),
+        ]);
+
+        let calls = vec![FunctionCallHygienic {
+            name: "turn_on_wifi".to_string(),
+            parameters: IndexMap::new(),
+        }];
+        let results = world_state.execute_function_calls(&calls);
+
+        assert_eq!(results.len(), 1);
+        assert!(results[0].is_success());
+        assert!(world_state.base_api.as_ref().unwrap().wifi);
+        assert!(world_state.reminder_api.as_ref().unwrap().base_api.wifi);
+    }
+}
+
+#[cfg(test)]
+mod unpopulated_api_error_tests {
+    use super::*;
+
+    #[test]
+    fn calling_a_function_whose_api_was_never_populated_returns_a_structured_error() {
+        let mut world_state = WorldState::default();
+        // message_api is deliberately left unpopulated
+
+        let calls = vec![FunctionCallHygienic {
+            name: "send_message".to_string(),
+            parameters: IndexMap::new(),
+        }];
+        let results = world_state.execute_function_calls(&calls);
+
+        assert_eq!(
+            results.len(),
+            1,
+            "one call in, one result out, even when the API is missing"
+        );
+        assert!(!results[0].is_success());
+        assert!(results[0]
+            .message
+            .contains("The MessageApi API is not available in this scenario"));
+    }
+}
+
+#[cfg(test)]
+mod dispatch_table_reachability_tests {
+    use super::*;
+
+    #[test]
+    fn every_registered_name_is_reachable_through_execute_function_calls() {
+        let mut world_state = WorldState::default();
+
+        for name in DISPATCH_TABLE.keys() {
+            let calls = vec![FunctionCallHygienic {
+                name: name.to_string(),
+                parameters: IndexMap::new(),
+            }];
+            let results = world_state.execute_function_calls(&calls);
+            assert_eq!(results.len(), 1, "dispatching {} produced no result", name);
+            assert!(
+                !results[0].message.contains("is currently not available"),
+                "{} is registered in DISPATCH_TABLE but was not found by name when dispatched",
+                name
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod bait_function_suffix_config_tests {
+    use super::*;
+
+    #[test]
+    fn the_default_suffix_list_treats_a_trailing_underscore_1_call_as_bait() {
+        let mut world_state = WorldState::default();
+        let calls = vec![FunctionCallHygienic {
+            name: "get_products_1".to_string(),
+            parameters: IndexMap::new(),
+        }];
+
+        let results = world_state.execute_function_calls(&calls);
+
+        assert!(world_state.called_a_bait_function);
+        assert_eq!(results.len(), 1);
+        assert!(!results[0].is_success());
+    }
+
+    #[test]
+    fn a_narrowed_suffix_config_lets_a_legitimate_tool_ending_in_1_through_safely() {
+        let mut world_state = WorldState::default();
+        world_state.bait_function_suffixes = vec!["_Budget".to_string(), "_Fast".to_string()];
+        world_state.populate_with_involved_classes(&vec!["FoodPlatform".to_string()]);
+        let calls = vec![FunctionCallHygienic {
+            name: "get_products_1".to_string(),
+            parameters: IndexMap::new(),
+        }];
+
+        let results = world_state.execute_function_calls(&calls);
+
+        assert!(!world_state.called_a_bait_function);
+        assert_eq!(results.len(), 1);
+        assert!(
+            !results[0].message.contains("blows up"),
+            "get_products_1 should not be treated as bait once removed from the configured suffix list"
+        );
+    }
+}
+
+#[cfg(test)]
+mod diff_tests {
+    use super::*;
+
+    #[test]
+    fn diff_collects_every_mismatching_sub_api_instead_of_stopping_at_the_first() {
+        let mut world_state = WorldState::default();
+        world_state.populate_with_involved_classes(&vec![
+            "MessageApi".to_string(),
+            "ReminderApi".to_string(),
+        ]);
+        let ground_truth = world_state.clone();
+
+        world_state.message_api.as_mut().unwrap().delete_message(1);
+        world_state.reminder_api.as_mut().unwrap().delete_reminder(1);
+
+        assert!(world_state.equals_ground_truth(&ground_truth).is_err());
+
+        let discrepancies = world_state.diff(&ground_truth);
+
+        assert_eq!(discrepancies.len(), 2, "{:?}", discrepancies);
+        assert!(discrepancies.iter().any(|d| d.starts_with("MessageApi:")));
+        assert!(discrepancies.iter().any(|d| d.starts_with("ReminderApi:")));
+    }
+
+    #[test]
+    fn diff_is_empty_when_world_states_match() {
+        let mut world_state = WorldState::default();
+        world_state.populate_with_involved_classes(&vec!["MessageApi".to_string()]);
+        let ground_truth = world_state.clone();
+
+        assert_eq!(world_state.diff(&ground_truth), Vec::<String>::new());
+    }
+
+    #[test]
+    fn diff_collects_a_balance_mismatch_and_a_missing_reservation_within_the_same_travel_sub_api() {
+        let mut world_state = WorldState::default();
+        world_state.populate_with_involved_classes(&vec!["Travel".to_string()]);
+        let ground_truth = world_state.clone();
+
+        let travel = world_state.travel.as_mut().unwrap();
+        travel.users.get_mut("user1").unwrap().cash_balance = NotNan::new(0.0).unwrap();
+        travel.reservations.retain(|r| r.reservation_id != "res_1");
+
+        assert!(
+            world_state.equals_ground_truth(&ground_truth).is_err(),
+            "sanity check: the mutated state should fail the non-collecting check too"
+        );
+
+        let discrepancies = world_state.diff(&ground_truth);
+
+        assert_eq!(discrepancies.len(), 3, "{:?}", discrepancies);
+        assert!(discrepancies.iter().any(|d| d.starts_with("Travel: User user1 does not match")));
+        assert!(discrepancies
+            .iter()
+            .any(|d| d.starts_with("Travel: Reservation does not exist in output. Expected reservation ID: res_1")));
+    }
 }